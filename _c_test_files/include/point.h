@@ -0,0 +1,6 @@
+#pragma once
+
+struct point {
+    int x;
+    int y;
+};
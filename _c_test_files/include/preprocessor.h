@@ -0,0 +1,10 @@
+#ifndef PREPROCESSOR_H
+#define PREPROCESSOR_H
+
+#define GUARDED_VALUE 41
+
+int header_add(int a, int b) {
+    return a + b;
+}
+
+#endif
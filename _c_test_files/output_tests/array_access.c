@@ -1,3 +1,5 @@
+#include <stdio.h>
+
 int main() {
     int a[] = {1, 2, 3, 4, 5, 6};
     a[2] = 2002;
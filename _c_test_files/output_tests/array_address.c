@@ -0,0 +1,21 @@
+#include <stdio.h>
+
+int sum_row(int (*row)[3]) {
+    return (*row)[0] + (*row)[1] + (*row)[2];
+}
+
+// the address of an array is a pointer to the whole array, not to its first element
+int main() {
+    int a[3] = {1, 2, 3};
+    int (*p)[3] = &a;
+    printf("%lu %lu %lu\n", sizeof *&a, sizeof(*p), sizeof(&a));
+    printf("%d %d\n", (*p)[0], (*p)[2]);
+
+    (*p)[1] = 20;
+    printf("%d %d\n", a[1], sum_row(&a));
+
+    int grid[2][3] = {{1, 2, 3}, {4, 5, 6}};
+    int (*row)[3] = &grid[1];
+    printf("%d %lu\n", sum_row(row), sizeof *&grid);
+    return 0;
+}
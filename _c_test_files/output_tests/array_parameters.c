@@ -0,0 +1,54 @@
+#include <stdio.h>
+
+// parameters declared as arrays are pointers to their first element
+int sum(int values[], int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total += values[i];
+    }
+    return total;
+}
+
+int trace(int m[][3], int rows) {
+    int total = 0;
+    for (int i = 0; i < rows; i++) {
+        total += m[i][i];
+    }
+    return total;
+}
+
+void fill(int m[3][3]) {
+    for (int i = 0; i < 3; i++) {
+        for (int j = 0; j < 3; j++) {
+            m[i][j] = i * 3 + j;
+        }
+    }
+}
+
+unsigned long pointer_size(int values[10]) {
+    return sizeof(values);
+}
+
+int count_args(int argc, char *argv[]) {
+    int total = 0;
+    for (int i = 0; i < argc; i++) {
+        if (argv[i][0] == 'a') {
+            total++;
+        }
+    }
+    return total;
+}
+
+int main() {
+    int values[4] = {1, 2, 3, 4};
+    printf("%d\n", sum(values, 4));
+
+    int m[3][3];
+    fill(m);
+    printf("%d %d\n", trace(m, 3), m[2][1]);
+    printf("%lu\n", pointer_size(values));
+
+    char *args[3] = {"apple", "banana", "avocado"};
+    printf("%d\n", count_args(3, args));
+    return 0;
+}
@@ -0,0 +1,35 @@
+#include <stdio.h>
+
+int counter = 3;
+static int hidden = 7;
+
+int read_later(void);
+
+int bump(void) {
+    extern int counter;
+    counter = counter + 1;
+    return counter;
+}
+
+// a block scope 'extern' refers to the file scope variable, even where a local hides it
+int main() {
+    int counter = 100;
+    {
+        extern int counter;
+        printf("%d\n", counter);
+    }
+    printf("%d %d\n", bump(), counter);
+    {
+        extern int hidden;
+        printf("%d\n", hidden);
+    }
+    printf("%d\n", read_later());
+    return 0;
+}
+
+int read_later(void) {
+    extern int defined_later;
+    return defined_later;
+}
+
+int defined_later = 42;
@@ -0,0 +1,29 @@
+#include <stdio.h>
+#include <string.h>
+
+// an 'unsigned char' buffer is passed to the 'char *' library functions with a warning
+unsigned long length_after(char *text, char wanted) {
+    char *found = strchr(text, wanted);
+    return strlen(found);
+}
+
+int main() {
+    char buf[16];
+    strcpy(buf, "x");
+    printf("%s %lu\n", buf, strlen(buf));
+
+    char name[32];
+    strcpy(name, "hello");
+    strcat(name, " world");
+    char *copy = name;
+    printf("%s %lu %d\n", copy, strlen(copy), strcmp(copy, "hello world") == 0);
+    printf("%lu %s\n", length_after(name, 'w'), strchr(copy, 'l'));
+
+    unsigned char bytes[8];
+    strncpy(bytes, name, 5);
+    bytes[5] = 0;
+    char *text = strstr(name, "wor");
+    puts(bytes);
+    puts(text);
+    return 0;
+}
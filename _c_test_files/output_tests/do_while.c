@@ -0,0 +1,34 @@
+#include <stdio.h>
+
+int main() {
+    int i = 0;
+    do {
+        printf("%d ", i);
+        i++;
+    } while (i < 3);
+    printf("\n");
+
+    // the body runs once even when the condition starts false
+    do {
+        printf("once\n");
+    } while (0);
+
+    int n = 10;
+    int odd_sum = 0;
+    do {
+        n--;
+        if (n % 2 == 0) {
+            continue;
+        }
+        if (n < 4) {
+            break;
+        }
+        odd_sum = odd_sum + n;
+    } while (n);
+    printf("%d %d\n", odd_sum, n);
+
+    int countdown = 3;
+    do printf("%d...", countdown); while (countdown = countdown - 1);
+    printf("\n");
+    return 0;
+}
@@ -0,0 +1,51 @@
+#include <stdio.h>
+
+enum color { RED, GREEN = 5, BLUE };
+
+enum state {
+    IDLE,
+    RUNNING = IDLE + 10,
+    STOPPED = RUNNING * 2,
+    LAST = -5,
+    AFTER_LAST,
+};
+
+typedef enum { SMALL = 1 << 2, LARGE = SMALL << 4 } size_kind;
+
+enum state current = IDLE;
+
+enum state step(enum state state) {
+    switch (state) {
+        case IDLE:
+            return RUNNING;
+        case RUNNING:
+            return STOPPED;
+        default:
+            return IDLE;
+    }
+}
+
+int main() {
+    printf("%d %d %d\n", RED, GREEN, BLUE);
+    printf("%d %d %d %d\n", RUNNING, STOPPED, LAST, AFTER_LAST);
+
+    enum color color = BLUE;
+    color = color + 1;
+    printf("%d\n", color);
+
+    current = step(current);
+    current = step(current);
+    printf("%d\n", current);
+
+    size_kind size = LARGE;
+    printf("%d %lu\n", size, sizeof(enum color));
+
+    enum local { ONE = 1, TWO } local = TWO;
+    {
+        int RED = 7;
+        printf("%d %d\n", RED, local);
+    }
+    int values[3] = {RED, ONE, GREEN + BLUE};
+    printf("%d\n", values[2]);
+    return 0;
+}
@@ -0,0 +1,10 @@
+#include <stdio.h>
+
+int answer(int base) {
+    return base * 6;
+}
+
+int main() {
+    printf("exiting\n");
+    return answer(7);
+}
@@ -1,3 +1,5 @@
+#include <stdio.h>
+
 void fizz_buzz(int x) {
     printf("%d: ", x);
     int is_mod_three = !(x % 3);
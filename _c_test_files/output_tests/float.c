@@ -0,0 +1,38 @@
+#include <stdio.h>
+
+float half(float value) {
+    return value / 2;
+}
+
+double widen(float value) {
+    return value;
+}
+
+float global = 1.25f;
+
+int main() {
+    float f = 3.5f;
+    double d = f;
+    float back = (float)(d * 2.0);
+    printf("%f %f %f\n", f, d, back);
+
+    printf("%f %f\n", half(5), widen(0.1f));
+    printf("%lu %lu %lu\n", sizeof(float), sizeof(f), sizeof(1.0f));
+
+    float sum = 0;
+    int i;
+    for (int j = 0; j < 4; j++) {
+        sum = sum + global;
+    }
+    printf("%.2f\n", sum);
+
+    i = f;
+    printf("%d %d\n", i, f > 3);
+
+    float mixed = f * 2 + 1;
+    printf("%f %f\n", mixed, -mixed);
+
+    char c = 'A';
+    printf("%c %d\n", c, c);
+    return 0;
+}
@@ -0,0 +1,70 @@
+#include <stdio.h>
+
+typedef int (*binop)(int, int);
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int sub(int a, int b) {
+    return a - b;
+}
+
+int mul(int a, int b) {
+    return a * b;
+}
+
+struct command {
+    int id;
+    int (*run)(int, int);
+};
+
+int apply(int (*op)(int, int), int a, int b) {
+    return op(a, b);
+}
+
+binop pick(int which) {
+    if (which == 0) {
+        return add;
+    }
+    return &sub;
+}
+
+void say(int times) {
+    printf("said %d\n", times);
+}
+
+int main() {
+    int (*op)(int, int) = add;
+    printf("%d\n", op(2, 3));
+    op = mul;
+    printf("%d\n", (*op)(4, 5));
+    printf("%d\n", apply(sub, 10, 4));
+    printf("%d\n", pick(0)(1, 2));
+    printf("%d\n", pick(1)(1, 2));
+
+    int (*table[3])(int, int) = {add, sub, mul};
+    for (int i = 0; i < 3; i++) {
+        printf("%d\n", table[i](6, 3));
+    }
+
+    binop ops[2];
+    ops[0] = mul;
+    ops[1] = add;
+    printf("%d\n", ops[0](7, 6) + ops[1](1, 1));
+
+    struct command cmd;
+    cmd.id = 7;
+    cmd.run = mul;
+    struct command *cmd_ptr = &cmd;
+    printf("%d %d\n", cmd.id, cmd_ptr->run(3, 3));
+
+    void (*hello)(int) = say;
+    hello(2);
+
+    binop *indirect = &op;
+    printf("%d\n", (*indirect)(8, 8));
+    printf("%d\n", op == mul);
+    printf("%lu\n", sizeof(binop));
+    return 0;
+}
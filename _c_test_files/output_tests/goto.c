@@ -0,0 +1,67 @@
+#include <stdio.h>
+
+typedef int T;
+
+int find(int *values, int count, int target) {
+    int index = 0;
+    for (int i = 0; i < count; i++) {
+        for (int j = 0; j < count; j++) {
+            if (values[i] + values[j] == target) {
+                index = i * 10 + j;
+                goto found;
+            }
+        }
+    }
+    return -1;
+found:
+    return index;
+}
+
+int countdown(int n) {
+    int total = 0;
+again:
+    total += n;
+    n--;
+    if (n > 0)
+        goto again;
+    return total;
+}
+
+int cleanup(int fail) {
+    int status = 0;
+    if (fail) {
+        status = 1;
+        goto out;
+    }
+    printf("work done\n");
+out:
+    printf("cleanup with %d\n", status);
+    return status;
+}
+
+int main() {
+    int values[4] = {1, 5, 7, 9};
+    printf("%d\n", find(&values[0], 4, 14));
+    printf("%d\n", find(&values[0], 4, 100));
+    printf("%d\n", countdown(4));
+    cleanup(0);
+    cleanup(1);
+
+    int x = 0;
+T:
+    x++;
+    if (x < 3) {
+        goto T;
+    }
+    printf("%d\n", x);
+
+    switch (x) {
+    case 3:
+        goto skip;
+    default:
+        printf("not reached\n");
+    }
+skip:
+    printf("skipped\n");
+    return 0;
+}
@@ -0,0 +1,73 @@
+#include <stdio.h>
+
+// gotos into labels nested in the bodies of other statements, the branch that jumps
+// continues after the statement and never falls into its else branch
+
+int jump_into_if(int x) {
+    if (x) {
+        goto inside;
+        if (0) {
+        inside:
+            printf("inside if\n");
+        }
+        printf("after if\n");
+    } else {
+        printf("else\n");
+    }
+    return 0;
+}
+
+int jump_into_while(int x) {
+    int count = 0;
+    if (x) {
+        goto inside;
+        while (count < 2) {
+        inside:
+            count = count + 1;
+        }
+        printf("after while %d\n", count);
+    } else {
+        printf("else\n");
+    }
+    return count;
+}
+
+int jump_into_for(int x) {
+    int total = 0;
+    if (x) {
+        goto inside;
+        for (int i = 0; i < 3; i = i + 1) {
+            total = total + 10;
+        inside:
+            total = total + 1;
+        }
+        printf("after for %d\n", total);
+    } else {
+        printf("else\n");
+    }
+    return total;
+}
+
+int jump_into_switch(int x) {
+    if (x) {
+        goto inside;
+        switch (x) {
+            case 5:
+                printf("five\n");
+            inside:
+                printf("inside switch\n");
+        }
+        printf("after switch\n");
+    } else {
+        printf("else\n");
+    }
+    return 0;
+}
+
+int main() {
+    jump_into_if(1);
+    jump_into_while(1);
+    jump_into_for(1);
+    jump_into_switch(1);
+    return 0;
+}
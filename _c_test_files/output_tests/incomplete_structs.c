@@ -0,0 +1,90 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+struct file;
+typedef struct tree Tree;
+
+struct node {
+    int value;
+    struct node *next;
+};
+
+struct tree {
+    int value;
+    Tree *left;
+    Tree *right;
+};
+
+// an opaque handle, callers only see a pointer to the incomplete struct
+struct file *open_file(int id);
+int file_id(struct file *handle);
+
+struct file {
+    int id;
+};
+
+struct file *open_file(int id) {
+    struct file *handle = (struct file *)malloc(sizeof(struct file));
+    handle->id = id;
+    return handle;
+}
+
+int file_id(struct file *handle) {
+    return handle->id;
+}
+
+int sum(struct node *list) {
+    struct node *end = (struct node *)0;
+    int total = 0;
+    while (list != end) {
+        total += list->value;
+        list = list->next;
+    }
+    return total;
+}
+
+Tree *insert(Tree *root, int value) {
+    Tree *empty = (Tree *)0;
+    if (root == empty) {
+        root = (Tree *)malloc(sizeof(Tree));
+        root->value = value;
+        root->left = empty;
+        root->right = empty;
+    } else if (value < root->value) {
+        root->left = insert(root->left, value);
+    } else {
+        root->right = insert(root->right, value);
+    }
+    return root;
+}
+
+void print_tree(Tree *root) {
+    if (root == (Tree *)0) {
+        return;
+    }
+    print_tree(root->left);
+    printf("%d ", root->value);
+    print_tree(root->right);
+}
+
+int main() {
+    struct node third = { 3, (struct node *)0 };
+    struct node second = { 2, &third };
+    struct node first = { 1, &second };
+    printf("sum = %d\n", sum(&first));
+    printf("second = %d\n", first.next->value);
+    printf("third = %d\n", first.next->next->value);
+
+    Tree *root = (Tree *)0;
+    int values[7] = { 5, 3, 8, 1, 4, 7, 9 };
+    for (int i = 0; i < 7; i++) {
+        root = insert(root, values[i]);
+    }
+    print_tree(root);
+    printf("\n");
+
+    struct file *handle = open_file(42);
+    printf("file = %d\n", file_id(handle));
+    free(handle);
+    return 0;
+}
@@ -0,0 +1,26 @@
+#include <stdio.h>
+
+long twice(long value) {
+    return value * 2;
+}
+
+int main() {
+    // an int added to a long is computed as a long
+    int one = 1;
+    long big = 3000000000;
+    printf("%lu %ld\n", sizeof(one + big), (one + big) / 2);
+
+    // unsigned values are zero extended when they are widened
+    unsigned int max = 4294967295u;
+    long widened = max;
+    unsigned char byte = 200;
+    int from_byte = byte;
+    printf("%ld %d\n", widened, from_byte);
+
+    // changing the signedness to a type of another width also changes the width
+    unsigned int large = 4000000000u;
+    signed char negative = -1;
+    unsigned int all_ones = negative;
+    printf("%ld %u\n", twice(large), all_ones);
+    return 0;
+}
@@ -0,0 +1,41 @@
+#include <stdio.h>
+
+int main() {
+    // char and short operands are computed with as int, so these do not wrap
+    short a = 30000;
+    short b = 30000;
+    int short_sum = a + b;
+    printf("%d %d\n", short_sum, a * b / b);
+
+    char c = 100;
+    char d = 100;
+    int char_sum = c + d;
+    printf("%d %d\n", char_sum, c * d);
+
+    unsigned char high = 200;
+    unsigned char low = 100;
+    printf("%d %d\n", high + low, low - high);
+
+    // both sides are compared as int, with their sign
+    char minus_one = -1;
+    unsigned char max = 255;
+    short negative = -1;
+    unsigned short large = 40000;
+    printf("%d %d\n", minus_one == max, negative < large);
+
+    // compound assignments compute as int and store the narrow type
+    short wrap = 32767;
+    short one = 1;
+    wrap += one;
+    unsigned char byte = 250;
+    unsigned char ten = 10;
+    byte += ten;
+    printf("%d %d\n", wrap, byte);
+
+    int bits = 6;
+    int mask = 3;
+    int after = 7;
+    bits &= mask;
+    printf("%d %d\n", bits, after);
+    return 0;
+}
@@ -0,0 +1,48 @@
+#include <stdio.h>
+
+struct pair {
+    short a;
+    short b;
+    int c;
+};
+
+short half(short value) {
+    return value / 2;
+}
+
+int main() {
+    short s = 32767;
+    s = s + 1;
+    printf("%d\n", s);
+
+    unsigned short us = 65535;
+    us = us + 1;
+    printf("%d\n", us);
+
+    short int si = -5;
+    int short is = half(si);
+    printf("%d\n", is);
+
+    long long big = 9000000000;
+    long long int also_big = big * 2;
+    printf("%lld\n", also_big);
+
+    unsigned long long ull = 18446744073709551615ull;
+    printf("%llu\n", ull);
+
+    int long unsigned mixed = 4000000000u;
+    printf("%lu\n", mixed);
+
+    long unsigned int count = 3;
+    signed short neg = -1;
+    printf("%lu %d\n", count, neg);
+
+    printf("%lu %lu %lu\n", sizeof(short), sizeof(unsigned short int), sizeof(long long));
+
+    printf("%lu\n", sizeof(struct pair));
+
+    // 'u' gives an 'unsigned int' unless the value needs an 'unsigned long'
+    printf("%lu %lu %lu %lu\n", sizeof(1u), sizeof(4294967296u), sizeof(1ul), sizeof(1l));
+    printf("%u %u\n", 4294967295u + 1u, 3u - 4u);
+    return 0;
+}
@@ -0,0 +1,25 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <math.h>
+#include <ctype.h>
+
+int main() {
+    char *greeting = "hello";
+    char buffer[16];
+    strcpy(buffer, greeting);
+    strcat(buffer, " world");
+    printf("%s %lu\n", buffer, strlen(buffer));
+    printf("%d\n", strcmp(greeting, "hello") == 0);
+
+    for (int i = 0; buffer[i]; i++) {
+        buffer[i] = toupper(buffer[i]);
+    }
+    puts(buffer);
+
+    // math.h needs libm, which is linked automatically
+    double root = sqrt(2.0);
+    printf("%.3f %.1f %.1f\n", root, pow(2.0, 10.0), floor(-1.5));
+    printf("%d %d\n", abs(-7), atoi("42"));
+    return 0;
+}
@@ -0,0 +1,54 @@
+#include <stdio.h>
+
+int main() {
+    // continue and break apply to the innermost loop
+    int pairs = 0;
+    for (int i = 0; i < 4; i++) {
+        int j = 0;
+        while (j < 4) {
+            j++;
+            if (j == 2) {
+                continue;
+            }
+            if (j == 4) {
+                break;
+            }
+            pairs++;
+        }
+    }
+    printf("pairs %d\n", pairs);
+
+    // continue in a for loop runs the post loop expression,
+    // the step limits end the loops if it does not
+    int odd = 0;
+    int steps = 0;
+    for (int k = 0; k < 10; k++) {
+        steps++;
+        if (steps > 100) {
+            break;
+        }
+        if (k % 2 == 0) {
+            continue;
+        }
+        odd = odd + k;
+    }
+    printf("odd %d, steps %d\n", odd, steps);
+
+    // a switch inside a loop takes the break, the loop takes the continue
+    int total = 0;
+    steps = 0;
+    for (int n = 0; n < 5 && steps < 100; n++) {
+        steps++;
+        switch (n) {
+            case 1:
+                continue;
+            case 3:
+                break;
+            default:
+                total = total + n;
+        }
+        total = total + 10;
+    }
+    printf("total %d, steps %d\n", total, steps);
+    return 0;
+}
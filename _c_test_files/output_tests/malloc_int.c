@@ -1,3 +1,6 @@
+#include <stdio.h>
+#include <stdlib.h>
+
 int main() {
     int * i = (int *) malloc(sizeof(int));
     *i = 200;
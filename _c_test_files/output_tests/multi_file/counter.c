@@ -0,0 +1,21 @@
+#include "counter.h"
+
+int shared = 1;
+
+// initialized before main, even though main is in another file
+int offset = 10;
+
+static int calls;
+
+static int next() {
+    calls = calls + 1;
+    return calls;
+}
+
+int counter_next() {
+    return next() * shared + offset;
+}
+
+int counter_total() {
+    return calls;
+}
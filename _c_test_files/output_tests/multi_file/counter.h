@@ -0,0 +1,6 @@
+#pragma once
+
+extern int shared;
+
+int counter_next();
+int counter_total();
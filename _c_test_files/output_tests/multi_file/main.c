@@ -0,0 +1,23 @@
+#include <stdio.h>
+#include "counter.h"
+
+extern int scale(int value);
+
+// counter.c has its own 'calls' and 'next', static keeps them apart
+static int calls = 100;
+
+static int next() {
+    calls = calls + 1;
+    return calls;
+}
+
+int main() {
+    shared = 5;
+    printf("%d\n", counter_next());
+    printf("%d\n", counter_next());
+    printf("%d\n", counter_total());
+    printf("%d\n", next());
+    printf("%d\n", calls);
+    printf("%d\n", scale(7));
+    return 0;
+}
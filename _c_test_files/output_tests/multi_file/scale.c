@@ -0,0 +1,5 @@
+extern int offset;
+
+int scale(int value) {
+    return value * offset;
+}
@@ -0,0 +1,63 @@
+#include <stdio.h>
+
+typedef int row[3];
+
+int grid[2][3] = {{1, 2, 3}, {4, 5, 6}};
+
+int sum_rows(int (*rows)[3], int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        for (int j = 0; j < 3; j++) {
+            total += rows[i][j];
+        }
+    }
+    return total;
+}
+
+int sum_row(int *values, int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total += values[i];
+    }
+    return total;
+}
+
+void scale(row *rows, int count, int factor) {
+    for (int i = 0; i < count; i++) {
+        for (int j = 0; j < 3; j++) {
+            rows[i][j] = rows[i][j] * factor;
+        }
+    }
+}
+
+int main() {
+    int matrix[3][4];
+    for (int i = 0; i < 3; i++) {
+        for (int j = 0; j < 4; j++) {
+            matrix[i][j] = i * 4 + j;
+        }
+    }
+    printf("%d %d %d\n", matrix[0][0], matrix[1][2], matrix[2][3]);
+
+    int identity[3][3] = {{1}, {0, 1}, {0, 0, 1}};
+    for (int i = 0; i < 3; i++) {
+        printf("%d %d %d\n", identity[i][0], identity[i][1], identity[i][2]);
+    }
+
+    printf("%d\n", sum_rows(grid, 2));
+    printf("%d\n", sum_row(grid[1], 3));
+    scale(grid, 2, 10);
+    printf("%d %d\n", grid[0][0], grid[1][2]);
+
+    int cube[2][2][2] = {{{1, 2}, {3, 4}}, {{5, 6}, {7, 8}}};
+    printf("%d %d\n", cube[0][1][0], cube[1][1][1]);
+
+    int (*rows)[3] = grid;
+    printf("%d\n", rows[1][1]);
+
+    row pair[2] = {{7, 8, 9}, {10, 11, 12}};
+    printf("%d\n", pair[1][0]);
+
+    printf("%lu %lu %lu %lu\n", sizeof(matrix), sizeof(matrix[0]), sizeof(cube), sizeof(rows));
+    return 0;
+}
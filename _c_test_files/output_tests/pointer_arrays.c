@@ -0,0 +1,54 @@
+#include <stdio.h>
+
+int doubled(int value) {
+    return value * 2;
+}
+
+int squared(int value) {
+    return value * value;
+}
+
+int *globals[2];
+
+int sum(int **values, int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total = total + *values[i];
+    }
+    return total;
+}
+
+int main() {
+    char *names[3] = {"first", "second", "third"};
+    for (int i = 0; i < 3; i++) {
+        printf("%d: %s\n", i, names[i]);
+    }
+    printf("%lu\n", sizeof(names));
+
+    int a = 1;
+    int b = 20;
+    int c = 300;
+    int *numbers[3];
+    numbers[0] = &a;
+    numbers[1] = &b;
+    numbers[2] = &c;
+    *numbers[1] = 40;
+    printf("%d %d\n", b, sum(numbers, 3));
+
+    // the array decays to a pointer to its first element
+    int **cursor = numbers;
+    printf("%d\n", *cursor[2]);
+
+    int *grid[2][2] = {{&a, &b}, {&c, &a}};
+    printf("%d %d %lu\n", *grid[0][1], *grid[1][0], sizeof(grid));
+
+    int (*operations[2])(int) = {doubled, squared};
+    int (**operation)(int) = operations;
+    printf("%d %d\n", operations[1](7), (*operation)(7));
+
+    int (**tables[1])(int);
+    tables[0] = operations;
+    globals[1] = &c;
+    printf("%d %d %lu\n", (*tables[0])(3), *globals[1], sizeof(globals));
+    return 0;
+}
@@ -0,0 +1,52 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+struct pair {
+    int first;
+    int second;
+};
+
+void make_pair(struct pair ** out, int first, int second) {
+    struct pair * created = (struct pair *) malloc(sizeof(struct pair));
+    created->first = first;
+    created->second = second;
+    *out = created;
+}
+
+void divide(int a, int b, int * quotient, int * remainder) {
+    *quotient = a / b;
+    *remainder = a % b;
+}
+
+void swap(char ** a, char ** b) {
+    char * temp = *a;
+    *a = *b;
+    *b = temp;
+}
+
+int main() {
+    struct pair * numbers;
+    struct pair ** numbers_ptr = &numbers;
+    make_pair(numbers_ptr, 1, 2);
+    printf("%d %d\n", numbers->first, (*numbers_ptr)->second);
+    free(numbers);
+
+    int quotient;
+    int remainder;
+    divide(17, 5, &quotient, &remainder);
+    printf("%d %d\n", quotient, remainder);
+
+    char * first = "first";
+    char * second = "second";
+    swap(&first, &second);
+    printf("%s %s\n", first, second);
+
+    int x = 42;
+    int * p = &x;
+    int ** pp = &p;
+    int *** ppp = &pp;
+    ***ppp = 7;
+    printf("%d %d\n", x, **pp);
+
+    return 0;
+}
@@ -0,0 +1,60 @@
+#include <stdio.h>
+#include "preprocessor.h"
+#include "preprocessor.h"
+#include "point.h"
+#include "point.h"
+
+#define SQUARE(x) ((x) * (x))
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+#define STR(x) #x
+#define XSTR(x) STR(x)
+#define CONCAT(a, b) a##b
+#define SUM3(a, b, c) \
+    ((a) + \
+     (b) + (c))
+#define NOTHING()
+#define VERSION 3
+
+int CONCAT(my, var) = 7;
+
+#if VERSION >= 3 && defined(SQUARE)
+int generation = 3;
+#elif VERSION == 2
+int generation = 2;
+#else
+int generation = 1;
+#endif
+
+#ifdef UNDEFINED_THING
+#error this is skipped
+#endif
+
+#ifndef GUARDED_VALUE
+#error the header was not included
+#endif
+
+#undef VERSION
+#if defined VERSION || !defined(MAX)
+#error VERSION should be undefined
+#endif
+
+int main() {
+    struct point p;
+    p.x = SQUARE(3 + 1);
+    p.y = MAX(4, 9);
+    printf("%d %d\n", p.x, p.y);
+    printf("%s\n", STR(a + b));
+    printf("%s\n", XSTR(GUARDED_VALUE));
+    printf("%d\n", myvar);
+    printf("%d\n", generation);
+    printf("%d\n", SUM3(1, 2, 3));
+    printf("%d\n", header_add(GUARDED_VALUE, 1));
+    int SELF = 2;
+#define SELF SELF + 1
+    printf("%d\n", SELF);
+    NOTHING()
+#if 0
+    this isn't C, but it's skipped
+#endif
+    return 0;
+}
@@ -0,0 +1,39 @@
+#include <stdio.h>
+
+int is_even(int n);
+int is_odd(int n);
+int abs(int x);
+int putchar(int c);
+void print_parity(int n);
+
+int main(void) {
+    for (int i = 0; i < 4; i++) {
+        print_parity(i);
+    }
+    printf("%d\n", abs(0 - 7));
+    putchar('!');
+    putchar('\n');
+    return 0;
+}
+
+void print_parity(int n) {
+    if (is_even(n)) {
+        printf("%d even\n", n);
+    } else {
+        printf("%d odd\n", n);
+    }
+}
+
+int is_even(int n) {
+    if (n == 0) {
+        return 1;
+    }
+    return is_odd(n - 1);
+}
+
+int is_odd(int n) {
+    if (n == 0) {
+        return 0;
+    }
+    return is_even(n - 1);
+}
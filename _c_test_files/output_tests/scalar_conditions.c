@@ -0,0 +1,39 @@
+#include <stdio.h>
+
+// a condition of any scalar type is true when it is unequal to zero
+int main() {
+    int number = 4;
+    int *pointer = &number;
+    int *null = (int *) 0;
+    double half = 0.5;
+    double zero = 0.0;
+
+    printf("%d %d\n", pointer ? *pointer : -1, null ? *null : -1);
+    printf("%d %d\n", half ? 1 : 0, zero ? 1 : 0);
+
+    if (half) {
+        printf("half is true\n");
+    }
+    if (null) {
+        printf("null is true\n");
+    } else {
+        printf("null is false\n");
+    }
+
+    double fraction = 1.0;
+    int halvings = 0;
+    while (fraction) {
+        fraction = fraction - 0.5;
+        halvings++;
+    }
+    printf("%d\n", halvings);
+
+    int *cursor = pointer;
+    int steps = 0;
+    do {
+        steps++;
+        cursor = null;
+    } while (cursor);
+    printf("%d\n", steps);
+    return 0;
+}
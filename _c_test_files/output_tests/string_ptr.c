@@ -1,3 +1,6 @@
+#include <stdio.h>
+#include <stdlib.h>
+
 int	main()
 {
 	unsigned char *str = (unsigned char *)"hello world!";
@@ -0,0 +1,138 @@
+#include <stdio.h>
+
+struct small { int a; int b; };
+struct mixed { int i; double d; };
+struct floats { float x; float y; float z; };
+struct doubles { double x; double y; };
+struct big { long a; long b; long c; };
+struct bytes { char a; char b; char c; };
+struct nested { struct small s; float f; };
+struct pair { long first; long second; };
+struct record { unsigned char *name; int id; double score; };
+
+struct small native_make_small(int a, int b);
+struct mixed native_mixed(struct mixed m);
+struct floats native_scale_floats(struct floats f, float k);
+struct doubles native_swap_doubles(struct doubles d);
+struct big native_make_big(long a);
+long native_sum_big(struct big b);
+struct bytes native_next_bytes(struct bytes b);
+struct nested native_nested(struct nested n);
+long native_sum_pairs(struct pair a, struct pair b, struct pair c, struct pair d, struct pair e,
+                      int last);
+double native_sum_doubles(struct doubles a, struct doubles b, struct doubles c, struct doubles d,
+                          struct doubles e);
+struct record native_promote(struct record r, int bonus);
+void native_call_micro(void);
+
+struct small micro_make_small(int a, int b) {
+    struct small s = { a, b };
+    return s;
+}
+
+struct mixed micro_mixed(struct mixed m) {
+    m.i = m.i + 1;
+    m.d = m.d * 2.0;
+    return m;
+}
+
+struct floats micro_scale_floats(struct floats f, float k) {
+    f.x = f.x * k;
+    f.y = f.y * k;
+    f.z = f.z * k;
+    return f;
+}
+
+struct doubles micro_swap_doubles(struct doubles d) {
+    struct doubles swapped = { d.y, d.x };
+    return swapped;
+}
+
+struct big micro_make_big(long a) {
+    struct big b = { a, a * 2, a * 3 };
+    return b;
+}
+
+long micro_sum_big(struct big b) {
+    return b.a + b.b + b.c;
+}
+
+struct bytes micro_next_bytes(struct bytes b) {
+    b.a = b.a + 1;
+    b.b = b.b + 1;
+    b.c = b.c + 1;
+    return b;
+}
+
+struct nested micro_nested(struct nested n) {
+    n.s.a = n.s.a + 1;
+    n.s.b = n.s.b + 1;
+    n.f = n.f * 2.0;
+    return n;
+}
+
+long micro_sum_pairs(struct pair a, struct pair b, struct pair c, struct pair d, struct pair e,
+                     int last) {
+    return a.first + a.second + b.first + b.second + c.first + c.second + d.first + d.second
+        + e.first + e.second + last;
+}
+
+double micro_sum_doubles(struct doubles a, struct doubles b, struct doubles c, struct doubles d,
+                         struct doubles e) {
+    return a.x + a.y + b.x + b.y + c.x + c.y + d.x + d.y + e.x + e.y;
+}
+
+struct record micro_promote(struct record r, int bonus) {
+    r.id = r.id + bonus;
+    r.score = r.score + bonus;
+    return r;
+}
+
+int main() {
+    struct small s = native_make_small(3, 4);
+    printf("micro: small %d %d\n", s.a, s.b);
+
+    struct mixed m = { 20, 2.5 };
+    m = native_mixed(m);
+    printf("micro: mixed %d %.2f\n", m.i, m.d);
+
+    struct floats f = { 0.5, 1.5, 2.5 };
+    f = native_scale_floats(f, 4.0);
+    printf("micro: floats %.2f %.2f %.2f\n", f.x, f.y, f.z);
+
+    struct doubles d = { 3.5, 4.5 };
+    d = native_swap_doubles(d);
+    printf("micro: doubles %.2f %.2f\n", d.x, d.y);
+
+    struct big b = native_make_big(11);
+    printf("micro: big %ld %ld %ld, sum %ld\n", b.a, b.b, b.c, native_sum_big(b));
+
+    struct bytes letters = { 'x', 'y', 'z' };
+    letters = native_next_bytes(letters);
+    printf("micro: bytes %c %c %c\n", letters.a, letters.b, letters.c);
+
+    struct nested n = { { 8, 9 }, 2.5 };
+    n = native_nested(n);
+    printf("micro: nested %d %d %.2f\n", n.s.a, n.s.b, n.f);
+
+    struct pair p1 = { 10, 20 };
+    struct pair p2 = { 30, 40 };
+    struct pair p3 = { 50, 60 };
+    struct pair p4 = { 70, 80 };
+    struct pair p5 = { 90, 100 };
+    printf("micro: pairs %ld\n", native_sum_pairs(p1, p2, p3, p4, p5, 1000));
+
+    struct doubles d1 = { 0.25, 1.0 };
+    struct doubles d2 = { 1.25, 2.0 };
+    struct doubles d3 = { 2.25, 3.0 };
+    struct doubles d4 = { 3.25, 4.0 };
+    struct doubles d5 = { 4.25, 5.0 };
+    printf("micro: doubles sum %.2f\n", native_sum_doubles(d1, d2, d3, d4, d5));
+
+    struct record r = { "micro", 2, 1.5 };
+    r = native_promote(r, 20);
+    printf("micro: record %s %d %.2f\n", r.name, r.id, r.score);
+
+    native_call_micro();
+    return 0;
+}
@@ -0,0 +1,128 @@
+// Compiled with the system C compiler, so that every struct crosses between the two compilers.
+#include <stdio.h>
+
+struct small { int a; int b; };
+struct mixed { int i; double d; };
+struct floats { float x; float y; float z; };
+struct doubles { double x; double y; };
+struct big { long a; long b; long c; };
+struct bytes { char a; char b; char c; };
+struct nested { struct small s; float f; };
+struct pair { long first; long second; };
+struct record { unsigned char *name; int id; double score; };
+
+struct small micro_make_small(int a, int b);
+struct mixed micro_mixed(struct mixed m);
+struct floats micro_scale_floats(struct floats f, float k);
+struct doubles micro_swap_doubles(struct doubles d);
+struct big micro_make_big(long a);
+long micro_sum_big(struct big b);
+struct bytes micro_next_bytes(struct bytes b);
+struct nested micro_nested(struct nested n);
+long micro_sum_pairs(struct pair a, struct pair b, struct pair c, struct pair d, struct pair e,
+                     int last);
+double micro_sum_doubles(struct doubles a, struct doubles b, struct doubles c, struct doubles d,
+                         struct doubles e);
+struct record micro_promote(struct record r, int bonus);
+
+struct small native_make_small(int a, int b) {
+    struct small s = { a, b };
+    return s;
+}
+
+struct mixed native_mixed(struct mixed m) {
+    m.i += 1;
+    m.d *= 2;
+    return m;
+}
+
+struct floats native_scale_floats(struct floats f, float k) {
+    f.x *= k;
+    f.y *= k;
+    f.z *= k;
+    return f;
+}
+
+struct doubles native_swap_doubles(struct doubles d) {
+    struct doubles swapped = { d.y, d.x };
+    return swapped;
+}
+
+struct big native_make_big(long a) {
+    struct big b = { a, a * 2, a * 3 };
+    return b;
+}
+
+long native_sum_big(struct big b) {
+    return b.a + b.b + b.c;
+}
+
+struct bytes native_next_bytes(struct bytes b) {
+    b.a += 1;
+    b.b += 1;
+    b.c += 1;
+    return b;
+}
+
+struct nested native_nested(struct nested n) {
+    n.s.a += 1;
+    n.s.b += 1;
+    n.f *= 2;
+    return n;
+}
+
+long native_sum_pairs(struct pair a, struct pair b, struct pair c, struct pair d, struct pair e,
+                      int last) {
+    return a.first + a.second + b.first + b.second + c.first + c.second + d.first + d.second
+        + e.first + e.second + last;
+}
+
+double native_sum_doubles(struct doubles a, struct doubles b, struct doubles c, struct doubles d,
+                          struct doubles e) {
+    return a.x + a.y + b.x + b.y + c.x + c.y + d.x + d.y + e.x + e.y;
+}
+
+struct record native_promote(struct record r, int bonus) {
+    r.id = r.id + bonus;
+    r.score = r.score + bonus;
+    return r;
+}
+
+void native_call_micro(void) {
+    struct small s = micro_make_small(1, 2);
+    printf("native: small %d %d\n", s.a, s.b);
+
+    struct mixed m = { 10, 1.25 };
+    m = micro_mixed(m);
+    printf("native: mixed %d %.2f\n", m.i, m.d);
+
+    struct floats f = { 1.5f, 2.5f, 3.5f };
+    f = micro_scale_floats(f, 2.0f);
+    printf("native: floats %.2f %.2f %.2f\n", f.x, f.y, f.z);
+
+    struct doubles d = { 1.5, 2.5 };
+    d = micro_swap_doubles(d);
+    printf("native: doubles %.2f %.2f\n", d.x, d.y);
+
+    struct big b = micro_make_big(7);
+    printf("native: big %ld %ld %ld, sum %ld\n", b.a, b.b, b.c, micro_sum_big(b));
+
+    struct bytes letters = { 'a', 'b', 'c' };
+    letters = micro_next_bytes(letters);
+    printf("native: bytes %c %c %c\n", letters.a, letters.b, letters.c);
+
+    struct nested n = { { 4, 5 }, 1.5f };
+    n = micro_nested(n);
+    printf("native: nested %d %d %.2f\n", n.s.a, n.s.b, n.f);
+
+    struct pair p1 = { 1, 2 }, p2 = { 3, 4 }, p3 = { 5, 6 }, p4 = { 7, 8 }, p5 = { 9, 10 };
+    printf("native: pairs %ld\n", micro_sum_pairs(p1, p2, p3, p4, p5, 100));
+
+    struct doubles d1 = { 0.5, 1 }, d2 = { 1.5, 2 }, d3 = { 2.5, 3 }, d4 = { 3.5, 4 },
+                   d5 = { 4.5, 5 };
+    printf("native: doubles sum %.2f\n", micro_sum_doubles(d1, d2, d3, d4, d5));
+
+    struct record r = { (unsigned char *) "native", 1, 0.5 };
+    r = micro_promote(r, 10);
+    printf("native: record %s %d %.2f\n", r.name, r.id, r.score);
+}
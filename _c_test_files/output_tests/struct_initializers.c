@@ -0,0 +1,58 @@
+#include <stdio.h>
+
+struct point {
+    int x;
+    int y;
+};
+
+union value {
+    int i;
+    float f;
+};
+
+struct point origin = { .y = 4 };
+int globals[6] = { 1, [4] = 5, 6 };
+
+int main() {
+    struct point p = { 1, 2 };
+    struct point q = { .y = 20, .x = 10 };
+    struct point r = { 7 };
+    printf("p = (%d, %d)\n", p.x, p.y);
+    printf("q = (%d, %d)\n", q.x, q.y);
+    printf("r = (%d, %d)\n", r.x, r.y);
+    printf("origin = (%d, %d)\n", origin.x, origin.y);
+
+    int numbers[] = { [3] = 7, 8, [1] = 2 };
+    long size = sizeof(numbers);
+    printf("%ld:", size);
+    for (int i = 0; i < 5; i++) {
+        printf(" %d", numbers[i]);
+    }
+    printf("\n");
+    for (int i = 0; i < 6; i++) {
+        printf(" %d", globals[i]);
+    }
+    printf("\n");
+
+    struct point points[3] = { { 1, 2 }, [2] = { .y = 6 } };
+    for (int i = 0; i < 3; i++) {
+        printf("(%d, %d) ", points[i].x, points[i].y);
+    }
+    printf("\n");
+
+    union value v = { .f = 1.5f };
+    union value w = { 3 };
+    printf("%f %d\n", v.f, w.i);
+
+    struct point copy;
+    copy = q;
+    q.x = 0;
+    printf("copy = (%d, %d), q = (%d, %d)\n", copy.x, copy.y, q.x, q.y);
+    struct point other = copy;
+    printf("other = (%d, %d)\n", other.x, other.y);
+    points[1] = copy;
+    printf("points[1] = (%d, %d)\n", points[1].x, points[1].y);
+    int scalar = { 3 };
+    printf("%d\n", scalar);
+    return 0;
+}
@@ -0,0 +1,62 @@
+#include <stdio.h>
+
+struct char_int {
+    char c;
+    int i;
+};
+
+struct int_char {
+    int i;
+    char c;
+};
+
+struct chars {
+    char a;
+    char b;
+    char c;
+};
+
+struct short_long {
+    short s;
+    long l;
+};
+
+struct nested {
+    char c;
+    struct char_int inner;
+};
+
+struct with_pointer {
+    char c;
+    int *p;
+};
+
+struct mixed_floats {
+    float f;
+    double d;
+    char c;
+};
+
+int main() {
+    printf("%lu %lu %lu %lu\n", sizeof(struct char_int), sizeof(struct int_char),
+           sizeof(struct chars), sizeof(struct short_long));
+    printf("%lu %lu %lu\n", sizeof(struct nested), sizeof(struct with_pointer),
+           sizeof(struct mixed_floats));
+
+    // the sizes have to match where the generated code puts the members
+    struct char_int pairs[2];
+    printf("%ld %ld\n", (long) &pairs[1] - (long) &pairs[0], (long) &pairs[0].i - (long) &pairs[0]);
+
+    struct nested outer;
+    printf("%ld %ld\n", (long) &outer.inner - (long) &outer,
+           (long) &outer.inner.i - (long) &outer);
+
+    struct mixed_floats floats[2];
+    printf("%ld %ld %ld\n", (long) &floats[0].d - (long) &floats[0],
+           (long) &floats[0].c - (long) &floats[0], (long) &floats[1] - (long) &floats[0]);
+
+    struct with_pointer pointers[2];
+    printf("%ld %ld\n", (long) &pointers[0].p - (long) &pointers[0],
+           (long) &pointers[1] - (long) &pointers[0]);
+    return 0;
+}
@@ -1,10 +1,13 @@
+#include <stdio.h>
+#include <stdlib.h>
+
 struct test {
     int x;
-    unsigned char * str;
+    char * str;
     double f;
 };
 
-unsigned char * hello = "hello world!";
+char * hello = "hello world!";
 
 void print_struct_test(struct test * ptr) {
     printf("struct test {\n\tx = %d;\n\tstr = \"%s\";\n\tf = %f;\n};\n", ptr->x, ptr->str, ptr->f);
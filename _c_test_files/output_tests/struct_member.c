@@ -1,6 +1,8 @@
+#include <stdio.h>
+
 struct car {
- unsigned char * brand_name;
- unsigned char * model_name;
+ char * brand_name;
+ char * model_name;
  int year;
  double price;
 };
@@ -0,0 +1,73 @@
+#include <stdio.h>
+
+int days_in_month(int month) {
+    switch (month) {
+        case 2:
+            return 28;
+        case 4:
+        case 6:
+        case 9:
+        case 11:
+            return 30;
+        default:
+            return 31;
+    }
+}
+
+char *grade(unsigned char letter) {
+    switch (letter) {
+        case 'a':
+            return "excellent";
+        case 'b':
+            return "good";
+        case 'c':
+            return "fine";
+    }
+    return "unknown";
+}
+
+int count_down(int from) {
+    int total = 0;
+    switch (from) {
+        case 3:
+            total = total + 3;
+        case 2:
+            total = total + 2;
+        case 1:
+            total = total + 1;
+            break;
+        case 0:
+            total = 0 - 1;
+    }
+    return total;
+}
+
+int main() {
+    for (int month = 1; month <= 12; month++) {
+        printf("%d ", days_in_month(month));
+    }
+    printf("\n");
+
+    printf("%s %s %s\n", grade('a'), grade('c'), grade('z'));
+    printf("%d %d %d\n", count_down(3), count_down(1), count_down(0));
+
+    for (int i = 0; i < 6; i++) {
+        switch (i % 3) {
+            case 1 + 1:
+                continue;
+            case 0:
+                for (int j = 0; j < 10; j++) {
+                    if (j == 2) {
+                        break;
+                    }
+                    printf("j%d ", j);
+                }
+                break;
+            default:
+                printf("d%d ", i);
+        }
+        printf("i%d ", i);
+    }
+    printf("\n");
+    return 0;
+}
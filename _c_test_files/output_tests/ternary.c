@@ -0,0 +1,38 @@
+#include <stdio.h>
+
+int calls = 0;
+
+int count(int value) {
+    calls++;
+    return value;
+}
+
+int sign(int x) {
+    return x < 0 ? 0 - 1 : x > 0 ? 1 : 0;
+}
+
+int main() {
+    int x = 5;
+    long big = 5000000000;
+    printf("%d %d %d\n", sign(0 - 9), sign(0), sign(x));
+
+    // only the selected arm is evaluated
+    int picked = x > 3 ? count(10) : count(20);
+    printf("%d %d\n", picked, calls);
+
+    // the arms are converted to a common type
+    long wide = x ? x : big;
+    printf("%ld %ld\n", wide, 0 ? x : big);
+
+    char *word = x % 2 ? "odd" : "even";
+    printf("%s\n", word);
+
+    int max = x > 2 ? x : 2;
+    max = (x > 7 ? x : 7) + 1;
+    printf("%d\n", max);
+
+    if (2) {
+        printf("non-zero is true\n");
+    }
+    return 0;
+}
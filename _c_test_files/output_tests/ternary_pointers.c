@@ -0,0 +1,36 @@
+#include <stdio.h>
+
+int main() {
+    int numbers[3] = {1, 2, 3};
+    int other = 40;
+    int *first = numbers;
+    void *anything = &other;
+
+    // both arms of the same pointer type
+    int *picked = 1 ? &other : first;
+    printf("same: %d\n", *picked);
+
+    // an array arm is a pointer to its first element, on either side
+    picked = 0 ? &other : numbers;
+    printf("array otherwise: %d\n", *picked);
+    picked = 1 ? numbers : &other;
+    printf("array then: %d\n", *picked);
+
+    // a null pointer constant takes the type of the other arm, on either side
+    int *none = 0 ? first : 0;
+    printf("null otherwise: %ld\n", (long) none);
+    none = 1 ? 0 : first;
+    printf("null then: %ld\n", (long) none);
+    none = 1 ? (void *) 0 : first;
+    printf("void null: %ld\n", (long) none);
+
+    // 'void *' wins over the other pointer type, on either side
+    int *back = (int *) (1 ? anything : first);
+    printf("void then: %d\n", *back);
+    back = (int *) (1 ? first : anything);
+    printf("void otherwise: %d\n", *back);
+
+    char *answer = other > 10 ? "big" : "small";
+    printf("%s\n", answer);
+    return 0;
+}
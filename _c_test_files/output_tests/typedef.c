@@ -0,0 +1,47 @@
+#include <stdio.h>
+
+typedef unsigned long size_t;
+typedef struct node Node;
+typedef int vec3[3];
+typedef char *string;
+
+struct node {
+    int value;
+    int *next;
+};
+
+typedef Node *NodePtr;
+typedef int *IntPtr;
+
+size_t length(NodePtr list) {
+    size_t count = 0;
+    IntPtr value = list->next;
+    count += *value;
+    return count;
+}
+
+int main() {
+    Node a;
+    Node b;
+    a.value = 1;
+    a.next = &b.value;
+    b.value = 2;
+    b.next = a.next;
+    printf("%lu\n", length(&a));
+
+    typedef int T;
+    T x = 3;
+    T *p = &x;
+    T y = 2;
+    x = x * y;
+    {
+        int T = 5;
+        printf("%d %d\n", T * y, *p);
+    }
+    printf("%lu %lu %d\n", sizeof(T), sizeof(string), (T)(size_t)7);
+    vec3 v = {1, 2, 3};
+    printf("%d\n", v[0] + v[1] + v[2]);
+    string s = "hi";
+    printf("%s\n", s);
+    return 0;
+}
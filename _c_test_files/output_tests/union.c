@@ -0,0 +1,66 @@
+#include <stdio.h>
+
+union number {
+    char c;
+    int i;
+    long l;
+    double d;
+};
+
+struct padded {
+    char tag;
+    int value;
+};
+
+union mixed {
+    char first;
+    struct padded padded;
+};
+
+enum kind { INTEGER, REAL };
+
+struct tagged {
+    enum kind kind;
+    union number value;
+};
+
+double as_double(struct tagged *value) {
+    if (value->kind == INTEGER) {
+        return value->value.l;
+    }
+    return value->value.d;
+}
+
+union number make(long value) {
+    union number result;
+    result.l = value;
+    return result;
+}
+
+int main() {
+    union number n;
+    n.l = 0;
+    n.i = 258;
+    printf("%d %d %ld\n", n.c, n.i, n.l);
+
+    printf("%lu %lu %lu\n", sizeof(union number), sizeof(struct padded), sizeof(union mixed));
+
+    struct tagged a;
+    a.kind = INTEGER;
+    a.value.l = 42;
+    struct tagged b;
+    b.kind = REAL;
+    b.value.d = 1.5;
+    printf("%f %f\n", as_double(&a), as_double(&b));
+
+    union number *pointer = &n;
+    pointer->d = 2.25;
+    printf("%f\n", n.d);
+
+    union number copy = n;
+    copy.i = 7;
+    printf("%f %d\n", n.d, copy.i);
+
+    printf("%ld\n", make(99).l);
+    return 0;
+}
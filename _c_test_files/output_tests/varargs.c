@@ -0,0 +1,76 @@
+#include <stdio.h>
+#include <stdarg.h>
+
+int sum(int count, ...) {
+    va_list args;
+    va_start(args, count);
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total += va_arg(args, int);
+    }
+    va_end(args);
+    return total;
+}
+
+double average(int count, ...) {
+    va_list args;
+    va_start(args, count);
+    double total = 0.0;
+    for (int i = 0; i < count; i++) {
+        total += va_arg(args, double);
+    }
+    va_end(args);
+    return total / count;
+}
+
+void log_message(char *level, char *format, ...) {
+    va_list args;
+    va_start(args, format);
+    printf("[%s] ", level);
+    vprintf(format, args);
+    va_end(args);
+}
+
+long sum_twice(int count, va_list args) {
+    va_list copy;
+    va_copy(copy, args);
+    long total = 0;
+    for (int i = 0; i < count; i++) {
+        total += va_arg(args, long);
+    }
+    for (int i = 0; i < count; i++) {
+        total += va_arg(copy, long);
+    }
+    va_end(copy);
+    return total;
+}
+
+long sum_longs(int count, ...) {
+    va_list args;
+    va_start(args, count);
+    long total = sum_twice(count, args);
+    va_end(args);
+    return total;
+}
+
+char *nth_string(int n, ...) {
+    va_list args;
+    va_start(args, n);
+    char *result = "none";
+    for (int i = 0; i <= n; i++) {
+        result = va_arg(args, char *);
+    }
+    va_end(args);
+    return result;
+}
+
+int main() {
+    printf("%d\n", sum(3, 1, 2, 3));
+    printf("%d\n", sum(10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10));
+    printf("%f\n", average(4, 1.0, 2.0, 3.0, 4.5));
+    printf("%f\n", average(10, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0));
+    log_message("info", "%d apples and %s\n", 5, "pears");
+    printf("%ld\n", sum_longs(3, 10l, 20l, 30l));
+    printf("%s\n", nth_string(2, "zero", "one", "two"));
+    return 0;
+}
@@ -0,0 +1,29 @@
+#include <stdio.h>
+
+// compiles with warnings, code after a return is never run
+int first_positive(int a, int b) {
+    if (a > 0) {
+        if (b > 0) return 2;
+    } else {
+        return 0;
+    }
+    return 1;
+    printf("unreachable\n");
+}
+
+int main() {
+    int assigned;
+    int through_pointer;
+    int *ptr = &through_pointer;
+    *ptr = 3;
+    assigned = 2;
+    printf("%d %d\n", assigned, through_pointer);
+    printf("%d %d %d\n", first_positive(1, 1), first_positive(1, -1), first_positive(-1, 1));
+    while (assigned) {
+        assigned = assigned - 1;
+        break;
+    }
+    printf("%d\n", assigned);
+    return 0;
+    printf("unreachable\n");
+}
@@ -0,0 +1,6 @@
+// the address of a pointer with 255 levels would need a 256th
+int main() {
+    int ***************************************************************************************************************************************************************************************************************************************************************deepest;
+    void *address = &deepest;
+    return 0;
+}
@@ -0,0 +1,6 @@
+int counter = 3;
+
+int main() {
+    extern long counter;
+    return counter;
+}
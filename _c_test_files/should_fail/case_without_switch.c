@@ -0,0 +1,6 @@
+int main() {
+    int x = 3;
+    case 3:
+        x = 4;
+    return x;
+}
@@ -0,0 +1,7 @@
+// only a single pointer to 'char' converts to the other signedness
+int main() {
+    char *text = "text";
+    char **words = &text;
+    unsigned char **bytes = words;
+    return 0;
+}
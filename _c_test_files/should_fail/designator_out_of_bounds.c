@@ -0,0 +1,4 @@
+int main() {
+    int values[4] = { 1, 2, [4] = 5 };
+    return values[0];
+}
@@ -0,0 +1,8 @@
+enum limits {
+    BIG = 2147483647,
+    TOO_BIG,
+};
+
+int main() {
+    return TOO_BIG;
+}
@@ -0,0 +1,8 @@
+int add(int a, int b) {
+    return a + b;
+}
+
+int main() {
+    int (*op)(int) = add;
+    return 0;
+}
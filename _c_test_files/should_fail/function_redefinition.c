@@ -0,0 +1,11 @@
+int one() {
+    return 1;
+}
+
+int one() {
+    return 1;
+}
+
+int main() {
+    return one();
+}
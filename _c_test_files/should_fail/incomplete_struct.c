@@ -0,0 +1,7 @@
+struct file;
+
+int main() {
+    struct file *handle = (struct file *)0;
+    struct file copy = *handle;
+    return 0;
+}
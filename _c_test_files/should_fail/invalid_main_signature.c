@@ -0,0 +1,3 @@
+int main(long argc) {
+    return 0;
+}
@@ -0,0 +1,10 @@
+int next_id() {
+    static int id = 0;
+    id = id + 1;
+    return id;
+}
+
+int main() {
+    next_id();
+    return next_id();
+}
@@ -0,0 +1,5 @@
+#include "does_not_exist.h"
+
+int main() {
+    return 0;
+}
@@ -0,0 +1,4 @@
+int main() {
+    printf("stdio.h is not included\n");
+    return 0;
+}
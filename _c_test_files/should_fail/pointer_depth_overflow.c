@@ -0,0 +1,5 @@
+// a declarator can have at most 255 '*'
+int main() {
+    int ****************************************************************************************************************************************************************************************************************************************************************too_deep = 0;
+    return 0;
+}
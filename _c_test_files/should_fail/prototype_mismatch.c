@@ -0,0 +1,9 @@
+int add(int a, int b);
+
+long add(int a, int b) {
+    return a + b;
+}
+
+int main() {
+    return add(1, 2);
+}
@@ -0,0 +1,10 @@
+int helper();
+
+// a function declared without 'static' has external linkage, it cannot become static later
+static int helper() {
+    return 1;
+}
+
+int main() {
+    return helper();
+}
@@ -0,0 +1,7 @@
+void count(static int times) {
+}
+
+int main() {
+    count(1);
+    return 0;
+}
@@ -0,0 +1,8 @@
+int main() {
+    int number = 5;
+    long wide = 6;
+    int *narrow_pointer = &number;
+    long *wide_pointer = &wide;
+    int *picked = number ? narrow_pointer : wide_pointer;
+    return *picked;
+}
@@ -0,0 +1,4 @@
+int main() {
+    int grid[2][3] = {{1, 2, 3}, {4, 5, 6, 7}};
+    return grid[0][0];
+}
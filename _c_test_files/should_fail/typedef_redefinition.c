@@ -0,0 +1,7 @@
+typedef int number;
+typedef long number;
+
+int main() {
+    number x = 0;
+    return x;
+}
@@ -0,0 +1,4 @@
+int main() {
+    goto missing;
+    return 0;
+}
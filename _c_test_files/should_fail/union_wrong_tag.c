@@ -0,0 +1,10 @@
+union value {
+    int i;
+    double d;
+};
+
+int main() {
+    struct value v;
+    v.i = 1;
+    return v.i;
+}
@@ -0,0 +1,6 @@
+#ifdef DEBUG
+int debug = 1;
+
+int main() {
+    return 0;
+}
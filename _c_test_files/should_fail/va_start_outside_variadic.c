@@ -0,0 +1,13 @@
+#include <stdarg.h>
+
+int first(int count) {
+    va_list args;
+    va_start(args, count);
+    int value = va_arg(args, int);
+    va_end(args);
+    return value;
+}
+
+int main() {
+    return first(1);
+}
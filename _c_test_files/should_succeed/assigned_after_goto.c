@@ -0,0 +1,10 @@
+// 'value' is read above its assignment, but the assignment always runs first
+int main() {
+    int value;
+    goto assign;
+read:
+    return value - 2;
+assign:
+    value = 2;
+    goto read;
+}
@@ -1,3 +1,5 @@
+#include <stdio.h>
+
 /*
     This test is used to debug and test basic blocks for codegen
 */
@@ -0,0 +1,14 @@
+// a 'char *' and an 'unsigned char *' convert to each other, which is allowed but reported
+int first(char *text) {
+    return text[0];
+}
+
+int main() {
+    char text[8];
+    unsigned char *bytes = text;
+    char *back = bytes;
+    unsigned char raw[8];
+    raw[0] = 0;
+    back[0] = 0;
+    return first(raw);
+}
@@ -0,0 +1,12 @@
+// enumerators may share a value, which is allowed but reported
+enum Signal {
+    SIGNAL_OFF,
+    SIGNAL_ON,
+    SIGNAL_FALSE = 0,
+    SIGNAL_TRUE,
+};
+
+int main() {
+    enum Signal signal = SIGNAL_TRUE;
+    return signal == SIGNAL_ON ? 0 : 1;
+}
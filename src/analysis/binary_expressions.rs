@@ -66,7 +66,7 @@ impl Analyzer {
             }
         } else if left.ty.is_numeric() && right.ty.is_numeric() {
             let (left, right) = self.binary_numeric_cast(left, right);
-            let ty = left.ty.clone();
+            ty = left.ty.clone();
             match op {
                 BinaryOp::Add => MlirExprKind::Add(left, right),
                 BinaryOp::Sub => MlirExprKind::Sub(left, right),
@@ -103,8 +103,11 @@ impl Analyzer {
             self.report_error(err);
             return Ok(left);
         }
+        if left.ty.is_va_list() {
+            self.report_error(CompilerError::VaListAssignment(span));
+            return Ok(left);
+        }
         let right_ty = right.ty.clone();
-        let right = self.implicit_cast(right, left.ty.clone(), span);
         let op = match op {
             AssignOp::Assign => None,
             AssignOp::Plus => Some(BinaryOp::Add),
@@ -119,12 +122,14 @@ impl Analyzer {
             AssignOp::RightShift => Some(BinaryOp::RightShift),
         };
         let ty = left.ty.clone();
-        let kind = if let Some(op) = op {
-            let expr = self.validate_binary_expression(&op, left.clone(), right, span)?;
-            MlirExprKind::Assign(left, expr)
+        // compound assignments operate on the promoted operands and store in the left type
+        let right = if let Some(op) = op {
+            self.validate_binary_expression(&op, left.clone(), right, span)?
         } else {
-            MlirExprKind::Assign(left, right)
+            right
         };
+        let right = self.implicit_cast(right, ty.clone(), span);
+        let kind = MlirExprKind::Assign(left, right);
         Ok(MlirExpr {
             span,
             kind: Box::new(kind),
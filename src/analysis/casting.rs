@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 
 use crate::analysis::Analyzer;
-use crate::data::error::CompilerError;
+use crate::data::error::{CompilerError, CompilerWarning};
 use crate::data::mlir::*;
 use crate::util::Span;
 
@@ -36,20 +36,31 @@ impl Analyzer {
             (
                 // any* <-> any*
                 MlirType {
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
                     ..
                 },
                 MlirType {
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
                     ..
                 },
             ) => CastType::PointerToPointer,
 
+            (
+                // function pointers <-> any*
+                _,
+                _,
+            ) if (expr.ty.is_function_pointer() || cast_to.is_function_pointer())
+                && (expr.ty.is_function_pointer() || expr.ty.is_pointer())
+                && (cast_to.is_function_pointer() || cast_to.is_pointer()) =>
+            {
+                CastType::PointerToPointer
+            }
+
             (
                 // int -> *ptr
                 _,
                 MlirType {
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
                     ..
                 },
             ) if expr.ty.is_integer() => CastType::IntToPointer,
@@ -57,7 +68,7 @@ impl Analyzer {
             (
                 // any* -> int
                 MlirType {
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
                     ..
                 },
                 _,
@@ -70,7 +81,7 @@ impl Analyzer {
                     ..
                 },
                 MlirType {
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
                     ..
                 },
             ) => CastType::ArrayToPointer,
@@ -119,31 +130,63 @@ impl Analyzer {
                 },
                 MlirType {
                     kind: MlirTypeKind::Void,
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(1),
                 },
             ) => CastType::ArrayToPointer,
 
             (
                 //  array<T> -> T*
                 MlirType {
-                    kind: left,
                     decl: MlirTypeDecl::Array(_),
+                    ..
                 },
                 MlirType {
-                    kind: right,
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
+                    ..
+                },
+            ) if expr.ty.deref_type().pointer_to(1).as_ref() == Some(&cast_to) => {
+                CastType::ArrayToPointer
+            }
+
+            (
+                //  char[] -> unsigned char*, and the other way around
+                MlirType {
+                    decl: MlirTypeDecl::Array(_),
+                    ..
+                },
+                MlirType {
+                    decl: MlirTypeDecl::Pointer(1),
+                    ..
+                },
+            ) if differs_in_char_signedness(&expr.ty.deref_type(), &cast_to.deref_type()) => {
+                self.report_pointer_sign(&expr.ty, &cast_to, span);
+                CastType::ArrayToPointer
+            }
+
+            (
+                // char* -> unsigned char*, and the other way around
+                MlirType {
+                    decl: MlirTypeDecl::Pointer(1),
+                    ..
+                },
+                MlirType {
+                    decl: MlirTypeDecl::Pointer(1),
+                    ..
                 },
-            ) if left == right => CastType::ArrayToPointer,
+            ) if differs_in_char_signedness(&expr.ty.deref_type(), &cast_to.deref_type()) => {
+                self.report_pointer_sign(&expr.ty, &cast_to, span);
+                CastType::PointerToPointer
+            }
 
             (
                 // any* -> void*
                 MlirType {
                     kind,
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(_),
                 },
                 MlirType {
                     kind: MlirTypeKind::Void,
-                    decl: MlirTypeDecl::Pointer,
+                    decl: MlirTypeDecl::Pointer(1),
                 },
             ) => CastType::PointerToPointer,
 
@@ -167,6 +210,50 @@ impl Analyzer {
         }
     }
 
+    fn report_pointer_sign(&mut self, from: &MlirType, to: &MlirType, span: Span) {
+        self.report_warning(CompilerWarning::PointerSign(
+            from.to_string(),
+            to.to_string(),
+            span,
+        ));
+    }
+
+    /// Arrays used as values are pointers to their first element.
+    pub(super) fn decay_array(&mut self, expr: MlirExpr) -> Result<MlirExpr, ()> {
+        if !expr.is_array() {
+            return Ok(expr);
+        }
+        let span = expr.span;
+        let pointer = self.pointer_to(expr.ty.deref_type(), 1, span)?;
+        Ok(self.implicit_cast(expr, pointer, span))
+    }
+
+    /// Arguments without a parameter type, such as the variadic arguments of 'printf',
+    /// are promoted to 'int' or 'double' like C does.
+    pub(super) fn default_argument_promotion(
+        &mut self,
+        expr: MlirExpr,
+        span: Span,
+    ) -> Result<MlirExpr, ()> {
+        if expr.ty.is_array() {
+            // arrays are passed as a pointer to their first element
+            return self.decay_array(expr);
+        }
+        if !expr.ty.is_basic() {
+            return Ok(expr);
+        }
+        Ok(match expr.ty.kind {
+            MlirTypeKind::Char(_) | MlirTypeKind::Short(_) => {
+                self.implicit_cast(expr, SIGNED_INT_TYPE, span)
+            }
+            MlirTypeKind::Float => {
+                let double = MlirType::new(MlirTypeKind::Double, MlirTypeDecl::Basic);
+                self.implicit_cast(expr, double, span)
+            }
+            _ => expr,
+        })
+    }
+
     fn numeric_cast(&mut self, expr: &MlirExpr, cast_to: &MlirType, span: Span) -> CastType {
         debug_assert!(expr.ty.is_numeric());
         debug_assert!(cast_to.is_numeric());
@@ -175,7 +262,8 @@ impl Analyzer {
                 if expr.ty.is_integer()
                     && cast_to.is_integer()
                     && expr.ty.get_is_unsigned()
-                    && !cast_to.get_is_unsigned() =>
+                    && !cast_to.get_is_unsigned()
+                    && is_same_width(&expr.ty.kind, &cast_to.kind) =>
             {
                 CastType::UnsignedToSigned
             }
@@ -184,7 +272,8 @@ impl Analyzer {
                 if expr.ty.is_integer()
                     && cast_to.is_integer()
                     && !expr.ty.get_is_unsigned()
-                    && cast_to.get_is_unsigned() =>
+                    && cast_to.get_is_unsigned()
+                    && is_same_width(&expr.ty.kind, &cast_to.kind) =>
             {
                 CastType::SignedToUnsigned
             }
@@ -254,10 +343,26 @@ impl Analyzer {
     }
 }
 
+/// Whether both types are a single 'char' that only differs in signedness, so that a
+/// 'char *' can be used as an 'unsigned char *' and the other way around.
+fn differs_in_char_signedness(left: &MlirType, right: &MlirType) -> bool {
+    left.is_basic()
+        && right.is_basic()
+        && matches!(
+            (&left.kind, &right.kind),
+            (MlirTypeKind::Char(left_unsigned), MlirTypeKind::Char(right_unsigned))
+                if left_unsigned != right_unsigned
+        )
+}
+
+/// The type two arithmetic operands are converted to, 'char' and 'short' operands are
+/// promoted to 'int' first like C does.
 pub(in crate::analysis) fn get_implicit_cast_together_type(
     left: &MlirTypeKind,
     right: &MlirTypeKind,
 ) -> MlirTypeKind {
+    let left = &left.integer_promotion();
+    let right = &right.integer_promotion();
     match (left, right) {
         (left, right) if left == right => left.clone(),
         (MlirTypeKind::Long(unsigned_left), MlirTypeKind::Long(unsigned_right))
@@ -270,11 +375,6 @@ pub(in crate::analysis) fn get_implicit_cast_together_type(
         {
             MlirTypeKind::Int(true)
         }
-        (MlirTypeKind::Char(unsigned_left), MlirTypeKind::Char(unsigned_right))
-            if unsigned_left != unsigned_right =>
-        {
-            MlirTypeKind::Char(true)
-        }
         _ => {
             let promotion_ordering = left.get_promotion_value().cmp(&right.get_promotion_value());
             match promotion_ordering {
@@ -286,13 +386,27 @@ pub(in crate::analysis) fn get_implicit_cast_together_type(
     }
 }
 
+/// Sign changes are only free when both integer kinds share a width.
+fn is_same_width(left: &MlirTypeKind, right: &MlirTypeKind) -> bool {
+    std::mem::discriminant(left) == std::mem::discriminant(right)
+}
+
 impl MlirTypeKind {
+    /// 'int' holds every value of 'char' and 'short', signed or unsigned.
+    fn integer_promotion(&self) -> MlirTypeKind {
+        match self {
+            MlirTypeKind::Char(_) | MlirTypeKind::Short(_) => MlirTypeKind::Int(false),
+            kind => kind.clone(),
+        }
+    }
+
     fn get_promotion_value(&self) -> u8 {
         match &self {
-            MlirTypeKind::Double => 5,
-            MlirTypeKind::Float => 4,
-            MlirTypeKind::Long(_) => 3,
-            MlirTypeKind::Int(_) => 2,
+            MlirTypeKind::Double => 6,
+            MlirTypeKind::Float => 5,
+            MlirTypeKind::Long(_) => 4,
+            MlirTypeKind::Int(_) => 3,
+            MlirTypeKind::Short(_) => 2,
             MlirTypeKind::Char(_) => 1,
             non_promotable => panic!("'{:?}' is not a promotable type!", non_promotable),
         }
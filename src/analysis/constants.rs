@@ -0,0 +1,80 @@
+use crate::data::mlir::{MlirExpr, MlirExprKind, MlirLiteral, MlirType, MlirTypeKind};
+
+/// Folds an integer constant expression, such as the value of a case label.
+/// Returns `None` if the expression depends on anything that is not known at compile time.
+pub(super) fn evaluate_integer_constant(expr: &MlirExpr) -> Option<i64> {
+    use MlirExprKind::*;
+
+    macro_rules! binary {
+        ($left:expr, $right:expr, |$l:ident, $r:ident| $body:expr) => {{
+            let $l = evaluate_integer_constant($left)?;
+            let $r = evaluate_integer_constant($right)?;
+            $body
+        }};
+    }
+
+    if !expr.ty.is_basic() || !expr.ty.is_integer() {
+        return None;
+    }
+
+    let value = match &*expr.kind {
+        Literal(literal) => match literal {
+            MlirLiteral::Char(value) => *value as i64,
+            MlirLiteral::UChar(value) => *value as i64,
+            MlirLiteral::Int(value) => *value as i64,
+            MlirLiteral::UInt(value) => *value as i64,
+            MlirLiteral::Long(value) => *value,
+            MlirLiteral::ULong(value) => *value as i64,
+            _ => return None,
+        },
+
+        Negate(inner) => evaluate_integer_constant(inner)?.wrapping_neg(),
+        BitwiseNot(inner) => !evaluate_integer_constant(inner)?,
+        LogicalNot(inner) => (evaluate_integer_constant(inner)? == 0) as i64,
+        Cast(_, _, inner) => evaluate_integer_constant(inner)?,
+        Ternary(condition, then, otherwise) => {
+            if evaluate_integer_constant(condition)? != 0 {
+                evaluate_integer_constant(then)?
+            } else {
+                evaluate_integer_constant(otherwise)?
+            }
+        }
+
+        Add(left, right) => binary!(left, right, |l, r| l.wrapping_add(r)),
+        Sub(left, right) => binary!(left, right, |l, r| l.wrapping_sub(r)),
+        Mul(left, right) => binary!(left, right, |l, r| l.wrapping_mul(r)),
+        Div(left, right) => binary!(left, right, |l, r| l.checked_div(r)?),
+        Mod(left, right) => binary!(left, right, |l, r| l.checked_rem(r)?),
+        BitwiseAnd(left, right) => binary!(left, right, |l, r| l & r),
+        BitwiseOr(left, right) => binary!(left, right, |l, r| l | r),
+        BitwiseXor(left, right) => binary!(left, right, |l, r| l ^ r),
+        LeftShift(left, right) => binary!(left, right, |l, r| l.wrapping_shl(r as u32)),
+        RightShift(left, right) => binary!(left, right, |l, r| l.wrapping_shr(r as u32)),
+        Equal(left, right) => binary!(left, right, |l, r| (l == r) as i64),
+        NotEqual(left, right) => binary!(left, right, |l, r| (l != r) as i64),
+        GreaterThan(left, right) => binary!(left, right, |l, r| (l > r) as i64),
+        GreaterThanEqual(left, right) => binary!(left, right, |l, r| (l >= r) as i64),
+        LessThan(left, right) => binary!(left, right, |l, r| (l < r) as i64),
+        LessThanEqual(left, right) => binary!(left, right, |l, r| (l <= r) as i64),
+        LogicalAnd(left, right) => binary!(left, right, |l, r| (l != 0 && r != 0) as i64),
+        LogicalOr(left, right) => binary!(left, right, |l, r| (l != 0 || r != 0) as i64),
+
+        _ => return None,
+    };
+
+    Some(wrap_to_type(value, &expr.ty))
+}
+
+/// Truncates a constant to the width of an integer type, extending it back to 64 bits
+/// according to the signedness of the type.
+pub(super) fn wrap_to_type(value: i64, ty: &MlirType) -> i64 {
+    match ty.kind {
+        MlirTypeKind::Char(true) => value as u8 as i64,
+        MlirTypeKind::Char(false) => value as i8 as i64,
+        MlirTypeKind::Short(true) => value as u16 as i64,
+        MlirTypeKind::Short(false) => value as i16 as i64,
+        MlirTypeKind::Int(true) => value as u32 as i64,
+        MlirTypeKind::Int(false) => value as i32 as i64,
+        _ => value,
+    }
+}
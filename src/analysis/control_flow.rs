@@ -15,6 +15,7 @@ use crate::data::mlir::{
 };
 use crate::display_internal_graphs;
 use crate::util::str_intern::InternedStr;
+use crate::util::Span;
 
 /*
 Some resources on control flow analysis:
@@ -178,7 +179,10 @@ impl<'a> BasicBlockFactory<'a> {
     pub fn build(mut self) -> Vec<Rc<RefCell<BasicBlock<'a>>>> {
         for stmt in self.mlir.0.iter() {
             match &stmt {
-                MlirStmt::Return(_) | MlirStmt::Goto(_) | MlirStmt::CondGoto(_, _, _) => {
+                MlirStmt::Return(_)
+                | MlirStmt::Goto(_)
+                | MlirStmt::CondGoto(_, _, _)
+                | MlirStmt::Switch(_, _, _) => {
                     self.statements.push(stmt);
                     self.transition_block();
                 }
@@ -285,6 +289,17 @@ impl<'a> GraphFactory<'a> {
                             Some((condition, false)),
                         );
                     }
+                    MlirStmt::Switch(_, cases, default) => {
+                        let labels = cases.iter().map(|(_, label)| label).chain([default]);
+                        for label in labels {
+                            let case_block = self
+                                .block_from_label
+                                .get(label)
+                                .expect("Corresponding label not found.")
+                                .clone();
+                            self.connect(current.clone(), case_block, None);
+                        }
+                    }
                     MlirStmt::Return(_) => {
                         self.connect(current.clone(), self.end.clone(), None);
                     }
@@ -300,17 +315,18 @@ impl<'a> GraphFactory<'a> {
             }
         }
 
-        let mut index = 0;
-        while let Some(block) = blocks.get(index) {
-            if block.borrow().incoming.is_empty() {
-                let block = block.clone();
-                self.remove_block(&mut blocks, block);
-                index = 0;
-            } else {
-                index += 1;
+        // blocks that only jump to each other, like a dead loop made of gotos, still have
+        // incoming edges, so anything that can't be reached from the start is removed
+        let reachable = Self::reachable_blocks(&self.start);
+        let original_blocks = blocks.clone();
+        for block in &original_blocks {
+            if !reachable.iter().any(|reached| Rc::ptr_eq(reached, block)) {
+                self.remove_block(&mut blocks, block.clone());
             }
         }
 
+        let unreachable = Self::unreachable_spans(&original_blocks, &blocks);
+
         blocks.insert(0, self.start.clone());
         blocks.push(self.end.clone());
 
@@ -319,7 +335,52 @@ impl<'a> GraphFactory<'a> {
             end: self.end,
             edges: self.edges,
             blocks,
+            unreachable,
+        }
+    }
+
+    fn reachable_blocks(start: &Rc<RefCell<BasicBlock<'a>>>) -> Vec<Rc<RefCell<BasicBlock<'a>>>> {
+        let mut reached = vec![start.clone()];
+        let mut index = 0;
+        while let Some(block) = reached.get(index).cloned() {
+            for edge in &block.borrow().outgoing {
+                if !reached.iter().any(|_block| Rc::ptr_eq(_block, &edge.to)) {
+                    reached.push(edge.to.clone());
+                }
+            }
+            index += 1;
+        }
+        reached
+    }
+
+    /// Finds the first statement of every contiguous run of removed blocks,
+    /// so a run of dead code is only reported once.
+    fn unreachable_spans(
+        original_blocks: &[Rc<RefCell<BasicBlock<'a>>>],
+        blocks: &[Rc<RefCell<BasicBlock<'a>>>],
+    ) -> Vec<Span> {
+        let mut spans = Vec::new();
+        let mut in_dead_region = false;
+        for block in original_blocks {
+            let removed = !blocks.iter().any(|_block| Rc::ptr_eq(_block, block));
+            if !removed {
+                in_dead_region = false;
+                continue;
+            }
+            if in_dead_region {
+                continue;
+            }
+            let span = block
+                .borrow()
+                .statements
+                .iter()
+                .find_map(|stmt| stmt.span());
+            if let Some(span) = span {
+                spans.push(span);
+                in_dead_region = true;
+            }
         }
+        spans
     }
 
     fn connect(
@@ -363,6 +424,7 @@ pub struct ControlFlowGraph<'a> {
     pub end: Rc<RefCell<BasicBlock<'a>>>,
     pub blocks: Vec<Rc<RefCell<BasicBlock<'a>>>>,
     pub edges: Vec<Rc<BasicBlockEdge<'a>>>,
+    pub unreachable: Vec<Span>, // the first statement of each unreachable region
 }
 
 impl<'a> ControlFlowGraph<'a> {
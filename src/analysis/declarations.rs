@@ -1,7 +1,9 @@
+use crate::analysis::constants::evaluate_integer_constant;
 use crate::analysis::{Analyzer, control_flow, err};
 use crate::data::ast::*;
 use crate::data::error::{CompilerError, CompilerWarning};
 use crate::data::mlir::*;
+use crate::data::symbols::FunctionSymbol;
 use crate::util::{Locatable, Span};
 use crate::util::str_intern::InternedStr;
 
@@ -10,7 +12,7 @@ impl Analyzer {
         &mut self,
         _struct: &Locatable<StructDeclaration>,
     ) -> Result<MlirStruct, ()> {
-        if _struct.declaration.specifier.pointer {
+        if _struct.declaration.specifier.pointer_depth > 0 {
             self.report_error(CompilerError::StructDeclarationPointer(_struct.location));
         }
         if !_struct.declaration.specifier.qualifiers.is_empty() {
@@ -29,20 +31,44 @@ impl Analyzer {
             MlirTypeKind::Struct(ident) => ident.clone(),
             _ => panic!(),
         };
+        let is_union = matches!(
+            _struct.declaration.specifier.ty.as_slice(),
+            [TypeSpecifier::Union(_)]
+        );
         let ident = _struct.declaration.location.into_locatable(ident);
         let location = _struct.location;
         let mut fields = Vec::new();
         let mut size = 0;
+        let mut align = 1;
         for member in &_struct.members {
             let span = member.location;
+            for storage_spec in &member.specifier.specifiers {
+                self.report_warning(CompilerWarning::UnsupportedStorageSpecifier(
+                    storage_spec.to_string(),
+                    span,
+                ))
+            }
             let member = self.process_dec_to_hlir_variable(member, span)?;
-            size += self.sizeof(&member.ty, span);
+            if member.ty.is_va_list() {
+                err!(self, VaListNotAllowed, span);
+                return Err(());
+            }
+            let member_size = self.sizeof(&member.ty, span);
+            let member_align = self.alignof(&member.ty, span);
+            size = if is_union {
+                size.max(member_size)
+            } else {
+                size.next_multiple_of(member_align) + member_size
+            };
+            align = align.max(member_align);
             fields.push(span.into_locatable(member));
         }
         let _struct = MlirStruct {
             ident,
             members: fields,
-            size,
+            size: size.next_multiple_of(align),
+            align,
+            is_union,
         };
         let add_struct_result = self
             .scope
@@ -54,22 +80,68 @@ impl Analyzer {
         Ok(_struct)
     }
 
-    pub(super) fn validate_function_definition(
+    /// A file scope declaration has at most one storage specifier.
+    pub(super) fn validate_storage_specifier(
         &mut self,
-        func: &Locatable<FunctionDeclaration>,
-    ) -> Result<MlirFunction, ()> {
-        self.branch_has_jumped = false;
-        let (func_span, func) = (func.location, &func.value);
-        let (dec_span, dec) = (func.declaration.location, &func.declaration.value);
-        if !dec.specifier.specifiers.is_empty() {
-            self.report_error(CompilerError::FunctionStorageSpecifiers(dec_span));
-            return Err(());
+        specifiers: &[StorageSpecifier],
+        span: Span,
+    ) -> Result<Option<StorageSpecifier>, ()> {
+        match specifiers {
+            [] => Ok(None),
+            [specifier] => Ok(Some(*specifier)),
+            _ => {
+                err!(
+                    self,
+                    CustomError,
+                    "A declaration can only have one storage specifier.".to_string(),
+                    span
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Whether the function is declared `static`, `extern` is the same as no specifier.
+    fn validate_function_linkage(
+        &mut self,
+        declaration: &Locatable<Declaration>,
+    ) -> Result<bool, ()> {
+        let (dec_span, dec) = (declaration.location, &declaration.value);
+        match self.validate_storage_specifier(&dec.specifier.specifiers, dec_span)? {
+            Some(StorageSpecifier::Typedef) => {
+                self.report_error(CompilerError::FunctionStorageSpecifiers(dec_span));
+                Err(())
+            }
+            Some(StorageSpecifier::Static)
+                if dec
+                    .ident
+                    .as_ref()
+                    .is_some_and(|ident| ident.as_ref() == "main") =>
+            {
+                err!(
+                    self,
+                    CustomError,
+                    "'main' cannot be static.".to_string(),
+                    dec_span
+                );
+                Err(())
+            }
+            specifier => Ok(specifier == Some(StorageSpecifier::Static)),
         }
+    }
+
+    fn validate_function_return_type_and_ident(
+        &mut self,
+        declaration: &Locatable<Declaration>,
+    ) -> Result<(Locatable<MlirType>, Locatable<InternedStr>), ()> {
+        let (dec_span, dec) = (declaration.location, &declaration.value);
 
         let ty =
             dec_span.into_locatable(self.validate_type(&dec.specifier, dec_span, true, false)?);
-
-        self.return_ty = Some(ty.clone());
+        if ty.is_va_list() {
+            err!(self, VaListNotAllowed, dec_span);
+            return Err(());
+        }
 
         let ident = &dec.ident;
         if ident.is_none() {
@@ -79,6 +151,72 @@ impl Analyzer {
         let ident = ident.as_ref().unwrap();
         let ident = ident.location.into_locatable(ident.value.clone());
 
+        Ok((ty, ident))
+    }
+
+    pub(super) fn validate_function_prototype(
+        &mut self,
+        func: &Locatable<FunctionDeclaration>,
+    ) -> Result<(), ()> {
+        let (func_span, func) = (func.location, &func.value);
+        let is_static = self.validate_function_linkage(&func.declaration)?;
+        let (ty, ident) = self.validate_function_return_type_and_ident(&func.declaration)?;
+
+        // parameter names are optional in prototypes, only the types are needed
+        let mut param_types = Vec::new();
+        for parameter in &func.parameters {
+            if !parameter.specifier.specifiers.is_empty() {
+                self.report_error(CompilerError::ParamStorageSpecifiers(parameter.location));
+                return Err(());
+            }
+            param_types.push(self.validate_type(
+                &parameter.specifier,
+                parameter.location,
+                false,
+                false,
+            )?);
+        }
+
+        if ident.as_ref() == "main" {
+            self.validate_main_signature(&ty, &param_types, func_span)?;
+        }
+
+        let function = FunctionSymbol {
+            ident: ident.clone(),
+            location: None,
+            return_ty: ty.value,
+            varargs: func.varargs,
+            params: param_types,
+            is_defined: false,
+            is_static,
+        };
+        let result = self
+            .scope
+            .borrow_mut()
+            .declare_function(function, func_span);
+        if let Err(err) = result {
+            self.report_error(err);
+            return Err(());
+        }
+        Ok(())
+    }
+
+    pub(super) fn validate_function_definition(
+        &mut self,
+        func: &Locatable<FunctionDeclaration>,
+    ) -> Result<MlirFunction, ()> {
+        self.branch_has_jumped = false;
+        self.labels.clear();
+        let (func_span, func) = (func.location, &func.value);
+        let func_body = func
+            .body
+            .as_ref()
+            .expect("Function definitions must have a body.");
+        let is_static = self.validate_function_linkage(&func.declaration)?;
+        let (mut ty, ident) = self.validate_function_return_type_and_ident(&func.declaration)?;
+
+        self.return_ty = Some(ty.clone());
+
         let raw_params = &func.parameters;
         let mut parameters = Vec::new();
         for parameter in raw_params {
@@ -91,29 +229,80 @@ impl Analyzer {
             .iter()
             .map(|var| var.ty.clone())
             .collect::<Vec<_>>();
-        self.scope
+
+        if ident.as_ref() == "main" {
+            self.validate_main_signature(&ty, &param_types, func_span)?;
+        }
+        self.varargs_last_param = parameters
+            .last()
+            .filter(|_| func.varargs)
+            .map(|param| param.ident.value.clone());
+
+        let function = FunctionSymbol {
+            ident: ident.clone(),
+            location: None,
+            return_ty: ty.value.clone(),
+            varargs: func.varargs,
+            params: param_types,
+            is_defined: true,
+            is_static,
+        };
+        let result = self
+            .scope
             .borrow_mut()
-            .add_function(&ident, ty.clone(), param_types, func_span);
+            .declare_function(function, func_span);
+        let is_static = match result {
+            Ok(is_static) => is_static,
+            Err(err) => {
+                self.report_error(err);
+                is_static
+            }
+        };
 
         self.push_scope();
         for mut parameter in &mut parameters {
             self.add_variable_to_scope(parameter, func_span);
+            self.scope.borrow_mut().mark_variable_initialized(&parameter.ident);
         }
 
-        let body = self.validate_block(&func.body);
+        let body = self.validate_block(func_body);
 
         self.pop_scope();
 
-        let mut body = Self::flatten_blocks(body?);
-
-        if *self.return_ty.as_ref().unwrap() == VOID_TYPE {
+        let body = body?;
+        self.validate_labels()?;
+        let mut body = Self::flatten_blocks(body);
+
+        if ident.as_ref() == "main" {
+            // the return value of main is the exit code, so main always returns an int
+            // and falling off the end of it exits with 0
+            let exit_success = || MlirExpr {
+                span: func_span,
+                kind: Box::new(MlirExprKind::Literal(MlirLiteral::Int(0))),
+                ty: SIGNED_INT_TYPE,
+                is_lval: false,
+            };
+            for stmt in body.0.iter_mut() {
+                if let MlirStmt::Return(value @ None) = stmt {
+                    *value = Some(exit_success());
+                }
+            }
+            // only when needed, otherwise the implicit return is reported as unreachable
+            let name = format!("<fn {}; {}>", ident.value, func_span);
+            let cfg = control_flow::ControlFlowGraph::new(&body, &name);
+            if !cfg.all_paths_return() {
+                body.0.push(MlirStmt::Return(Some(exit_success())));
+            }
+            ty.value = SIGNED_INT_TYPE;
+        } else if *self.return_ty.as_ref().unwrap() == VOID_TYPE {
             let return_void = MlirStmt::Return(None);
             body.0.push(return_void);
         }
 
-        let body = func.body.location.into_locatable(body);
+        let body = func_body.location.into_locatable(body);
 
         self.return_ty = None;
+        self.varargs_last_param = None;
 
         let func = MlirFunction {
             span: func_span,
@@ -121,6 +310,8 @@ impl Analyzer {
             ident,
             parameters,
             body,
+            is_static,
+            varargs: func.varargs,
         };
 
         self.validate_function_return(&func, func_span);
@@ -128,6 +319,35 @@ impl Analyzer {
         Ok(func)
     }
 
+    /// `main` may be declared as `int main()` or `int main(int argc, char **argv)`,
+    /// `void main()` is still accepted and exits with 0.
+    fn validate_main_signature(
+        &mut self,
+        return_ty: &MlirType,
+        param_types: &[MlirType],
+        span: Span,
+    ) -> Result<(), ()> {
+        let valid_return = *return_ty == SIGNED_INT_TYPE || *return_ty == VOID_TYPE;
+        let valid_params = match param_types {
+            [] => true,
+            [
+                argc,
+                MlirType {
+                    kind: MlirTypeKind::Char(_),
+                    decl: MlirTypeDecl::Pointer(2),
+                },
+            ] => *argc == SIGNED_INT_TYPE,
+            _ => false,
+        };
+
+        if valid_return && valid_params {
+            Ok(())
+        } else {
+            self.report_error(CompilerError::InvalidMainSignature(span));
+            Err(())
+        }
+    }
+
     fn validate_function_return(&mut self, function: &MlirFunction, span: Span) {
         let cfg = control_flow::ControlFlowGraph::new(
             &function.body,
@@ -139,6 +359,9 @@ impl Analyzer {
                 span,
             ));
         }
+        for span in &cfg.unreachable {
+            self.report_warning(CompilerWarning::UnreachableCode(*span));
+        }
     }
 
     fn flatten_blocks(hlir_block: MlirBlock) -> MlirBlock {
@@ -194,37 +417,45 @@ impl Analyzer {
         let mut variable =
             self.process_dec_to_hlir_variable(&declaration.value, declaration.location)?;
 
-        let ty = self.validate_type(&declaration.specifier, span, false, false)?;
-        if var.is_array && var.array_size.is_none() && var.initializer.is_none() {
-            let err = CompilerError::ArraySizeNotSpecified(span);
-            self.report_error(err);
+        let mut ty = self.validate_array_dimensions(
+            variable.ty.value.clone(),
+            &var.inner_array_sizes,
+            span,
+        )?;
+        if let (true, Some(init)) = (ty.is_va_list(), &var.initializer) {
+            err!(self, VaListAssignment, init.location);
             return Err(());
         }
+        let mut initializer = None;
+        if var.is_array {
+            let array_size = match (var.array_size, &var.initializer) {
+                (Some(size), _) => size,
+                // without a size the array is as long as its initializer
+                (None, Some(init)) => {
+                    let Expression::ArrayInitializer(elements) = &init.value else {
+                        err!(self, ArraySizeNotSpecified, span);
+                        return Err(());
+                    };
+                    let (length, array_init) =
+                        self.validate_array_initializer(&ty, None, elements)?;
+                    initializer = Some(init.location.into_locatable(array_init));
+                    length as usize
+                }
+                (None, None) => {
+                    err!(self, ArraySizeNotSpecified, span);
+                    return Err(());
+                }
+            };
+            ty = self.validate_array_dimensions(ty, &[array_size], span)?;
+        }
 
-        let initializer = if let Some(init) = &var.initializer {
-            Some(init.location.into_locatable(self.validate_initializer(
+        if let (None, Some(init)) = (&initializer, &var.initializer) {
+            initializer = Some(init.location.into_locatable(self.validate_initializer(
                 &ty,
                 init,
                 init.location,
-            )?))
-        } else {
-            None
-        };
-
-        let array_size = var.array_size.map(|size| size as u64).or_else(|| {
-            initializer.as_ref().and_then(|init| match &init.value {
-                MlirVarInit::Array(arr) => Some(arr.len() as u64),
-                MlirVarInit::Expr(_) => None,
-            })
-        });
-
-        let ty = if let Some(array_size) = array_size {
-            let mut ty = ty;
-            ty.decl = MlirTypeDecl::Array(array_size);
-            ty
-        } else {
-            ty
-        };
+            )?));
+        }
 
         let ty = declaration.location.into_locatable(ty);
 
@@ -234,6 +465,287 @@ impl Analyzer {
         Ok(variable)
     }
 
+    /// Validates a file scope variable, returning it with whether it is a definition.
+    /// `static` gives it internal linkage, while `extern` without an initializer only declares
+    /// a variable that is defined elsewhere, possibly in another translation unit.
+    pub(super) fn validate_global_variable(
+        &mut self,
+        locatable_variable: &Locatable<VariableDeclaration>,
+    ) -> Result<(MlirVariable, bool), ()> {
+        let span = locatable_variable.location;
+        let specifiers = &locatable_variable.declaration.specifier.specifiers;
+        let specifier = self.validate_storage_specifier(specifiers, span)?;
+
+        let mut var = self.validate_variable_declaration(locatable_variable)?;
+        if var.ty.is_va_list() {
+            err!(self, VaListNotAllowed, span);
+            return Err(());
+        }
+        var.is_static = specifier == Some(StorageSpecifier::Static);
+        let is_definition =
+            specifier != Some(StorageSpecifier::Extern) || var.initializer.is_some();
+
+        let result = self
+            .scope
+            .borrow_mut()
+            .declare_global(&mut var, is_definition, span);
+        if let Err(err) = result {
+            self.report_error(err);
+            return Err(());
+        }
+        // globals are zero initialized
+        self.scope
+            .borrow_mut()
+            .mark_variable_initialized(&var.ident);
+        Ok((var, is_definition))
+    }
+
+    /// Validates a block scope `extern` declaration, it refers to the file scope variable of the
+    /// same name instead of declaring a local one.
+    pub(super) fn validate_block_extern(
+        &mut self,
+        locatable_variable: &Locatable<VariableDeclaration>,
+    ) -> Result<(), ()> {
+        let span = locatable_variable.location;
+        if let Some(init) = &locatable_variable.initializer {
+            err!(
+                self,
+                CustomError,
+                "A block scope 'extern' declaration cannot be initialized.".to_string(),
+                init.location
+            );
+            return Err(());
+        }
+        let mut var = self.validate_variable_declaration(locatable_variable)?;
+        if var.ty.is_va_list() {
+            err!(self, VaListNotAllowed, span);
+            return Err(());
+        }
+        let result = self.scope.borrow_mut().declare_block_extern(&mut var, span);
+        match result {
+            Ok(true) => self.block_externs.push(var),
+            Ok(false) => {}
+            Err(err) => {
+                self.report_error(err);
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Typedefs only name a type, nothing is emitted for them.
+    pub(super) fn validate_typedef(
+        &mut self,
+        locatable_variable: &Locatable<VariableDeclaration>,
+    ) -> Result<(), ()> {
+        let span = locatable_variable.location;
+        let var = &locatable_variable.value;
+        let declaration = &var.declaration;
+
+        if declaration.specifier.specifiers.len() > 1 {
+            err!(
+                self,
+                CustomError,
+                "A typedef cannot have other storage specifiers.".to_string(),
+                span
+            );
+            return Err(());
+        }
+        if let Some(init) = &var.initializer {
+            err!(
+                self,
+                CustomError,
+                "A typedef cannot be initialized.".to_string(),
+                init.location
+            );
+            return Err(());
+        }
+        let Some(ident) = &declaration.ident else {
+            err!(
+                self,
+                ExpectedButFound,
+                "<identifier>".to_string(),
+                ";".to_string(),
+                span
+            );
+            return Err(());
+        };
+
+        // the struct may be completed after the typedef, it is checked where the name is used
+        let ty = self.validate_type(&declaration.specifier, span, true, true)?;
+        let mut ty = self.validate_array_dimensions(ty, &var.inner_array_sizes, span)?;
+        if var.is_array {
+            let Some(size) = var.array_size else {
+                err!(self, ArraySizeNotSpecified, span);
+                return Err(());
+            };
+            ty = self.validate_array_dimensions(ty, &[size], span)?;
+        }
+
+        let result = self
+            .scope
+            .borrow_mut()
+            .add_typedef(&ident.value, ty, ident.location);
+        if let Err(err) = result {
+            self.report_error(err);
+            return Err(());
+        }
+        Ok(())
+    }
+
+    /// A declaration without an identifier is only useful when it declares an enum,
+    /// as in `enum color { RED, GREEN };`.
+    pub(super) fn validate_empty_declaration(
+        &mut self,
+        locatable_variable: &Locatable<VariableDeclaration>,
+    ) -> Result<(), ()> {
+        let span = locatable_variable.location;
+        let specifier = &locatable_variable.declaration.specifier;
+        let declares_enum = specifier
+            .ty
+            .iter()
+            .any(|ty| matches!(ty, TypeSpecifier::Enum(_, Some(_))));
+        // 'struct file;' declares a struct that is defined later, or never for an opaque type
+        let declares_struct = specifier.pointer_depth == 0
+            && matches!(
+                specifier.ty.as_slice(),
+                [TypeSpecifier::Struct(_) | TypeSpecifier::Union(_)]
+            );
+        if !declares_enum && !declares_struct {
+            err!(self, DeclarationMissingIdentifier, span);
+            return Err(());
+        }
+        self.validate_type(specifier, span, false, declares_struct)?;
+        Ok(())
+    }
+
+    /// Declares the enum tag and its enumerators when the specifier has a body,
+    /// otherwise the tag must have been declared already.
+    pub(super) fn validate_enum_specifier(
+        &mut self,
+        ident: Option<&InternedStr>,
+        enumerators: Option<&Vec<Locatable<Enumerator>>>,
+        location: Span,
+    ) -> Result<(), ()> {
+        let Some(enumerators) = enumerators else {
+            let ident = ident.expect("The parser requires a tag for enums without a body.");
+            let result = self.scope.borrow().check_enum_exists(ident, location);
+            if let Err(err) = result {
+                self.report_error(err);
+                return Err(());
+            }
+            return Ok(());
+        };
+        if let Some(ident) = ident {
+            let result = self.scope.borrow_mut().add_enum(ident, location);
+            if let Err(err) = result {
+                self.report_error(err);
+                return Err(());
+            }
+        }
+
+        let mut values: Vec<(i64, &InternedStr)> = Vec::new();
+        let mut next_value = 0;
+        for enumerator in enumerators {
+            let Enumerator { ident, value } = &enumerator.value;
+            let value = match value {
+                Some(expr) => {
+                    let expr = self.validate_expression(expr)?;
+                    let Some(value) = evaluate_integer_constant(&expr) else {
+                        err!(
+                            self,
+                            EnumeratorRequiresConstant,
+                            ident.to_string(),
+                            expr.span
+                        );
+                        return Err(());
+                    };
+                    value
+                }
+                None => next_value,
+            };
+            if i32::try_from(value).is_err() {
+                err!(
+                    self,
+                    EnumeratorOutOfRange,
+                    ident.to_string(),
+                    value,
+                    enumerator.location
+                );
+                return Err(());
+            }
+            if let Some((_, other)) = values.iter().find(|(other, _)| *other == value) {
+                let warning = CompilerWarning::DuplicateEnumeratorValue(
+                    ident.to_string(),
+                    other.to_string(),
+                    enumerator.location,
+                );
+                self.report_warning(warning);
+            }
+            values.push((value, &ident.value));
+
+            let result =
+                self.scope
+                    .borrow_mut()
+                    .add_enumerator(&ident.value, value as i32, ident.location);
+            if let Err(err) = result {
+                self.report_error(err);
+                return Err(());
+            }
+            next_value = value + 1;
+        }
+        Ok(())
+    }
+
+    /// More stars in the declarator make pointers to the function pointer.
+    pub(super) fn validate_function_pointer_type(
+        &mut self,
+        return_ty: MlirType,
+        declarator: &FunctionPointerDeclarator,
+        location: Span,
+    ) -> Result<MlirType, ()> {
+        if return_ty.is_array() {
+            err!(
+                self,
+                CustomError,
+                "Functions cannot return arrays.".to_string(),
+                location
+            );
+            return Err(());
+        }
+        if return_ty.is_va_list() {
+            err!(self, VaListNotAllowed, location);
+            return Err(());
+        }
+        let mut params = Vec::new();
+        for parameter in &declarator.parameters {
+            if !parameter.specifier.specifiers.is_empty() {
+                err!(self, ParamStorageSpecifiers, parameter.location);
+                return Err(());
+            }
+            params.push(self.validate_type(
+                &parameter.specifier,
+                parameter.location,
+                false,
+                false,
+            )?);
+        }
+        let decl = match declarator.pointer_depth {
+            1 => MlirTypeDecl::Basic,
+            depth => MlirTypeDecl::Pointer(depth - 1),
+        };
+        let signature = MlirFunctionSignature {
+            return_ty,
+            params,
+            varargs: declarator.varargs,
+        };
+        let ty = MlirType::new(MlirTypeKind::FunctionPointer(Box::new(signature)), decl);
+        Ok(match declarator.array_size {
+            Some(size) => ty.array_of(size as u64),
+            None => ty,
+        })
+    }
+
     pub(crate) fn process_dec_to_hlir_variable(
         &mut self,
         dec: &Declaration,
@@ -257,13 +769,6 @@ impl Analyzer {
                 }
             }
         }
-        for storage_spec in &dec.specifier.specifiers {
-            // need to change this span to the specific storage spec location
-            self.report_warning(CompilerWarning::UnsupportedStorageSpecifier(
-                storage_spec.to_string(),
-                span,
-            ))
-        }
         let ty = span.into_locatable(self.validate_type(&dec.specifier, span, false, false)?);
 
         Ok(MlirVariable {
@@ -272,30 +777,186 @@ impl Analyzer {
             ty,
             ident,
             is_const,
+            is_static: false,
             initializer: None,
         })
     }
 
+    /// Wraps the type in arrays of the given dimensions, `[10][20]` makes an array of
+    /// 10 arrays of 20, so the innermost dimension is applied first.
+    pub(super) fn validate_array_dimensions(
+        &mut self,
+        element_ty: MlirType,
+        array_sizes: &[usize],
+        span: Span,
+    ) -> Result<MlirType, ()> {
+        let mut ty = element_ty;
+        if ty.is_va_list() && !array_sizes.is_empty() {
+            err!(self, VaListNotAllowed, span);
+            return Err(());
+        }
+        for size in array_sizes.iter().rev() {
+            ty = ty.array_of(*size as u64);
+        }
+        Ok(ty)
+    }
+
     pub(super) fn validate_initializer(
         &mut self,
         var_ty: &MlirType,
         expr: &Expression,
         span: Span,
     ) -> Result<MlirVarInit, ()> {
-        if let Expression::ArrayInitializer(arr) = expr {
-            let basic_ty = var_ty.as_basic();
-            let mut inits = Vec::with_capacity(arr.len());
-            for init in arr {
-                let init = self.validate_expression(init)?;
-                let span = init.span;
-                let init = self.implicit_cast(init, basic_ty.clone(), span);
-                inits.push(init);
-            }
-            Ok(MlirVarInit::Array(inits))
-        } else {
+        let Expression::ArrayInitializer(elements) = expr else {
             let expr = self.validate_expression(expr)?;
             let expr = self.implicit_cast(expr, var_ty.clone(), span);
-            Ok(MlirVarInit::Expr(expr))
+            return Ok(MlirVarInit::Expr(expr));
+        };
+        if let MlirTypeDecl::Array(size) = var_ty.decl {
+            let element_ty = var_ty.deref_type();
+            let (_, init) = self.validate_array_initializer(&element_ty, Some(size), elements)?;
+            return Ok(init);
         }
+        if var_ty.is_basic() && matches!(var_ty.kind, MlirTypeKind::Struct(_)) {
+            return self.validate_struct_initializer(var_ty, elements, span);
+        }
+        // a scalar may have its initializer in braces, 'int x = { 5 };'
+        match elements.as_slice() {
+            [] => {
+                err!(
+                    self,
+                    CustomError,
+                    "A scalar initializer cannot be empty.".to_string(),
+                    span
+                );
+                Err(())
+            }
+            [InitializerElement {
+                designator: Some(designator),
+                ..
+            }, ..] => {
+                self.report_invalid_designator(designator, var_ty);
+                Err(())
+            }
+            [element] => self.validate_initializer(var_ty, &element.value, element.value.location),
+            [_, element, ..] => {
+                let span = element.value.location;
+                err!(self, TooManyInitializers, var_ty.to_string(), span);
+                Err(())
+            }
+        }
+    }
+
+    /// Returns the initializer and the length of the array, elements without a designator
+    /// follow the previous element. The length is checked against `size` when it is known.
+    pub(super) fn validate_array_initializer(
+        &mut self,
+        element_ty: &MlirType,
+        size: Option<u64>,
+        elements: &[InitializerElement],
+    ) -> Result<(u64, MlirVarInit), ()> {
+        let array_ty = || element_ty.clone().array_of(size.unwrap_or(0));
+        let mut inits: Vec<(u64, MlirVarInit)> = Vec::with_capacity(elements.len());
+        let mut index = 0;
+        let mut length = 0;
+        for element in elements {
+            match &element.designator {
+                Some(Designator::Index(designator)) => {
+                    let value = self.validate_expression(designator)?;
+                    let Some(value) = evaluate_integer_constant(&value) else {
+                        err!(self, DesignatorRequiresConstant, designator.location);
+                        return Err(());
+                    };
+                    if value < 0 || size.is_some_and(|size| value as u64 >= size) {
+                        let ty = array_ty().to_string();
+                        err!(self, DesignatorOutOfBounds, value, ty, designator.location);
+                        return Err(());
+                    }
+                    index = value as u64;
+                }
+                Some(designator) => {
+                    self.report_invalid_designator(designator, &array_ty());
+                    return Err(());
+                }
+                None => {}
+            }
+            if size.is_some_and(|size| index >= size) {
+                let span = element.value.location;
+                err!(self, TooManyInitializers, array_ty().to_string(), span);
+                return Err(());
+            }
+
+            let init =
+                self.validate_initializer(element_ty, &element.value, element.value.location)?;
+            // a later initializer for the same element replaces the earlier one
+            inits.retain(|(i, _)| *i != index);
+            inits.push((index, init));
+            index += 1;
+            length = length.max(index);
+        }
+        Ok((length, MlirVarInit::Array(inits)))
+    }
+
+    fn validate_struct_initializer(
+        &mut self,
+        struct_ty: &MlirType,
+        elements: &[InitializerElement],
+        span: Span,
+    ) -> Result<MlirVarInit, ()> {
+        let ident = struct_ty.get_struct_ident();
+        let result = self.scope.borrow_mut().get_struct(ident, span);
+        let _struct = match result {
+            Ok(_struct) => _struct,
+            Err(err) => {
+                self.report_error(err);
+                return Err(());
+            }
+        };
+        let mut inits: Vec<(InternedStr, MlirVarInit)> = Vec::with_capacity(elements.len());
+        let mut position = 0;
+        for element in elements {
+            match &element.designator {
+                Some(Designator::Member(member)) => {
+                    let Some(found) = _struct.members.iter().position(|m| *m == member.value)
+                    else {
+                        let (name, ident) = (member.value.to_string(), ident.to_string());
+                        err!(self, MemberNotFound, name, ident, member.location);
+                        return Err(());
+                    };
+                    position = found;
+                }
+                Some(designator) => {
+                    self.report_invalid_designator(designator, struct_ty);
+                    return Err(());
+                }
+                None => {}
+            }
+            // a union holds one member, without a designator only the first is initialized
+            let is_full = _struct.is_union && element.designator.is_none() && !inits.is_empty();
+            let Some(member) = _struct.members.get(position).filter(|_| !is_full) else {
+                let span = element.value.location;
+                err!(self, TooManyInitializers, struct_ty.to_string(), span);
+                return Err(());
+            };
+
+            let member_ty = &_struct.body[member].ty;
+            let init =
+                self.validate_initializer(member_ty, &element.value, element.value.location)?;
+            if _struct.is_union {
+                inits.clear();
+            }
+            inits.retain(|(m, _)| m != member);
+            inits.push((member.clone(), init));
+            position += 1;
+        }
+        Ok(MlirVarInit::Struct(inits))
+    }
+
+    fn report_invalid_designator(&mut self, designator: &Designator, ty: &MlirType) {
+        let (designator, span) = match designator {
+            Designator::Member(member) => (format!(".{}", member.value), member.location),
+            Designator::Index(index) => ("[]".to_string(), index.location),
+        };
+        err!(self, InvalidDesignator, designator, ty.to_string(), span);
     }
 }
@@ -3,8 +3,10 @@ use crate::data::ast::{
     AssignOp, BinaryOp, Declaration, Expression, PostfixOp, TypeOrExpression, UnaryOp,
 };
 use crate::data::error::{CompilerError, CompilerWarning};
+use crate::data::libc;
 use crate::data::mlir::{
-    MlirExpr, MlirExprKind, MlirLiteral, MlirType, MlirTypeDecl, MlirTypeKind, VOID_PTR,
+    MlirExpr, MlirExprKind, MlirLiteral, MlirType, MlirTypeDecl, MlirTypeKind, SIGNED_INT_TYPE,
+    VOID_PTR,
 };
 use crate::data::tokens::Literal;
 use crate::util::{Locatable, Span};
@@ -20,11 +22,18 @@ impl Analyzer {
             Expression::PostFix(op, expr) => self.validate_post_inc_or_dec(op, expr, expr.location),
             Expression::Unary(op, expr) => self.validate_unary(op, expr),
             Expression::Binary(op, left, right) => self.validate_binary(op, left, right),
-            Expression::FunctionCall(ident, args) => self.validate_function_call(ident, args),
+            Expression::Ternary(condition, then, otherwise) => {
+                self.validate_ternary(condition, then, otherwise)
+            }
+            Expression::FunctionCall(callee, args) => self.validate_function_call(callee, args),
             Expression::Index(left, index) => self.validate_index(left, index),
             Expression::Member(body, member) => self.validate_member(body, member),
             Expression::PointerMember(body, member) => self.validate_pointer_member(body, member),
             Expression::Cast(dec, expr) => self.validate_cast_expression(dec, expr),
+            Expression::VaStart(va_list, last) => self.validate_va_start(va_list, last),
+            Expression::VaArg(va_list, ty) => self.validate_va_arg(va_list, ty),
+            Expression::VaEnd(va_list) => self.validate_va_end(va_list),
+            Expression::VaCopy(dest, src) => self.validate_va_copy(dest, src),
             _ => unreachable!(),
         }
         // .map(|expr| expr.fold())
@@ -34,6 +43,30 @@ impl Analyzer {
         &mut self,
         variable: &Locatable<InternedStr>,
     ) -> Result<MlirExpr, ()> {
+        let enumerator = self
+            .scope
+            .borrow_mut()
+            .get_enumerator(&variable.value, variable.location);
+        if let Some(value) = enumerator {
+            return Ok(MlirExpr {
+                span: variable.location,
+                kind: Box::new(MlirExprKind::Literal(MlirLiteral::Int(value))),
+                ty: SIGNED_INT_TYPE,
+                is_lval: false,
+            });
+        }
+        let function = self
+            .scope
+            .borrow_mut()
+            .get_function(&variable.value, variable.location);
+        if let Some(function) = function {
+            return Ok(MlirExpr {
+                span: variable.location,
+                ty: function.pointer_type(),
+                kind: Box::new(MlirExprKind::Function(function.ident)),
+                is_lval: false,
+            });
+        }
         let result = self
             .scope
             .borrow_mut()
@@ -45,9 +78,22 @@ impl Analyzer {
                 is_lval: true,
             });
         if let Err(err) = result {
+            // a library function whose header was not included
+            let err = match (err, libc::header_of(&variable.value)) {
+                (CompilerError::IdentNotFound(ident, span), Some(header)) => {
+                    CompilerError::MissingLibraryInclude(ident.to_string(), header.name, span)
+                }
+                (err, _) => err,
+            };
             self.report_error(err);
             Err(())
         } else {
+            // the reads are checked in source order, not along the control flow, so a variable
+            // that is assigned on every path can still be reported, which is why it is '-Wall' only
+            if !self.scope.borrow_mut().mark_variable_initialized(&variable.value) {
+                let warning = CompilerWarning::UninitializedVariable(variable.location);
+                self.report_warning(warning);
+            }
             Ok(result.unwrap())
         }
     }
@@ -90,11 +136,19 @@ impl Analyzer {
 
         let size = match &ty.kind {
             MlirTypeKind::Char(_) => CHAR_SIZE,
+            MlirTypeKind::Short(_) => SHORT_SIZE,
             MlirTypeKind::Int(_) => INT_SIZE,
             MlirTypeKind::Long(_) => LONG_SIZE,
             MlirTypeKind::Double => DOUBLE_SIZE,
             MlirTypeKind::Void => 0,
             MlirTypeKind::Float => FLOAT_SIZE,
+            MlirTypeKind::FunctionPointer(_) => POINTER_SIZE,
+            MlirTypeKind::Array(element) => self.sizeof(element, span),
+            // the layout is only known to codegen, so it can't be measured or stored in memory
+            MlirTypeKind::VaList => {
+                self.report_error(CompilerError::VaListNotAllowed(span));
+                0
+            }
             MlirTypeKind::Struct(ident) => {
                 let result = self.scope.borrow_mut().get_struct_size(ident, span);
                 if let Err(err) = result {
@@ -113,6 +167,31 @@ impl Analyzer {
         }
     }
 
+    /// Natural alignment, matching the layout LLVM uses for the same types.
+    pub(super) fn alignof(&mut self, ty: &MlirType, span: Span) -> u64 {
+        use crate::data::arch::*;
+        if ty.is_pointer() {
+            return POINTER_SIZE;
+        }
+
+        match &ty.kind {
+            MlirTypeKind::Struct(ident) => {
+                let result = self.scope.borrow_mut().get_struct_align(ident, span);
+                if let Err(err) = result {
+                    self.report_error(err);
+                    1
+                } else {
+                    result.unwrap()
+                }
+            }
+            MlirTypeKind::Array(element) => self.alignof(element, span),
+            // scalars are aligned to their size, arrays like their elements
+            kind => self
+                .sizeof(&MlirType::new(kind.clone(), MlirTypeDecl::Basic), span)
+                .max(1),
+        }
+    }
+
     pub(super) fn validate_post_inc_or_dec(
         &mut self,
         op: &PostfixOp,
@@ -149,6 +228,10 @@ impl Analyzer {
         expr: &Locatable<Box<Expression>>,
     ) -> Result<MlirExpr, ()> {
         let span = expr.location;
+        if let (UnaryOp::AddressOf, Expression::Variable(ident)) = (op, expr.value.as_ref()) {
+            // the variable may be initialized through the pointer
+            self.scope.borrow_mut().mark_variable_initialized(&ident.value);
+        }
         let expr = self.validate_expression(expr)?;
         self.validate_unary_expression(op, expr, span)
     }
@@ -160,30 +243,96 @@ impl Analyzer {
         right: &Locatable<Box<Expression>>,
     ) -> Result<MlirExpr, ()> {
         let span = left.location.merge(right.location);
+        if let (BinaryOp::Assign(AssignOp::Assign), Expression::Variable(ident)) =
+            (op, left.value.as_ref())
+        {
+            // the right side is validated first so it can't read the variable it initializes
+            let right = self.validate_expression(right)?;
+            self.scope.borrow_mut().mark_variable_initialized(&ident.value);
+            let left = self.validate_expression(left)?;
+            return self.validate_binary_expression(op, left, right, span);
+        }
         let left = self.validate_expression(left)?;
         let right = self.validate_expression(right)?;
         self.validate_binary_expression(op, left, right, span)
     }
 
+    fn validate_ternary(
+        &mut self,
+        condition: &Locatable<Box<Expression>>,
+        then: &Locatable<Box<Expression>>,
+        otherwise: &Locatable<Box<Expression>>,
+    ) -> Result<MlirExpr, ()> {
+        let span = condition.location.merge(otherwise.location);
+        let condition = self.validate_conditional(condition)?;
+        let then = self.validate_expression(then)?;
+        let otherwise = self.validate_expression(otherwise)?;
+
+        // numeric arms go through the usual arithmetic conversions, pointer arms meet at
+        // the pointer type they share, 'void *' wins over any other pointer and
+        // a null pointer constant takes the type of the other arm
+        let (then, otherwise) = if then.ty.is_numeric() && otherwise.ty.is_numeric() {
+            self.binary_numeric_cast(then, otherwise)
+        } else {
+            let then = self.decay_array(then)?;
+            let otherwise = self.decay_array(otherwise)?;
+            let ty = if then.ty == otherwise.ty
+                || (then.is_pointer() && otherwise.is_null_pointer_constant())
+            {
+                then.ty.clone()
+            } else if otherwise.is_pointer() && then.is_null_pointer_constant() {
+                otherwise.ty.clone()
+            } else if (then.ty == VOID_PTR && otherwise.is_pointer())
+                || (otherwise.ty == VOID_PTR && then.is_pointer())
+            {
+                VOID_PTR
+            } else {
+                err!(
+                    self,
+                    CannotImplicitCast,
+                    otherwise.ty.to_string(),
+                    then.ty.to_string(),
+                    otherwise.span
+                );
+                return Err(());
+            };
+            (
+                self.ternary_arm_cast(then, ty.clone()),
+                self.ternary_arm_cast(otherwise, ty),
+            )
+        };
+
+        Ok(MlirExpr {
+            span,
+            ty: then.ty.clone(),
+            is_lval: false,
+            kind: Box::new(MlirExprKind::Ternary(condition, then, otherwise)),
+        })
+    }
+
+    fn ternary_arm_cast(&mut self, arm: MlirExpr, ty: MlirType) -> MlirExpr {
+        let span = arm.span;
+        if ty.is_pointer() && arm.is_null_pointer_constant() {
+            self.explicit_cast(arm, ty, span)
+        } else {
+            self.implicit_cast(arm, ty, span)
+        }
+    }
+
     fn validate_function_call(
         &mut self,
-        ident: &Locatable<InternedStr>,
+        callee: &Locatable<Box<Expression>>,
         args: &Vec<Locatable<Expression>>,
     ) -> Result<MlirExpr, ()> {
-        let span = ident.location;
-        let ident = ident.value.clone();
-        let mut resolver = self.scope.borrow_mut();
-        let func = resolver.validate_function_call(ident.clone(), span);
-        if func.is_err() {
-            let func = func.unwrap_err();
-            return Err(());
-        }
-        let func = func.unwrap();
-        let varargs = func.varargs;
-        let params = func.params.clone();
-        let location = func.location;
-        let return_ty = func.return_ty.clone();
-        drop(resolver);
+        let span = callee.location;
+        let callee = self.validate_expression(callee)?;
+        let signature = match &callee.ty.kind {
+            MlirTypeKind::FunctionPointer(signature) if callee.ty.is_basic() => signature.clone(),
+            _ => {
+                self.report_error(CompilerError::NotAFunction(span));
+                return Err(());
+            }
+        };
         let mut hlir_args = Vec::new();
         let mut last_arg_span = span;
         for loc_expr in args {
@@ -193,18 +342,26 @@ impl Analyzer {
                 loc_expr.location,
             ));
         }
-        let hlir_args = self.try_cast_function_args(hlir_args, &params)?;
-
-        self.validate_function_params(varargs, &params, &hlir_args, span)?;
-        let kind = MlirExprKind::FunctionCall {
-            location,
-            ident,
-            args: hlir_args.into_iter().map(|arg| arg.0).collect(),
+        let hlir_args = self.try_cast_function_args(hlir_args, &signature.params)?;
+
+        self.validate_function_params(signature.varargs, &signature.params, &hlir_args, span)?;
+        let args = hlir_args.into_iter().map(|arg| arg.0).collect();
+        // calls through a function name are direct, anything else goes through the pointer
+        let kind = match *callee.kind {
+            MlirExprKind::Function(ident) => {
+                let function = self.scope.borrow_mut().get_function(&ident, span);
+                MlirExprKind::FunctionCall {
+                    location: function.and_then(|function| function.location),
+                    ident,
+                    args,
+                }
+            }
+            _ => MlirExprKind::IndirectCall(callee, args),
         };
         Ok(MlirExpr {
             span: span.merge(last_arg_span),
             kind: Box::new(kind),
-            ty: return_ty,
+            ty: signature.return_ty,
             is_lval: false,
         })
     }
@@ -214,7 +371,7 @@ impl Analyzer {
         param_types: &[MlirType],
     ) -> Result<Vec<(MlirExpr, Span)>, ()> {
         let mut args = args;
-        let var_args = args.split_off(param_types.len());
+        let var_args = args.split_off(param_types.len().min(args.len()));
         let mut processed_args = Vec::new();
         for (arg, param_ty) in args.into_iter().zip(param_types.iter()) {
             let arg_ty = arg.0.ty.clone();
@@ -222,7 +379,10 @@ impl Analyzer {
             let arg = self.implicit_cast(arg.0, param_ty.clone(), span);
             processed_args.push((arg, span));
         }
-        processed_args.extend(var_args);
+        for (arg, span) in var_args {
+            let arg = self.default_argument_promotion(arg, span)?;
+            processed_args.push((arg, span));
+        }
         Ok(processed_args)
     }
 
@@ -301,39 +461,45 @@ impl Analyzer {
     ) -> Result<MlirExpr, ()> {
         let (literal, ty) = match literal {
             Literal::Integer { value, suffix } => {
-                if suffix.is_some() {
-                    let warning = CompilerWarning::SuffixIgnored(span);
-                    self.report_warning(warning);
-                }
-                if *value <= i64::MAX as isize {
+                // literals without a suffix are 'long', like C a 'u' alone gives an
+                // 'unsigned int' when the value fits in one and an 'unsigned long' otherwise,
+                // values past 'long' wrap around in the lexer and are unsigned like in gcc
+                let is_unsigned = suffix.as_ref().is_some_and(|suffix| suffix.contains('u'));
+                let is_long = suffix.as_ref().is_some_and(|suffix| suffix.contains('l'));
+                let fits_unsigned_int = u32::try_from(*value).is_ok();
+                if is_unsigned && !is_long && fits_unsigned_int {
                     (
-                        MlirLiteral::Long(*value as i64),
-                        MlirType::new(MlirTypeKind::Long(false), MlirTypeDecl::Basic),
+                        MlirLiteral::UInt(*value as u32),
+                        MlirType::new(MlirTypeKind::Int(true), MlirTypeDecl::Basic),
                     )
-                } else if *value <= u64::MAX as isize {
+                } else if is_unsigned || *value < 0 {
                     (
                         MlirLiteral::ULong(*value as u64),
                         MlirType::new(MlirTypeKind::Long(true), MlirTypeDecl::Basic),
                     )
                 } else {
-                    let err = CompilerError::NumberTooLarge(span);
-                    self.report_error(err);
                     (
-                        MlirLiteral::Long(0),
+                        MlirLiteral::Long(*value as i64),
                         MlirType::new(MlirTypeKind::Long(false), MlirTypeDecl::Basic),
                     )
                 }
             }
-            Literal::Float { value, suffix } => {
-                if suffix.is_some() {
-                    let warning = CompilerWarning::SuffixIgnored(span);
-                    self.report_warning(warning);
+            Literal::Float { value, suffix } => match suffix.as_deref() {
+                Some("f") => (
+                    MlirLiteral::Float(*value as f32),
+                    MlirType::new(MlirTypeKind::Float, MlirTypeDecl::Basic),
+                ),
+                suffix => {
+                    if suffix.is_some() {
+                        let warning = CompilerWarning::SuffixIgnored(span);
+                        self.report_warning(warning);
+                    }
+                    (
+                        MlirLiteral::Double(*value),
+                        MlirType::new(MlirTypeKind::Double, MlirTypeDecl::Basic),
+                    )
                 }
-                (
-                    MlirLiteral::Double(*value),
-                    MlirType::new(MlirTypeKind::Double, MlirTypeDecl::Basic),
-                )
-            }
+            },
             Literal::Char { value } => (
                 MlirLiteral::UChar(*value as u8),
                 MlirType::new(MlirTypeKind::Char(true), MlirTypeDecl::Basic),
@@ -342,7 +508,7 @@ impl Analyzer {
                 let mut value = value.bytes().collect::<Vec<_>>();
                 (
                     MlirLiteral::String(value),
-                    MlirType::new(MlirTypeKind::Char(true), MlirTypeDecl::Pointer),
+                    MlirType::new(MlirTypeKind::Char(false), MlirTypeDecl::Pointer(1)),
                 )
             }
         };
@@ -373,7 +539,8 @@ impl Analyzer {
             err!(self, CannotIndexWith, index.ty.to_string(), index_span);
         }
 
-        let ty = left.ty.clone().as_basic();
+        let ty = left.ty.deref_type();
+        self.check_complete_type(&ty, left_span)?;
         Ok(MlirExpr {
             kind: Box::new(MlirExprKind::Index(left, index)),
             span: left_span.merge(index_span),
@@ -382,6 +549,20 @@ impl Analyzer {
         })
     }
 
+    /// A pointer to an incomplete struct can be passed around, but not dereferenced.
+    fn check_complete_type(&mut self, ty: &MlirType, span: Span) -> Result<(), ()> {
+        match &ty.kind {
+            MlirTypeKind::Struct(ident) if !ty.is_pointer() => {
+                let result = self
+                    .scope
+                    .borrow_mut()
+                    .check_struct_exists(ident, None, true, span);
+                result.or_else(|err| self.report_error(err))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub(super) fn validate_pointer_member_access(
         &mut self,
         body: MlirExpr,
@@ -393,8 +574,7 @@ impl Analyzer {
             err!(self, ArrowOnNonPointer, body_span);
         }
         // dereference to underlying type,
-        let mut ty = body.ty.clone();
-        ty.decl = MlirTypeDecl::Basic;
+        let ty = body.ty.deref_type();
         let expr = MlirExpr {
             span: body_span.merge(member_span),
             kind: Box::new(MlirExprKind::Deref(body)),
@@ -458,6 +638,10 @@ impl Analyzer {
                             kind: MlirTypeKind::Char(false),
                             decl: MlirTypeDecl::Basic,
                         }),
+                        MlirTypeKind::Short(true) => Some(MlirType {
+                            kind: MlirTypeKind::Short(false),
+                            decl: MlirTypeDecl::Basic,
+                        }),
                         MlirTypeKind::Int(true) => Some(MlirType {
                             kind: MlirTypeKind::Int(false),
                             decl: MlirTypeDecl::Basic,
@@ -517,43 +701,39 @@ impl Analyzer {
                     })
                 }
             }
+            // a function pointer that is dereferenced decays right back into the pointer
+            UnaryOp::Deref if expr.ty.is_function_pointer() => Ok(expr),
+            UnaryOp::AddressOf if matches!(*expr.kind, MlirExprKind::Function(_)) => Ok(expr),
             UnaryOp::Deref => {
                 if !expr.is_pointer() {
                     let err = CompilerError::DerefOnNonPointer(expr.ty.to_string(), span);
                     self.report_error(err);
                     return Ok(expr);
                 }
-                let ty_kind = expr.ty.kind.clone();
+                let ty = expr.ty.deref_type();
+                self.check_complete_type(&ty, span)?;
 
                 Ok(MlirExpr {
                     span,
                     kind: Box::new(MlirExprKind::Deref(expr)),
-                    ty: MlirType {
-                        decl: MlirTypeDecl::Basic,
-                        kind: ty_kind,
-                    },
+                    ty,
                     is_lval: true,
                 })
             }
             UnaryOp::AddressOf => {
-                if expr.is_pointer() {
-                    let err = CompilerError::AttemptedAddressOfPointer(span);
-                    self.report_error(err);
-                    return Ok(expr);
-                }
                 if !expr.is_lval {
                     let err = CompilerError::CannotAddressNonLVal(span);
                     self.report_error(err);
                     return Ok(expr);
                 }
-                let ty_kind = expr.ty.kind.clone();
+                let Some(ty) = expr.ty.address_of_type() else {
+                    err!(self, PointerDepthExceeded, span);
+                    return Err(());
+                };
                 Ok(MlirExpr {
                     span,
                     kind: Box::new(MlirExprKind::AddressOf(expr)),
-                    ty: MlirType {
-                        kind: ty_kind,
-                        decl: MlirTypeDecl::Pointer,
-                    },
+                    ty,
                     is_lval: false,
                 })
             }
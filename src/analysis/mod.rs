@@ -1,12 +1,14 @@
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::iter::Map;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 
+use crate::analysis::statements::{LabelContext, SwitchContext};
 use crate::analysis::symbols::SymbolResolver;
 use crate::data::ast::*;
 use crate::data::error::{CompilerError, CompilerWarning, Reporter};
+use crate::data::libc::LibcHeader;
 use crate::data::mlir::*;
 use crate::util::str_intern::InternedStr;
 use crate::util::{Locatable, Span};
@@ -14,11 +16,13 @@ use crate::{display_mlir, output_analyzer};
 
 mod binary_expressions;
 mod casting;
+mod constants;
 mod control_flow;
 mod declarations;
 mod expressions;
 mod statements;
 mod symbols;
+mod varargs;
 
 static mut LABEL_COUNT: usize = 0;
 
@@ -49,7 +53,7 @@ macro_rules! err {
 
 pub(in crate::analysis) use err;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct SharedReporter(Rc<RefCell<Reporter>>);
 
 impl Deref for SharedReporter {
@@ -64,8 +68,14 @@ pub struct Analyzer {
     scope: Box<RefCell<SymbolResolver>>,
     reporter: SharedReporter,
     return_ty: Option<MlirType>, // for functions
+    varargs_last_param: Option<InternedStr>, // for variadic functions, named by 'va_start'
     loop_label_stack: VecDeque<InternedStr>,
+    break_label_stack: VecDeque<InternedStr>, // loops and switches
+    switch_stack: VecDeque<SwitchContext>,
+    labels: HashMap<InternedStr, LabelContext>, // for functions
     branch_has_jumped: bool,
+    requires_main: bool,
+    block_externs: Vec<MlirVariable>, // declared by a block scope 'extern' only
 }
 
 impl Analyzer {
@@ -75,25 +85,64 @@ impl Analyzer {
             scope: Box::new(RefCell::new(SymbolResolver::create_root())),
             reporter: SharedReporter::default(),
             return_ty: None,
+            varargs_last_param: None,
             loop_label_stack: VecDeque::new(),
+            break_label_stack: VecDeque::new(),
+            switch_stack: VecDeque::new(),
+            labels: HashMap::new(),
             branch_has_jumped: false,
+            requires_main: true,
+            block_externs: Vec::new(),
         }
     }
 
+    /// A file compiled together with others can leave main to one of them.
+    pub fn with_main_required(mut self, requires_main: bool) -> Self {
+        self.requires_main = requires_main;
+        self
+    }
+
+    /// Declares the functions of the C library headers the source included.
+    pub fn with_library_headers(self, headers: &[&LibcHeader]) -> Self {
+        for header in headers {
+            self.scope.borrow_mut().include_library_header(header);
+        }
+        self
+    }
+
+    pub fn reporter(&self) -> SharedReporter {
+        self.reporter.clone()
+    }
+
     pub fn validate(mut self) -> Result<MlirModule, SharedReporter> {
         let mut globals = Vec::new();
+        let mut external_globals: Vec<MlirVariable> = Vec::new();
         let mut functions = Vec::new();
         let mut structs = Vec::new();
         let ast = self.ast.take().expect("Ast must be Some(T)");
         for node in &*ast {
             use crate::data::ast::InitDeclaration::*;
             match node {
+                Declaration(locatable_variable)
+                    if locatable_variable.declaration.specifier.is_typedef() =>
+                {
+                    let _ = self.validate_typedef(locatable_variable);
+                }
+                Declaration(locatable_variable)
+                    if locatable_variable.declaration.ident.is_none() =>
+                {
+                    let _ = self.validate_empty_declaration(locatable_variable);
+                }
                 Declaration(locatable_variable) => {
-                    if let Ok(mut var) = self.validate_variable_declaration(locatable_variable) {
-                        self.add_variable_to_scope(&mut var, locatable_variable.location);
-                        globals.push(var);
+                    match self.validate_global_variable(locatable_variable) {
+                        Ok((var, true)) => globals.push(var),
+                        Ok((var, false)) => external_globals.push(var),
+                        Err(()) => {}
                     }
                 }
+                Function(locatable_function) if locatable_function.body.is_none() => {
+                    let _ = self.validate_function_prototype(locatable_function);
+                }
                 Function(locatable_function) => {
                     if let Ok(func) = self.validate_function_definition(locatable_function) {
                         functions.push(func);
@@ -115,14 +164,25 @@ impl Analyzer {
 
         let main_exists = functions.iter().any(|f| f.ident.as_ref() == "main");
 
-        if self.reporter.borrow().status().is_ok() && !main_exists {
+        if self.reporter.borrow().status().is_ok() && !main_exists && self.requires_main {
             self.report_error(CompilerError::MissingMain);
         }
 
+        // an extern declaration only refers to another unit when nothing here defines it
+        external_globals.append(&mut self.block_externs);
+        let mut declared = HashSet::new();
+        external_globals.retain(|var| {
+            !globals.iter().any(|global| global.uid == var.uid) && declared.insert(var.uid)
+        });
+
+        let external_functions = self.scope.borrow().get_undefined_functions();
+
         let mlir = MlirModule {
             functions,
             structs,
             globals,
+            external_globals,
+            external_functions,
         };
 
         if display_mlir() {
@@ -140,6 +200,15 @@ impl Analyzer {
         }
     }
 
+    /// A typedef of a pointer can be pointed to again, so the depths add up.
+    fn pointer_to(&mut self, ty: MlirType, depth: u8, span: Span) -> Result<MlirType, ()> {
+        let Some(ty) = ty.pointer_to(depth) else {
+            err!(self, PointerDepthExceeded, span);
+            return Err(());
+        };
+        Ok(ty)
+    }
+
     fn report_error(&mut self, error: CompilerError) -> Result<(), ()> {
         self.reporter.0.borrow_mut().report_error(error);
         Err(())
@@ -204,140 +273,170 @@ impl Analyzer {
         is_function_return_ty: bool,
         is_struct_dec: bool,
     ) -> Result<MlirType, ()> {
-        #[derive(Debug, PartialEq)]
-        enum State {
-            Start,
-            SeenUnsigned,
-            SeenSigned,
-            End,
+        let mut typedef_decl = MlirTypeDecl::Basic;
+        let mut tag_is_union = None;
+
+        // C allows the integer specifiers in any order, so count them and check the
+        // combination afterwards: 'int long unsigned' is the same as 'unsigned long int'
+        let mut is_unsigned: Option<bool> = None;
+        let (mut chars, mut shorts, mut ints, mut longs) = (0, 0, 0, 0);
+        let mut other = None;
+        for ty_spec in &declaration.ty {
+            match ty_spec {
+                TypeSpecifier::Signed | TypeSpecifier::Unsigned => {
+                    let unsigned = matches!(ty_spec, TypeSpecifier::Unsigned);
+                    let err = match is_unsigned {
+                        None => {
+                            is_unsigned = Some(unsigned);
+                            continue;
+                        }
+                        Some(seen) if seen != unsigned => {
+                            CompilerError::CannotCombineSignedAndUnsigned(location)
+                        }
+                        Some(_) => CompilerError::InvalidTypeSpecifier(location),
+                    };
+                    self.report_error(err);
+                    return Err(());
+                }
+                TypeSpecifier::Char => chars += 1,
+                TypeSpecifier::Short => shorts += 1,
+                TypeSpecifier::Int => ints += 1,
+                TypeSpecifier::Long => longs += 1,
+                ty_spec if other.is_none() => other = Some(ty_spec),
+                _ => {
+                    self.report_error(CompilerError::InvalidTypeSpecifier(location));
+                    return Err(());
+                }
+            }
         }
-        let mut hlir_type: Option<MlirTypeKind> = None;
-        let mut state = State::Start;
-        let mut iter = declaration.ty.iter();
-        macro_rules! seen_signed_or_unsigned {
-            ($ty_spec:ident, $unsigned:literal) => {
-                match $ty_spec {
-                    Some(TypeSpecifier::Char) => {
-                        hlir_type = Some(MlirTypeKind::Char($unsigned));
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Int) => {
-                        hlir_type = Some(MlirTypeKind::Int($unsigned));
-                        state = State::End;
+
+        let unsigned = is_unsigned.unwrap_or(false);
+        let ty_kind = match (other, chars, shorts, ints, longs) {
+            (Some(ty_spec), 0, 0, 0, 0) => {
+                if is_unsigned.is_some() {
+                    let err =
+                        CompilerError::TypeCannotBeSignedOrUnsigned(ty_spec.to_string(), location);
+                    self.report_error(err);
+                    return Err(());
+                }
+                match ty_spec {
+                    TypeSpecifier::Void => MlirTypeKind::Void,
+                    TypeSpecifier::Float => MlirTypeKind::Float,
+                    TypeSpecifier::Double => MlirTypeKind::Double,
+                    TypeSpecifier::VaList => MlirTypeKind::VaList,
+                    TypeSpecifier::Struct(ident) => {
+                        tag_is_union = Some(false);
+                        MlirTypeKind::Struct(ident.clone())
                     }
-                    Some(TypeSpecifier::Long) => {
-                        hlir_type = Some(MlirTypeKind::Long($unsigned));
-                        state = State::End;
+                    TypeSpecifier::Union(ident) => {
+                        tag_is_union = Some(true);
+                        MlirTypeKind::Struct(ident.clone())
                     }
-                    Some(ty) => {
-                        let err =
-                            CompilerError::TypeCannotBeSignedOrUnsigned(ty.to_string(), location);
-                        self.report_error(err);
-                        return Err(());
+                    TypeSpecifier::Enum(ident, enumerators) => {
+                        self.validate_enum_specifier(
+                            ident.as_ref(),
+                            enumerators.as_ref(),
+                            location,
+                        )?;
+                        MlirTypeKind::Int(false)
                     }
-                    None => {
-                        let err = CompilerError::ExpectedTypeSpecifier(location);
-                        self.report_error(err);
-                        return Err(());
+                    TypeSpecifier::TypedefName(ident) => {
+                        let result = self.scope.borrow().get_typedef(ident, location);
+                        match result {
+                            Ok(ty) => {
+                                typedef_decl = ty.decl;
+                                ty.kind
+                            }
+                            Err(err) => {
+                                self.report_error(err);
+                                return Err(());
+                            }
+                        }
                     }
+                    _ => unreachable!("integer specifiers are counted above"),
                 }
-            };
-        }
+            }
+            (None, 0, 0, 0, 0) if is_unsigned.is_none() => {
+                self.report_error(CompilerError::ExpectedTypeSpecifier(location));
+                return Err(());
+            }
+            (None, 1, 0, 0, 0) => MlirTypeKind::Char(unsigned),
+            (None, 0, 1, 0 | 1, 0) => MlirTypeKind::Short(unsigned),
+            (None, 0, 0, 0 | 1, 0) => MlirTypeKind::Int(unsigned),
+            // 'long long' is as wide as 'long' on every supported target
+            (None, 0, 0, 0 | 1, 1 | 2) => MlirTypeKind::Long(unsigned),
+            _ => {
+                self.report_error(CompilerError::InvalidTypeSpecifier(location));
+                return Err(());
+            }
+        };
 
-        loop {
-            let mut ty_spec = if state != State::End {
-                iter.next()
-            } else {
-                None
+        if let MlirTypeKind::Struct(ident) = &ty_kind {
+            let by_value = declaration.pointer_depth == 0
+                && !matches!(typedef_decl, MlirTypeDecl::Pointer(_))
+                && declaration.function_pointer.is_none()
+                && declaration.array_pointer.is_none()
+                && declaration.parameter_array.is_none();
+            let result = match tag_is_union {
+                // naming a tag declares it, the struct is incomplete until it is defined
+                Some(is_union) if is_struct_dec || !by_value => self
+                    .scope
+                    .borrow_mut()
+                    .declare_struct(ident, is_union, location),
+                _ => self.scope.borrow_mut().check_struct_exists(
+                    ident,
+                    tag_is_union,
+                    by_value && !is_struct_dec,
+                    location,
+                ),
             };
-
-            match state {
-                State::Start => match ty_spec {
-                    Some(TypeSpecifier::Void) => {
-                        hlir_type = Some(MlirTypeKind::Void);
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Char) => {
-                        hlir_type = Some(MlirTypeKind::Char(false));
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Int) => {
-                        hlir_type = Some(MlirTypeKind::Int(false));
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Long) => {
-                        hlir_type = Some(MlirTypeKind::Long(false));
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Double) => {
-                        hlir_type = Some(MlirTypeKind::Double);
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Struct(ident)) => {
-                        hlir_type = Some(MlirTypeKind::Struct(ident.clone()));
-                        state = State::End;
-                    }
-                    Some(TypeSpecifier::Signed) => {
-                        state = State::SeenSigned;
-                    }
-                    Some(TypeSpecifier::Unsigned) => {
-                        state = State::SeenUnsigned;
-                    }
-                    None => panic!("Span: {:?}", location),
-                },
-                State::SeenUnsigned => seen_signed_or_unsigned!(ty_spec, true),
-                State::SeenSigned => seen_signed_or_unsigned!(ty_spec, false),
-                State::End => break,
+            if let Err(err) = result {
+                self.report_error(err);
+                return Err(());
             }
         }
-        debug_assert!(hlir_type.is_some());
-        if iter.next().is_some() {
-            let err = CompilerError::InvalidTypeSpecifier(location);
-            self.report_error(err);
-            return Err(());
-        }
-        let ty_kind = hlir_type.unwrap();
 
-        match &ty_kind {
-            MlirTypeKind::Struct(ident) if !is_struct_dec => {
-                let result = self.scope.borrow_mut().check_struct_exists(ident, location);
-                if let Err(err) = result {
-                    self.report_error(err);
-                    return Err(());
-                }
-            }
-            _ => (),
+        let mut ty = MlirType::new(ty_kind, typedef_decl);
+        if declaration.pointer_depth > 0 {
+            ty = self.pointer_to(ty, declaration.pointer_depth, location)?;
         }
 
-        let ty_dec = if declaration.pointer {
-            MlirTypeDecl::Pointer
-        } else {
-            MlirTypeDecl::Basic
-        };
+        if let Some(declarator) = &declaration.function_pointer {
+            return self.validate_function_pointer_type(ty, declarator, location);
+        }
+        if let Some(declarator) = &declaration.array_pointer {
+            ty = self.validate_array_dimensions(ty, &declarator.array_sizes, location)?;
+            ty = self.pointer_to(ty, declarator.pointer_depth, location)?;
+        }
+        if let Some(declarator) = &declaration.parameter_array {
+            // like C, a parameter declared as an array is a pointer to its first element
+            ty = self.validate_array_dimensions(ty, &declarator.inner_array_sizes, location)?;
+            ty = self.pointer_to(ty, 1, location)?;
+        }
 
         if !is_function_return_ty
-            && matches!(ty_kind, MlirTypeKind::Void)
-            && !matches!(ty_dec, MlirTypeDecl::Pointer)
+            && matches!(ty.kind, MlirTypeKind::Void)
+            && !matches!(ty.decl, MlirTypeDecl::Pointer(_))
         {
             self.report_error(CompilerError::IncompleteType(location));
             return Err(());
         }
 
-        Ok(MlirType {
-            kind: ty_kind,
-            decl: ty_dec,
-        })
+        Ok(ty)
     }
 }
 
 #[cfg(test)]
 macro_rules! make_dec_specifier {
-    ($types:expr, $is_ptr:expr) => {
+    ($types:expr, $pointer_depth:expr) => {
         DeclarationSpecifier {
             specifiers: vec![],
             qualifiers: vec![],
             ty: $types,
-            pointer: $is_ptr,
+            pointer_depth: $pointer_depth,
+            function_pointer: None,
+            array_pointer: None,
+            parameter_array: None,
         }
     };
 }
@@ -354,10 +453,15 @@ fn test_validate_type_returns_error_for_invalid_type_orientations() {
         vec![Signed, Unsigned, Long],
         vec![Signed, Double],
         vec![Void],
+        vec![Long, Long, Long],
+        vec![Short, Long],
+        vec![Short, Char],
+        vec![Unsigned, Unsigned, Int],
+        vec![Int, Double],
     ];
     for types in type_tests {
         let mut validator = Analyzer::new(AbstractSyntaxTree::default());
-        let types = make_dec_specifier!(types, false);
+        let types = make_dec_specifier!(types, 0);
         let result = validator.validate_type(&types, Span::default(), false, false);
         if result.is_ok() {
             panic!("Expected error, got ok, test: {:?}", types);
@@ -392,11 +496,39 @@ fn test_validate_type_returns_ok_for_valid_type_orientations() {
             MlirTypeDecl::Basic,
         ),
         (vec![Double], MlirTypeKind::Double, MlirTypeDecl::Basic),
-        (vec![Void], MlirTypeKind::Void, MlirTypeDecl::Pointer),
+        (vec![Short], MlirTypeKind::Short(false), MlirTypeDecl::Basic),
+        (
+            vec![Unsigned, Short, Int],
+            MlirTypeKind::Short(true),
+            MlirTypeDecl::Basic,
+        ),
+        (
+            vec![Long, Long],
+            MlirTypeKind::Long(false),
+            MlirTypeDecl::Basic,
+        ),
+        (
+            vec![Long, Int],
+            MlirTypeKind::Long(false),
+            MlirTypeDecl::Basic,
+        ),
+        (
+            vec![Int, Long, Unsigned],
+            MlirTypeKind::Long(true),
+            MlirTypeDecl::Basic,
+        ),
+        (vec![Unsigned], MlirTypeKind::Int(true), MlirTypeDecl::Basic),
+        (vec![Void], MlirTypeKind::Void, MlirTypeDecl::Pointer(1)),
+        (vec![Char], MlirTypeKind::Char(false), MlirTypeDecl::Pointer(2)),
+        (vec![Void], MlirTypeKind::Void, MlirTypeDecl::Pointer(3)),
     ];
     for (types, expected, decl) in type_tests {
         let mut validator = Analyzer::new(AbstractSyntaxTree::default());
-        let dec_spec = make_dec_specifier!(types, decl == MlirTypeDecl::Pointer);
+        let pointer_depth = match decl {
+            MlirTypeDecl::Pointer(depth) => depth,
+            _ => 0,
+        };
+        let dec_spec = make_dec_specifier!(types, pointer_depth);
         let expected = MlirType {
             decl,
             kind: expected,
@@ -409,10 +541,12 @@ fn test_validate_type_returns_ok_for_valid_type_orientations() {
 #[cfg(test)]
 mod tests {
     use crate::analysis::SharedReporter;
+    use crate::data::error::CompilerWarning;
     use crate::data::mlir::MlirModule;
     use crate::{analysis, lexer, parser};
 
-    pub(in crate::analysis) fn run_analysis_test(path: &str) -> Result<MlirModule, SharedReporter> {
+    fn analyzer_for(path: &str) -> analysis::Analyzer {
+        crate::tests::init_args();
         let source = std::fs::read_to_string(path).expect("Could not read file.");
         let lexer = lexer::Lexer::new(source.into())
             .lex_all()
@@ -420,6 +554,67 @@ mod tests {
             .into_iter();
         let parser = parser::Parser::new(lexer);
         let result = parser.parse_all().expect("Error in Parser.");
-        analysis::Analyzer::new(result).validate()
+        analysis::Analyzer::new(result)
+    }
+
+    pub(in crate::analysis) fn run_analysis_test(path: &str) -> Result<MlirModule, SharedReporter> {
+        analyzer_for(path).validate()
+    }
+
+    /// The names of the warnings reported for a file that has to pass the analysis.
+    fn run_warnings_test(path: &str) -> Vec<&'static str> {
+        let analyzer = analyzer_for(path);
+        let reporter = analyzer.reporter();
+        assert!(
+            analyzer.validate().is_ok(),
+            "'{path}' did not pass the analysis"
+        );
+        let reporter = reporter.borrow();
+        reporter
+            .warnings()
+            .iter()
+            .map(CompilerWarning::name)
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_enum_value_warning() {
+        let warnings = run_warnings_test("_c_test_files/should_succeed/duplicate_enum_value.c");
+        let duplicates = warnings
+            .iter()
+            .filter(|name| **name == "duplicate-enum-value")
+            .count();
+        assert_eq!(duplicates, 2, "{warnings:?}");
+    }
+
+    #[test]
+    fn pointer_sign_warning() {
+        let warnings = run_warnings_test("_c_test_files/should_succeed/char_pointer_sign.c");
+        let pointer_signs = warnings
+            .iter()
+            .filter(|name| **name == "pointer-sign")
+            .count();
+        assert_eq!(pointer_signs, 3, "{warnings:?}");
+        assert_eq!(
+            first_error_code("_c_test_files/should_fail/char_pointer_depth.c"),
+            "E0085"
+        );
+    }
+
+    /// The first error code of a file that has to fail the analysis.
+    fn first_error_code(path: &str) -> &'static str {
+        let Err(reporter) = run_analysis_test(path) else {
+            panic!("'{path}' passed the analysis");
+        };
+        let reporter = reporter.borrow();
+        reporter.errors[0].code()
+    }
+
+    #[test]
+    fn storage_specifier_errors() {
+        let codes = ["local_static", "block_extern_mismatch", "static_parameter"]
+            .map(|name| first_error_code(&format!("_c_test_files/should_fail/{name}.c")));
+        // 'static' locals, a type that does not match the file scope variable and parameters
+        assert_eq!(codes, ["E0123", "E0113", "E0063"]);
     }
 }
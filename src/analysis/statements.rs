@@ -1,13 +1,40 @@
+use crate::analysis::constants::{evaluate_integer_constant, wrap_to_type};
 use crate::analysis::Analyzer;
-use crate::data::ast::{Block, Expression, Statement, VariableDeclaration};
-use crate::data::error::CompilerError;
-use crate::data::mlir::{MlirBlock, MlirExpr, MlirStmt, SIGNED_INT_TYPE, VOID_TYPE};
+use crate::data::ast::{Block, Expression, Statement, StorageSpecifier, VariableDeclaration};
+use crate::data::error::{CompilerError, CompilerWarning};
+use crate::data::mlir::{MlirBlock, MlirExpr, MlirStmt, MlirType, SIGNED_INT_TYPE, VOID_TYPE};
+use crate::util::str_intern::InternedStr;
 use crate::util::{str_intern, Locatable, Span};
 
+/// The case labels found so far in the body of a switch statement.
+pub(super) struct SwitchContext {
+    label: InternedStr,
+    ty: MlirType,
+    cases: Vec<(i64, InternedStr)>,
+    default: Option<InternedStr>,
+}
+
+/// A source level label, which can be jumped to from anywhere in its function.
+#[derive(Default)]
+pub(super) struct LabelContext {
+    defined: Option<Span>,
+    used: Option<Span>, // the first goto that jumps to this label
+}
+
+/// Source level labels are prefixed so they never collide with the generated ones.
+fn user_label(ident: &InternedStr) -> InternedStr {
+    str_intern::intern(format!("label_{}", ident))
+}
+
 impl Analyzer {
-    #[inline(always)]
-    fn validate_conditional(&mut self, expression: &Expression) -> Result<MlirExpr, ()> {
+    /// A condition is true when it compares unequal to zero, so a scalar keeps its own type and
+    /// is compared with a zero of that type.
+    pub(super) fn validate_conditional(&mut self, expression: &Expression) -> Result<MlirExpr, ()> {
         let cond = self.validate_expression(expression)?;
+        let cond = self.decay_array(cond)?;
+        if cond.ty.is_numeric() || cond.is_pointer() || cond.ty.is_function_pointer() {
+            return Ok(cond);
+        }
         let cond_span = cond.span;
         Ok(self.implicit_cast(cond, SIGNED_INT_TYPE, cond_span))
     }
@@ -18,9 +45,13 @@ impl Analyzer {
         let mut branch_has_jumped = false;
         for raw_stmt in &block.0 {
             if let Some(stmt) = self.validate_statement(raw_stmt)? {
+                // jump may have unreachable code after the jump, unless it can be jumped into
+                branch_has_jumped = if contains_label(&stmt) {
+                    self.branch_has_jumped
+                } else {
+                    self.branch_has_jumped || branch_has_jumped
+                };
                 statements.push(stmt);
-                // jump may have unreachable code after the jump
-                branch_has_jumped = self.branch_has_jumped || branch_has_jumped;
             }
         }
         self.branch_has_jumped = branch_has_jumped;
@@ -45,9 +76,19 @@ impl Analyzer {
             Statement::While(condition, body) => {
                 self.validate_while_loop_statement(condition, body)
             }
+            Statement::DoWhile(body, condition) => {
+                self.validate_do_while_loop_statement(body, condition)
+            }
             Statement::For(initializer, condition, post_loop, body) => {
                 self.validate_for_loop(initializer, condition, post_loop, body)
             }
+            Statement::Switch(controlling, body) => {
+                self.validate_switch_statement(controlling, body)
+            }
+            Statement::Case(value) => self.validate_case_label(value, stmt.location),
+            Statement::Default => self.validate_default_label(stmt.location),
+            Statement::Label(ident) => self.validate_label(ident, stmt.location),
+            Statement::Goto(ident) => self.validate_goto_statement(ident, stmt.location),
             Statement::Block(block) => Ok(Some(MlirStmt::Block(self.validate_block(block)?))),
             Statement::Return(value) => self.validate_return_statement(value, stmt.location),
             Statement::Continue => self.validate_continue_statement(stmt.location),
@@ -59,8 +100,7 @@ impl Analyzer {
     fn validate_continue_statement(&mut self, span: Span) -> Result<Option<MlirStmt>, ()> {
         self.branch_has_jumped = true;
         self.loop_label_stack
-            .iter()
-            .last()
+            .front()
             .map(|label| Some(MlirStmt::Goto(label.clone())))
             .ok_or_else(|| {
                 self.report_error(CompilerError::ContinueWithoutLoop(span));
@@ -69,25 +109,205 @@ impl Analyzer {
 
     fn validate_break_statement(&mut self, span: Span) -> Result<Option<MlirStmt>, ()> {
         self.branch_has_jumped = true;
-        self.loop_label_stack
-            .iter()
-            .last()
-            .map(|label| Some(MlirStmt::Goto(str_intern::intern(format!("{}_end", label)))))
+        self.break_label_stack
+            .front()
+            .map(|label| Some(MlirStmt::Goto(label.clone())))
             .ok_or_else(|| {
                 self.report_error(CompilerError::BreakWithoutLoop(span));
             })
     }
 
+    fn validate_label(&mut self, ident: &InternedStr, span: Span) -> Result<Option<MlirStmt>, ()> {
+        let label = self.labels.entry(ident.clone()).or_default();
+        if let Some(previous) = label.defined {
+            self.report_error(CompilerError::DuplicateLabel(
+                ident.to_string(),
+                span,
+                Some(previous),
+            ));
+            return Err(());
+        }
+        label.defined = Some(span);
+
+        self.branch_has_jumped = false;
+        Ok(Some(MlirStmt::Label(user_label(ident))))
+    }
+
+    fn validate_goto_statement(
+        &mut self,
+        ident: &InternedStr,
+        span: Span,
+    ) -> Result<Option<MlirStmt>, ()> {
+        self.branch_has_jumped = true;
+        let label = self.labels.entry(ident.clone()).or_default();
+        label.used.get_or_insert(span);
+        Ok(Some(MlirStmt::Goto(user_label(ident))))
+    }
+
+    /// Labels are only known once the whole function body has been seen,
+    /// since a goto may jump forward to a label defined after it.
+    pub(super) fn validate_labels(&mut self) -> Result<(), ()> {
+        let mut labels = std::mem::take(&mut self.labels)
+            .into_iter()
+            .collect::<Vec<_>>();
+        labels.sort_by_key(|(_, label)| label.defined.or(label.used).map(|span| span.start));
+
+        let mut result = Ok(());
+        for (ident, label) in labels {
+            match (label.defined, label.used) {
+                (None, Some(used)) => {
+                    self.report_error(CompilerError::UndefinedLabel(ident.to_string(), used));
+                    result = Err(());
+                }
+                (Some(defined), None) => {
+                    self.report_warning(CompilerWarning::UnusedLabel(defined));
+                }
+                _ => (),
+            }
+        }
+        result
+    }
+
+    fn validate_switch_statement(
+        &mut self,
+        controlling: &Locatable<Expression>,
+        body: &Locatable<Statement>,
+    ) -> Result<Option<MlirStmt>, ()> {
+        let value = self.validate_expression(controlling)?;
+        if !value.ty.is_basic() || !value.ty.is_integer() {
+            self.report_error(CompilerError::SwitchRequiresInteger(
+                value.ty.to_string(),
+                controlling.location,
+            ));
+            return Err(());
+        }
+
+        let label = str_intern::intern(format!("switch_{}", super::create_label()));
+        let end_label = str_intern::intern(format!("{}_end", label));
+
+        self.switch_stack.push_front(SwitchContext {
+            label,
+            ty: value.ty.clone(),
+            cases: Vec::new(),
+            default: None,
+        });
+        self.break_label_stack.push_front(end_label.clone());
+
+        let body = self.validate_statement(body);
+
+        self.break_label_stack.pop_front();
+        let context = self.switch_stack.pop_front().unwrap();
+
+        let body = body?;
+
+        // the end of the switch is only reachable without a default label,
+        // by breaking out of the body, or by falling off the end of it
+        let end_is_reachable = context.default.is_none()
+            || body.as_ref().is_none_or(|body| {
+                !ends_with_jump(body) || jumps_to_label(body, &end_label)
+            });
+
+        // without a default label, unmatched values skip the body entirely
+        let default = context.default.unwrap_or_else(|| end_label.clone());
+        let mut block = vec![MlirStmt::Switch(value, context.cases, default)];
+        if let Some(body) = body {
+            block.push(body);
+        }
+        if end_is_reachable {
+            block.push(MlirStmt::Label(end_label));
+        }
+
+        self.branch_has_jumped = !end_is_reachable;
+
+        Ok(Some(MlirStmt::Block(MlirBlock(block))))
+    }
+
+    fn validate_case_label(
+        &mut self,
+        value: &Locatable<Expression>,
+        span: Span,
+    ) -> Result<Option<MlirStmt>, ()> {
+        let Some(switch_ty) = self.switch_stack.front().map(|context| context.ty.clone()) else {
+            self.report_error(CompilerError::CaseWithoutSwitch("case".to_string(), span));
+            return Err(());
+        };
+
+        let expr = self.validate_expression(value)?;
+        let Some(constant) = evaluate_integer_constant(&expr) else {
+            self.report_error(CompilerError::CaseRequiresConstant(value.location));
+            return Err(());
+        };
+        let constant = wrap_to_type(constant, &switch_ty);
+
+        let context = self.switch_stack.front_mut().unwrap();
+        if context.cases.iter().any(|(case, _)| *case == constant) {
+            self.report_error(CompilerError::DuplicateCaseValue(constant, span));
+            return Err(());
+        }
+        let label = str_intern::intern(format!("{}_case_{}", context.label, context.cases.len()));
+        context.cases.push((constant, label.clone()));
+
+        self.branch_has_jumped = false;
+        Ok(Some(MlirStmt::Label(label)))
+    }
+
+    fn validate_default_label(&mut self, span: Span) -> Result<Option<MlirStmt>, ()> {
+        let Some(context) = self.switch_stack.front_mut() else {
+            self.report_error(CompilerError::CaseWithoutSwitch("default".to_string(), span));
+            return Err(());
+        };
+        if context.default.is_some() {
+            self.report_error(CompilerError::DuplicateDefaultLabel(span));
+            return Err(());
+        }
+        let label = str_intern::intern(format!("{}_default", context.label));
+        context.default = Some(label.clone());
+
+        self.branch_has_jumped = false;
+        Ok(Some(MlirStmt::Label(label)))
+    }
+
     fn validate_variable_declaration_statement(
         &mut self,
         var_dec: &Locatable<VariableDeclaration>,
     ) -> Result<Option<MlirStmt>, ()> {
+        if var_dec.declaration.specifier.is_typedef() {
+            self.validate_typedef(var_dec)?;
+            return Ok(None);
+        }
+        if var_dec.declaration.ident.is_none() {
+            self.validate_empty_declaration(var_dec)?;
+            return Ok(None);
+        }
+        if self.validate_local_storage(var_dec)? == Some(StorageSpecifier::Extern) {
+            self.validate_block_extern(var_dec)?;
+            return Ok(None);
+        }
         let span = var_dec.location;
         let mut var_dec = self.validate_variable_declaration(var_dec)?;
         self.add_variable_to_scope(&mut var_dec, span)?;
         Ok(Some(MlirStmt::VariableDeclaration(var_dec)))
     }
 
+    /// Local variables are always automatic, a block scope `extern` refers to a file scope
+    /// variable and `static` is only handled at file scope.
+    fn validate_local_storage(
+        &mut self,
+        var_dec: &Locatable<VariableDeclaration>,
+    ) -> Result<Option<StorageSpecifier>, ()> {
+        let specifiers = &var_dec.declaration.specifier.specifiers;
+        match self.validate_storage_specifier(specifiers, var_dec.location)? {
+            Some(StorageSpecifier::Static) => {
+                self.report_error(CompilerError::LocalStorageSpecifier(
+                    StorageSpecifier::Static.to_string(),
+                    var_dec.location,
+                ));
+                Err(())
+            }
+            specifier => Ok(specifier),
+        }
+    }
+
     fn validate_if_statement(
         &mut self,
         condition: &Locatable<Expression>,
@@ -119,6 +339,8 @@ impl Analyzer {
 
         block.push(MlirStmt::Label(else_label));
 
+        // without an else branch the end of the statement is reached through the else label
+        self.branch_has_jumped = false;
         if let Some(otherwise) = otherwise {
             if let Some(otherwise) = self.validate_statement(otherwise)? {
                 block.push(otherwise);
@@ -147,6 +369,7 @@ impl Analyzer {
         let label_string_end = str_intern::intern(format!("{}_end", label_string));
 
         self.loop_label_stack.push_front(label_string.clone());
+        self.break_label_stack.push_front(label_string_end.clone());
         let label = MlirStmt::Label(label_string.clone());
         block.push(label);
 
@@ -172,7 +395,48 @@ impl Analyzer {
         block.push(end_label);
 
         self.loop_label_stack.pop_front();
+        self.break_label_stack.pop_front();
         self.pop_scope();
+        // the body jumping doesn't mean the code after the loop is unreachable
+        self.branch_has_jumped = false;
+
+        Ok(Some(MlirStmt::Block(MlirBlock(block))))
+    }
+
+    fn validate_do_while_loop_statement(
+        &mut self,
+        body: &Locatable<Statement>,
+        condition: &Locatable<Expression>,
+    ) -> Result<Option<MlirStmt>, ()> {
+        self.push_scope();
+
+        let mut block = Vec::new();
+        let label_string = str_intern::intern(format!("loop_{}", super::create_label()));
+        let label_string_cond = str_intern::intern(format!("{}_cond", label_string));
+        let label_string_end = str_intern::intern(format!("{}_end", label_string));
+
+        // continue still has to check the condition before starting the next iteration
+        self.loop_label_stack.push_front(label_string_cond.clone());
+        self.break_label_stack.push_front(label_string_end.clone());
+        block.push(MlirStmt::Label(label_string.clone()));
+
+        if let Some(body) = self.validate_statement(body)? {
+            block.push(body);
+        }
+
+        block.push(MlirStmt::Label(label_string_cond));
+
+        let condition = self.validate_conditional(condition)?;
+        let branch = MlirStmt::CondGoto(condition, label_string, label_string_end.clone());
+        block.push(branch);
+
+        block.push(MlirStmt::Label(label_string_end));
+
+        self.loop_label_stack.pop_front();
+        self.break_label_stack.pop_front();
+        self.pop_scope();
+        // the body jumping doesn't mean the code after the loop is unreachable
+        self.branch_has_jumped = false;
 
         Ok(Some(MlirStmt::Block(MlirBlock(block))))
     }
@@ -212,17 +476,27 @@ impl Analyzer {
         self.push_scope();
         let loop_start_label = str_intern::intern(format!("loop_{}", super::create_label()));
         let loop_body_label = str_intern::intern(format!("{}_body", loop_start_label));
+        let loop_post_label = str_intern::intern(format!("{}_post", loop_start_label));
         let loop_end_label = str_intern::intern(format!("{}_end", loop_start_label));
         let mut block = Vec::new();
 
         if let Some(initializer) = initializer {
+            if let Some(specifier) = self.validate_local_storage(initializer)? {
+                self.report_error(CompilerError::CustomError(
+                    format!("The declaration of a 'for' loop cannot be '{specifier}'."),
+                    initializer.location,
+                ));
+                return Err(());
+            }
             let mut var_dec = self.validate_variable_declaration(initializer)?;
             self.add_variable_to_scope(&mut var_dec, initializer.location);
             let var_stmt = MlirStmt::VariableDeclaration(var_dec);
             block.push(var_stmt);
         }
 
-        self.loop_label_stack.push_front(loop_start_label.clone());
+        // continue has to run the post loop expression before checking the condition again
+        self.loop_label_stack.push_front(loop_post_label.clone());
+        self.break_label_stack.push_front(loop_end_label.clone());
         block.push(MlirStmt::Label(loop_start_label.clone()));
 
         if let Some(condition) = condition {
@@ -238,6 +512,8 @@ impl Analyzer {
             block.push(body);
         }
 
+        block.push(MlirStmt::Label(loop_post_label));
+
         if let Some(post_loop) = post_loop {
             let post_loop = self.validate_expression(post_loop)?;
             let post_loop_stmt = MlirStmt::Expression(post_loop);
@@ -252,7 +528,47 @@ impl Analyzer {
 
         self.pop_scope();
         self.loop_label_stack.pop_front();
+        self.break_label_stack.pop_front();
+        self.branch_has_jumped = false;
 
         Ok(Some(MlirStmt::Block(MlirBlock(block))))
     }
 }
+
+fn ends_with_jump(stmt: &MlirStmt) -> bool {
+    match stmt {
+        MlirStmt::Block(block) => block.0.last().is_some_and(ends_with_jump),
+        MlirStmt::Goto(_)
+        | MlirStmt::CondGoto(_, _, _)
+        | MlirStmt::Switch(_, _, _)
+        | MlirStmt::Return(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether a label is nested anywhere in the statement, if, switch and loop bodies
+/// are all lowered to blocks.
+fn contains_label(stmt: &MlirStmt) -> bool {
+    match stmt {
+        MlirStmt::Block(block) => block.0.iter().any(contains_label),
+        MlirStmt::Label(_) => true,
+        MlirStmt::Expression(_)
+        | MlirStmt::VariableDeclaration(_)
+        | MlirStmt::Goto(_)
+        | MlirStmt::CondGoto(_, _, _)
+        | MlirStmt::Switch(_, _, _)
+        | MlirStmt::Return(_) => false,
+    }
+}
+
+fn jumps_to_label(stmt: &MlirStmt, label: &InternedStr) -> bool {
+    match stmt {
+        MlirStmt::Block(block) => block.0.iter().any(|stmt| jumps_to_label(stmt, label)),
+        MlirStmt::Goto(target) => target == label,
+        MlirStmt::CondGoto(_, then, _else) => then == label || _else == label,
+        MlirStmt::Switch(_, cases, default) => {
+            default == label || cases.iter().any(|(_, case)| case == label)
+        }
+        _ => false,
+    }
+}
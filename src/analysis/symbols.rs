@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::data::error::CompilerError;
+use crate::data::libc::LibcHeader;
 use crate::data::mlir::{
     MlirExpr, MlirExprKind, MlirLiteral, MlirStruct, MlirType, MlirTypeDecl, MlirTypeKind,
     MlirVariable,
@@ -27,63 +28,210 @@ pub(crate) enum SymbolKind {
     Function(FunctionSymbol),
     Struct(StructSymbol),
     Variable(VariableSymbol),
+    Enumerator(i32),
 }
 
 #[derive(Default, Debug)]
 pub struct SymbolResolver {
     un_accessed_items: HashMap<InternedStr, Span>,
+    declared_at: HashMap<InternedStr, Span>,
+    typedefs: HashMap<InternedStr, (MlirType, Span)>,
+    enum_tags: HashMap<InternedStr, Span>,
     pub(super) symbols: HashMap<InternedStr, SymbolKind>,
     pub(super) parent: Option<Box<RefCell<SymbolResolver>>>,
 }
 
 impl SymbolResolver {
     pub fn create_root() -> Self {
-        let mut root = Self {
+        Self {
             un_accessed_items: HashMap::default(),
+            declared_at: HashMap::default(),
+            typedefs: HashMap::default(),
+            enum_tags: HashMap::default(),
             symbols: HashMap::default(),
             parent: None,
-        };
-        root.init_builtins();
-        root
+        }
     }
 
     pub fn remove_self(self) -> Option<Box<RefCell<Self>>> {
         self.parent
     }
 
-    fn init_builtins(&mut self) {
+    /// Makes the functions of an included library header visible.
+    pub(crate) fn include_library_header(&mut self, header: &LibcHeader) {
         debug_assert!(self.parent.is_none());
-        for builtin in BUILTINS.iter() {
-            self.symbols.insert(
-                str_intern::intern(builtin.0),
-                SymbolKind::Function(builtin.1.clone()), // ignore access check on builtins
-            );
+        for function in &header.functions {
+            // ignore access check on library functions
+            self.symbols
+                .entry(function.ident.clone())
+                .or_insert_with(|| SymbolKind::Function(function.clone()));
         }
     }
+
     pub fn new(parent: Option<Box<RefCell<SymbolResolver>>>) -> Self {
         Self {
             un_accessed_items: HashMap::default(),
+            declared_at: HashMap::default(),
+            typedefs: HashMap::default(),
+            enum_tags: HashMap::default(),
             symbols: HashMap::default(),
             parent,
         }
     }
 
-    #[inline]
-    pub fn add_function(
+    /// Adds a function prototype or definition, a definition is a symbol with `is_defined` set.
+    /// Redeclarations must match the existing signature, and a function can only be defined once.
+    /// Returns whether the function is static, which is decided by its first declaration.
+    pub fn declare_function(
         &mut self,
-        ident: &InternedStr,
-        return_ty: MlirType,
-        params: Vec<MlirType>,
+        function: FunctionSymbol,
+        span: Span,
+    ) -> Result<bool, CompilerError> {
+        let ident = function.ident.clone();
+        if let Some(SymbolKind::Function(existing)) = self.symbols.get_mut(&ident) {
+            let previous = self.declared_at.get(&ident).copied();
+            if existing.return_ty != function.return_ty
+                || existing.params != function.params
+                || existing.varargs != function.varargs
+            {
+                return Err(CompilerError::FunctionSignatureMismatch(
+                    ident.to_string(),
+                    span,
+                    previous,
+                ));
+            }
+            if function.is_defined && existing.is_defined {
+                return Err(CompilerError::FunctionRedefinition(
+                    ident.to_string(),
+                    span,
+                    previous,
+                ));
+            }
+            if function.is_static && !existing.is_static {
+                return Err(CompilerError::StaticAfterNonStatic(
+                    ident.to_string(),
+                    span,
+                    previous,
+                ));
+            }
+            if function.is_defined {
+                self.declared_at.insert(ident, span);
+            }
+            existing.is_defined |= function.is_defined;
+            return Ok(existing.is_static);
+        }
+
+        let is_static = function.is_static;
+        self.add_symbol(&ident, SymbolKind::Function(function), span)?;
+        Ok(is_static)
+    }
+
+    /// Adds a file scope variable. An `extern` declaration can be repeated and completed by
+    /// a definition later on, they all share the uid of the first declaration.
+    pub fn declare_global(
+        &mut self,
+        var: &mut MlirVariable,
+        is_definition: bool,
         span: Span,
     ) -> SymbolResult {
-        let symbol = SymbolKind::Function(FunctionSymbol {
-            ident: ident.clone(),
-            location: None,
-            return_ty,
-            varargs: false,
-            params,
+        let ident = var.ident.value.clone();
+        let Some(SymbolKind::Variable(existing)) = self.symbols.get_mut(&ident) else {
+            self.add_variable(var, span)?;
+            if !is_definition {
+                if let Some(SymbolKind::Variable(symbol)) = self.symbols.get_mut(&ident) {
+                    symbol.is_defined = false;
+                }
+                // declarations are not reported as unused, only what is defined here
+                self.un_accessed_items.remove(&ident);
+            }
+            return Ok(());
+        };
+
+        let previous = self.declared_at.get(&ident).copied();
+        if existing.ty != var.ty.value {
+            return Err(CompilerError::VariableSignatureMismatch(
+                ident.to_string(),
+                span,
+                previous,
+            ));
+        }
+        if is_definition && existing.is_defined {
+            return Err(CompilerError::IdentifierExists(span, previous));
+        }
+        if var.is_static && !existing.is_static {
+            return Err(CompilerError::StaticAfterNonStatic(
+                ident.to_string(),
+                span,
+                previous,
+            ));
+        }
+        var.uid = existing.uid;
+        var.is_static = existing.is_static;
+        existing.is_defined |= is_definition;
+        if is_definition {
+            self.declared_at.insert(ident, span);
+        }
+        Ok(())
+    }
+
+    /// Adds a block scope `extern` declaration, which refers to the file scope variable of the same
+    /// name and shares its uid. The file scope declares the variable when it has not yet, so it can
+    /// still be defined later on or in another translation unit. Returns whether it was declared.
+    pub fn declare_block_extern(
+        &mut self,
+        var: &mut MlirVariable,
+        span: Span,
+    ) -> Result<bool, CompilerError> {
+        let ident = var.ident.value.clone();
+        let is_new = match &self.parent {
+            Some(parent) => parent.borrow_mut().declare_file_scope_extern(var, span)?,
+            None => unreachable!("Block scope declarations are never in the file scope."),
+        };
+        let symbol = SymbolKind::Variable(VariableSymbol {
+            uid: var.uid,
+            ty: var.ty.clone(),
+            is_const: var.is_const,
+            is_initialized: true,
+            array_size: match &var.ty.decl {
+                MlirTypeDecl::Array(size) => Some(*size),
+                _ => None,
+            },
+            is_static: var.is_static,
+            is_defined: false,
         });
-        self.add_symbol(ident, symbol, span)
+        self.add_symbol(&ident, symbol, span)?;
+        // like a file scope declaration, it is not reported as unused
+        self.un_accessed_items.remove(&ident);
+        Ok(is_new)
+    }
+
+    fn declare_file_scope_extern(
+        &mut self,
+        var: &mut MlirVariable,
+        span: Span,
+    ) -> Result<bool, CompilerError> {
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().declare_file_scope_extern(var, span),
+            None => {
+                let is_new = !self.symbols.contains_key(&var.ident.value);
+                self.declare_global(var, false, span)?;
+                Ok(is_new)
+            }
+        }
+    }
+
+    /// Prototypes that never received a definition, these are expected to be resolved by the linker.
+    pub fn get_undefined_functions(&self) -> Vec<FunctionSymbol> {
+        let mut functions = self
+            .symbols
+            .values()
+            .filter_map(|symbol| match symbol {
+                SymbolKind::Function(func) if !func.is_defined => Some(func.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|left, right| left.ident.as_ref().cmp(right.ident.as_ref()));
+        functions
     }
 
     pub fn add_variable(
@@ -97,12 +245,16 @@ impl SymbolResolver {
         };
         let uid = update_global_variable_count();
         var.uid = uid;
+        // reads before initialization are only tracked for scalars
+        let is_scalar = !var.ty.is_array() && !matches!(var.ty.kind, MlirTypeKind::Struct(_));
         let symbol = SymbolKind::Variable(VariableSymbol {
             uid,
             ty: var.ty.clone(),
             is_const: var.is_const,
-            is_initialized: var.initializer.is_some(),
+            is_initialized: var.initializer.is_some() || !is_scalar,
             array_size,
+            is_static: var.is_static,
+            is_defined: true,
         });
         self.add_symbol(&var.ident, symbol, span)?;
         Ok(uid)
@@ -111,11 +263,16 @@ impl SymbolResolver {
     #[inline]
     fn add_symbol(&mut self, ident: &InternedStr, kind: SymbolKind, span: Span) -> SymbolResult {
         if self.symbols.contains_key(ident) {
-            Err(CompilerError::IdentifierExists(span))
+            let previous = self.declared_at.get(ident).copied();
+            Err(CompilerError::IdentifierExists(span, previous))
+        } else if !matches!(kind, SymbolKind::Struct(_)) && self.typedefs.contains_key(ident) {
+            let previous = self.typedefs.get(ident).map(|(_, span)| *span);
+            Err(CompilerError::IdentifierExists(span, previous))
         } else if !matches!(kind, SymbolKind::Function { .. }) && ident.as_ref() == "main" {
             Err(CompilerError::MainIsReserved(span))
         } else {
             self.symbols.insert(ident.clone(), kind);
+            self.declared_at.insert(ident.clone(), span);
             if ident.as_ref() != "main" {
                 self.un_accessed_items.insert(ident.clone(), span);
             }
@@ -123,8 +280,92 @@ impl SymbolResolver {
         }
     }
 
+    /// Typedef names live beside struct tags, so `typedef struct node node;` is allowed.
+    /// Repeating a typedef is fine as long as it names the same type.
+    pub fn add_typedef(&mut self, ident: &InternedStr, ty: MlirType, span: Span) -> SymbolResult {
+        if let Some((existing, previous)) = self.typedefs.get(ident) {
+            return if *existing == ty {
+                Ok(())
+            } else {
+                Err(CompilerError::IdentifierExists(span, Some(*previous)))
+            };
+        }
+        match self.symbols.get(ident) {
+            Some(SymbolKind::Struct(_)) | None => {
+                self.typedefs.insert(ident.clone(), (ty, span));
+                Ok(())
+            }
+            Some(_) => {
+                let previous = self.declared_at.get(ident).copied();
+                Err(CompilerError::IdentifierExists(span, previous))
+            }
+        }
+    }
+
+    pub fn get_typedef(&self, ident: &InternedStr, span: Span) -> Result<MlirType, CompilerError> {
+        if let Some((ty, _)) = self.typedefs.get(ident) {
+            Ok(ty.clone())
+        } else if let Some(parent) = self.parent.as_ref() {
+            parent.borrow().get_typedef(ident, span)
+        } else {
+            Err(CompilerError::IdentNotFound((*ident).clone(), span))
+        }
+    }
+
+    pub fn add_enum(&mut self, ident: &InternedStr, span: Span) -> SymbolResult {
+        if let Some(previous) = self.enum_tags.get(ident) {
+            return Err(CompilerError::IdentifierExists(span, Some(*previous)));
+        }
+        self.enum_tags.insert(ident.clone(), span);
+        Ok(())
+    }
+
+    pub fn check_enum_exists(&self, ident: &InternedStr, span: Span) -> SymbolResult {
+        if self.enum_tags.contains_key(ident) {
+            Ok(())
+        } else if let Some(parent) = self.parent.as_ref() {
+            parent.borrow().check_enum_exists(ident, span)
+        } else {
+            Err(CompilerError::EnumNotFound(ident.to_string(), span))
+        }
+    }
+
+    /// Enumerators share the namespace of variables, but are not reported when unused.
+    pub fn add_enumerator(&mut self, ident: &InternedStr, value: i32, span: Span) -> SymbolResult {
+        if self.symbols.contains_key(ident) || self.typedefs.contains_key(ident) {
+            let previous = self
+                .declared_at
+                .get(ident)
+                .or(self.typedefs.get(ident).map(|(_, span)| span))
+                .copied();
+            return Err(CompilerError::IdentifierExists(span, previous));
+        }
+        self.symbols
+            .insert(ident.clone(), SymbolKind::Enumerator(value));
+        self.declared_at.insert(ident.clone(), span);
+        Ok(())
+    }
+
+    /// The value of the enumerator an identifier refers to, if it is not shadowed by anything else.
+    pub fn get_enumerator(&mut self, ident: &InternedStr, span: Span) -> Option<i32> {
+        match self.retrieve(ident, span) {
+            Ok(SymbolKind::Enumerator(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The function an identifier refers to, if it is not shadowed by anything else.
+    pub fn get_function(&mut self, ident: &InternedStr, span: Span) -> Option<FunctionSymbol> {
+        match self.retrieve(ident, span) {
+            Ok(SymbolKind::Function(func)) => Some(func),
+            _ => None,
+        }
+    }
+
     pub fn get_unused_idents(&self) -> Vec<(InternedStr, Span)> {
-        self.un_accessed_items.clone().into_iter().collect()
+        let mut idents = self.un_accessed_items.clone().into_iter().collect::<Vec<_>>();
+        idents.sort_by_key(|(_, span)| span.start);
+        idents
     }
 
     pub fn validate_function_call(
@@ -138,6 +379,17 @@ impl SymbolResolver {
         }
     }
 
+    /// Marks a variable as initialized, returns whether it already was.
+    pub fn mark_variable_initialized(&mut self, ident: &InternedStr) -> bool {
+        match self.symbols.get_mut(ident) {
+            Some(SymbolKind::Variable(var)) => std::mem::replace(&mut var.is_initialized, true),
+            Some(_) => true,
+            None => self.parent.as_ref().is_none_or(|parent| {
+                parent.borrow_mut().mark_variable_initialized(ident)
+            }),
+        }
+    }
+
     pub fn get_variable_type_and_id(
         &mut self,
         ident: &InternedStr,
@@ -189,6 +441,8 @@ impl SymbolResolver {
                 is_const: field.is_const,
                 is_initialized: field.initializer.is_some(),
                 array_size,
+                is_static: false,
+                is_defined: true,
             };
             if body.contains_key(&ident) {
                 return Err(CompilerError::MemberAlreadyExists(ident.clone(), span));
@@ -197,14 +451,56 @@ impl SymbolResolver {
         }
         let mut symbol = StructSymbol {
             size: _struct.size,
+            align: _struct.align,
+            is_union: _struct.is_union,
+            is_complete: true,
             as_type,
             body,
+            members: _struct.members.iter().map(|m| m.ident.clone()).collect(),
         };
         let symbol = SymbolKind::Struct(symbol);
+        // the definition completes a struct that was declared without a body
+        if matches!(self.symbols.get(&ident), Some(SymbolKind::Struct(s)) if !s.is_complete) {
+            self.symbols.insert(ident.clone(), symbol);
+            self.declared_at.insert(ident, span);
+            return Ok(());
+        }
         self.add_symbol(&ident, symbol, span)
     }
 
-    fn get_struct(
+    /// Declares a struct tag without a body, such as `struct file;` or `struct node *next`.
+    /// The struct is incomplete until it is defined, so it can only be used behind a pointer.
+    pub fn declare_struct(
+        &mut self,
+        ident: &InternedStr,
+        is_union: bool,
+        span: Span,
+    ) -> SymbolResult {
+        match self.get_struct(ident, span) {
+            Ok(_struct) if _struct.is_union != is_union => {
+                Err(CompilerError::WrongKindOfTag(ident.to_string(), span))
+            }
+            Ok(_) => Ok(()),
+            Err(CompilerError::IdentNotFound(..)) => {
+                let symbol = StructSymbol {
+                    size: 0,
+                    align: 1,
+                    is_union,
+                    is_complete: false,
+                    as_type: MlirType::new(
+                        MlirTypeKind::Struct(ident.clone()),
+                        MlirTypeDecl::Basic,
+                    ),
+                    body: HashMap::default(),
+                    members: Vec::new(),
+                };
+                self.add_symbol(ident, SymbolKind::Struct(symbol), span)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn get_struct(
         &mut self,
         ident: &InternedStr,
         span: Span,
@@ -214,6 +510,21 @@ impl SymbolResolver {
             _ => Err(CompilerError::NotAStruct(span)),
         }
     }
+
+    /// Only a struct that has been defined can be used by value.
+    fn get_complete_struct(
+        &mut self,
+        ident: &InternedStr,
+        span: Span,
+    ) -> Result<StructSymbol, CompilerError> {
+        let _struct = self.get_struct(ident, span)?;
+        if !_struct.is_complete {
+            let ty = _struct.as_type.to_string();
+            return Err(CompilerError::IncompleteStruct(ty, span));
+        }
+        Ok(_struct)
+    }
+
     pub fn validate_struct_member_access(
         &mut self,
         _struct: Locatable<MlirExpr>,
@@ -223,7 +534,7 @@ impl SymbolResolver {
             MlirTypeKind::Struct(ident) => ident,
             _ => panic!("`validate_struct_member_access` called on non struct expression."),
         };
-        let _match = self.get_struct(ident, _struct.location)?;
+        let _match = self.get_complete_struct(ident, _struct.location)?;
         let ty = _match
             .body
             .get(&member.value)
@@ -246,36 +557,70 @@ impl SymbolResolver {
         ident: &InternedStr,
         span: Span,
     ) -> Result<u64, CompilerError> {
-        Ok(self.get_struct(ident, span)?.size)
+        Ok(self.get_complete_struct(ident, span)?.size)
     }
 
+    pub fn get_struct_align(
+        &mut self,
+        ident: &InternedStr,
+        span: Span,
+    ) -> Result<u64, CompilerError> {
+        Ok(self.get_complete_struct(ident, span)?.align)
+    }
+
+    /// `is_union` is `None` when the tag is not known, such as for a type behind a typedef.
     pub fn check_struct_exists(
         &mut self,
         ident: &InternedStr,
+        is_union: Option<bool>,
+        require_complete: bool,
         span: Span,
     ) -> Result<(), CompilerError> {
-        self.get_struct(ident, span).map(|_| ())
+        let _struct = if require_complete {
+            self.get_complete_struct(ident, span)?
+        } else {
+            self.get_struct(ident, span)?
+        };
+        match is_union {
+            Some(is_union) if is_union != _struct.is_union => {
+                Err(CompilerError::WrongKindOfTag(ident.to_string(), span))
+            }
+            _ => Ok(()),
+        }
     }
 }
 
 #[test]
-fn test_builtin_function_is_called_correctly() {
+fn test_library_function_is_called_correctly() {
     let mut resolver = crate::analysis::symbols::SymbolResolver::create_root();
+    resolver.include_library_header(crate::data::libc::header("stdio.h").unwrap());
     let ident = crate::util::str_intern::intern("printf");
     let call = resolver.validate_function_call(ident, Span::default());
     assert!(call.is_ok())
 }
 
+#[test]
+fn test_library_function_needs_its_header() {
+    let mut resolver = SymbolResolver::create_root();
+    let ident = str_intern::intern("printf");
+    let call = resolver.validate_function_call(ident, Span::default());
+    assert!(call.is_err())
+}
+
 #[test]
 fn test_parent_scope_is_accessed_in_retrieve() {
     let mut resolver = SymbolResolver::create_root();
     let symbol = SymbolKind::Struct(StructSymbol {
         size: 0,
+        align: 1,
+        is_union: false,
+        is_complete: true,
         as_type: MlirType {
             kind: MlirTypeKind::Void,
             decl: MlirTypeDecl::Basic,
         },
         body: Default::default(),
+        members: Default::default(),
     });
     let ident = "test_ident".into();
     resolver.add_symbol(&ident, symbol, Span::default());
@@ -0,0 +1,141 @@
+use crate::analysis::{Analyzer, err};
+use crate::data::ast::{Declaration, Expression};
+use crate::data::error::CompilerError;
+use crate::data::mlir::{MlirExpr, MlirExprKind, MlirType, MlirTypeKind, VOID_TYPE};
+use crate::util::str_intern::InternedStr;
+use crate::util::{Locatable, Span};
+
+// The builtins behind the <stdarg.h> macros. Each takes the va_list itself rather than its
+// value, codegen passes its address to the LLVM intrinsics.
+
+impl Analyzer {
+    /// `va_start(ap, last)` points `ap` at the arguments after `last`, which has to be the
+    /// last named parameter of the variadic function it is used in.
+    pub(super) fn validate_va_start(
+        &mut self,
+        va_list: &Locatable<Box<Expression>>,
+        last: &Locatable<InternedStr>,
+    ) -> Result<MlirExpr, ()> {
+        let span = va_list.location.merge(last.location);
+        let va_list = self.validate_va_list_operand(va_list, true)?;
+        let Some(last_param) = self.varargs_last_param.clone() else {
+            err!(self, VaStartOutsideVariadic, span);
+            return Err(());
+        };
+        // naming the parameter counts as using it
+        let variable = Locatable::new(last.location, last.value.clone());
+        self.validate_expression(&Expression::Variable(variable))?;
+        if last.value != last_param {
+            err!(
+                self,
+                VaStartNotLastParameter,
+                last_param.to_string(),
+                last.location
+            );
+            return Err(());
+        }
+        Ok(Self::va_builtin(
+            MlirExprKind::VaStart(va_list),
+            VOID_TYPE,
+            span,
+        ))
+    }
+
+    /// `va_arg(ap, type)` reads the next argument, which was passed with the default argument
+    /// promotions, so a 'char' argument can only be read as an 'int'.
+    pub(super) fn validate_va_arg(
+        &mut self,
+        va_list: &Locatable<Box<Expression>>,
+        ty: &Locatable<Declaration>,
+    ) -> Result<MlirExpr, ()> {
+        let span = va_list.location.merge(ty.location);
+        let va_list = self.validate_va_list_operand(va_list, false)?;
+        let ty = self.validate_type(&ty.specifier, ty.location, false, false)?;
+        let promoted = match &ty.kind {
+            _ if !ty.is_basic() => None,
+            MlirTypeKind::Char(_) | MlirTypeKind::Short(_) => Some("int"),
+            MlirTypeKind::Float => Some("double"),
+            _ => None,
+        };
+        if let Some(promoted) = promoted {
+            err!(
+                self,
+                PromotedVaArgType,
+                ty.to_string(),
+                promoted.to_string(),
+                span
+            );
+            return Err(());
+        }
+        let is_scalar = ty.is_pointer()
+            || (ty.is_basic() && (ty.kind.is_numeric() || ty.is_function_pointer()));
+        if !is_scalar {
+            err!(
+                self,
+                CustomError,
+                format!("Reading a '{ty}' with 'va_arg' is not currently supported."),
+                span
+            );
+            return Err(());
+        }
+        Ok(Self::va_builtin(MlirExprKind::VaArg(va_list), ty, span))
+    }
+
+    pub(super) fn validate_va_end(
+        &mut self,
+        va_list: &Locatable<Box<Expression>>,
+    ) -> Result<MlirExpr, ()> {
+        let span = va_list.location;
+        let va_list = self.validate_va_list_operand(va_list, false)?;
+        Ok(Self::va_builtin(
+            MlirExprKind::VaEnd(va_list),
+            VOID_TYPE,
+            span,
+        ))
+    }
+
+    /// `va_copy(dest, src)` starts `dest` at the argument `src` is at.
+    pub(super) fn validate_va_copy(
+        &mut self,
+        dest: &Locatable<Box<Expression>>,
+        src: &Locatable<Box<Expression>>,
+    ) -> Result<MlirExpr, ()> {
+        let span = dest.location.merge(src.location);
+        let src = self.validate_va_list_operand(src, false)?;
+        let dest = self.validate_va_list_operand(dest, true)?;
+        Ok(Self::va_builtin(
+            MlirExprKind::VaCopy(dest, src),
+            VOID_TYPE,
+            span,
+        ))
+    }
+
+    /// The va_list the builtins work on has to be an lval, `va_start` and `va_copy`
+    /// initialize it.
+    fn validate_va_list_operand(
+        &mut self,
+        va_list: &Locatable<Box<Expression>>,
+        initializes: bool,
+    ) -> Result<MlirExpr, ()> {
+        if let (true, Expression::Variable(ident)) = (initializes, va_list.value.as_ref()) {
+            self.scope
+                .borrow_mut()
+                .mark_variable_initialized(&ident.value);
+        }
+        let expr = self.validate_expression(va_list)?;
+        if !expr.ty.is_va_list() || !expr.is_lval {
+            err!(self, NotAVaList, expr.ty.to_string(), va_list.location);
+            return Err(());
+        }
+        Ok(expr)
+    }
+
+    fn va_builtin(kind: MlirExprKind, ty: MlirType, span: Span) -> MlirExpr {
+        MlirExpr {
+            span,
+            ty,
+            is_lval: false,
+            kind: Box::new(kind),
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::types::{AnyType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType};
+use inkwell::values::{
+    BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue, PointerValue,
+};
+use inkwell::AddressSpace;
+
+use crate::codegen::Compiler;
+use crate::data::arch::*;
+use crate::data::mlir::{MlirExpr, MlirType, MlirTypeDecl, MlirTypeKind};
+use crate::target::Arch;
+
+// Structs passed and returned by value follow the C calling convention of the target, so that
+// functions can be called from and call into code built by other C compilers. LLVM leaves this
+// to the frontend: a struct is split into the registers its ABI assigns it, or goes through
+// memory. Every other type is passed as its own LLVM type.
+
+/// The argument registers of the x86_64 System V ABI.
+const X86_64_INT_REGISTERS: usize = 6;
+const X86_64_SSE_REGISTERS: usize = 8;
+
+/// How a parameter is passed.
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::codegen) enum ParamAbi<'ctx> {
+    Direct,
+    /// In registers, as the given pieces of its memory, each passed as its own argument.
+    Coerced(Vec<BasicTypeEnum<'ctx>>),
+    /// As a pointer to a copy of the struct. For `byval` (x86_64) the copy itself is placed in
+    /// the stack arguments, otherwise (AArch64) the caller passes the address of its copy.
+    Indirect {
+        byval: bool,
+    },
+}
+
+/// How a value is returned.
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::codegen) enum ReturnAbi<'ctx> {
+    Direct,
+    /// In registers, as a value of the given type that is read from the struct's memory.
+    Coerced(BasicTypeEnum<'ctx>),
+    /// Written to memory the caller provides through a hidden first `sret` parameter.
+    Sret,
+}
+
+#[derive(Debug)]
+pub(in crate::codegen) struct FunctionAbi<'ctx> {
+    pub(in crate::codegen) ret: ReturnAbi<'ctx>,
+    pub(in crate::codegen) params: Vec<ParamAbi<'ctx>>,
+}
+
+/// The scalars a struct is made of, which decide the registers it is passed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scalar {
+    Integer, // including pointers
+    Float,
+    Double,
+}
+
+impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
+    pub(in crate::codegen) fn function_abi(
+        &self,
+        return_ty: &MlirType,
+        params: &[MlirType],
+    ) -> FunctionAbi<'ctx> {
+        match self.arch {
+            Arch::X86_64 => self.x86_64_function_abi(return_ty, params),
+            Arch::Aarch64 => self.aarch64_function_abi(return_ty, params),
+        }
+    }
+
+    /// The LLVM type of a function once its signature is lowered by `abi`.
+    pub(in crate::codegen) fn lower_function_type(
+        &self,
+        return_ty: &MlirType,
+        params: &[MlirType],
+        varargs: bool,
+        abi: &FunctionAbi<'ctx>,
+    ) -> FunctionType<'ctx> {
+        let ptr_type = self.context.i8_type().ptr_type(AddressSpace::default());
+        let mut param_types: Vec<BasicMetadataTypeEnum> = Vec::new();
+        if abi.ret == ReturnAbi::Sret {
+            param_types.push(ptr_type.into());
+        }
+        for (param, param_abi) in params.iter().zip(&abi.params) {
+            match param_abi {
+                ParamAbi::Direct => param_types.push(self.convert_param_type(param).into()),
+                ParamAbi::Coerced(parts) => {
+                    param_types.extend(parts.iter().map(|part| BasicMetadataTypeEnum::from(*part)))
+                }
+                ParamAbi::Indirect { .. } => param_types.push(ptr_type.into()),
+            }
+        }
+
+        match &abi.ret {
+            ReturnAbi::Direct => self.convert_function_type(return_ty, &param_types, varargs),
+            ReturnAbi::Coerced(ty) => ty.fn_type(&param_types, varargs),
+            ReturnAbi::Sret => self.context.void_type().fn_type(&param_types, varargs),
+        }
+    }
+
+    /// The `sret` and `byval` attributes of a lowered signature, by LLVM parameter index.
+    /// They are needed on the function and on every call of it.
+    pub(in crate::codegen) fn abi_attributes(
+        &self,
+        return_ty: &MlirType,
+        params: &[MlirType],
+        abi: &FunctionAbi<'ctx>,
+    ) -> Vec<(u32, Attribute)> {
+        let type_attribute = |name: &str, ty: &MlirType| {
+            let kind = Attribute::get_named_enum_kind_id(name);
+            let ty = self.convert_type(ty).as_any_type_enum();
+            self.context.create_type_attribute(kind, ty)
+        };
+        let mut attributes = Vec::new();
+        let mut index = 0;
+        if abi.ret == ReturnAbi::Sret {
+            attributes.push((index, type_attribute("sret", return_ty)));
+            index += 1;
+        }
+        for (param, param_abi) in params.iter().zip(&abi.params) {
+            match param_abi {
+                ParamAbi::Direct | ParamAbi::Indirect { byval: false } => index += 1,
+                ParamAbi::Coerced(parts) => index += parts.len() as u32,
+                ParamAbi::Indirect { byval: true } => {
+                    // the stack slots of arguments are 8 byte aligned
+                    let align = Attribute::get_named_enum_kind_id("align");
+                    attributes.push((index, type_attribute("byval", param)));
+                    attributes.push((index, self.context.create_enum_attribute(align, 8)));
+                    index += 1;
+                }
+            }
+        }
+        attributes
+    }
+
+    /// Lowers the arguments of a call, preceded by the memory for the returned struct when it
+    /// is returned through `sret`.
+    pub(in crate::codegen) fn compile_call_arguments(
+        &mut self,
+        abi: &FunctionAbi<'ctx>,
+        return_ty: &MlirType,
+        args: &[MlirExpr],
+    ) -> (
+        Vec<BasicMetadataValueEnum<'ctx>>,
+        Option<PointerValue<'ctx>>,
+    ) {
+        let mut values: Vec<BasicMetadataValueEnum> = Vec::new();
+        let sret = (abi.ret == ReturnAbi::Sret).then(|| {
+            let ty = self.convert_type(return_ty);
+            self.create_entry_block_allocation(ty, "sret")
+        });
+        if let Some(sret) = sret {
+            values.push(sret.into());
+        }
+
+        for (arg, param_abi) in args.iter().zip(&abi.params) {
+            match param_abi {
+                ParamAbi::Direct => values.push(self.compile_argument(arg).into()),
+                ParamAbi::Coerced(parts) => {
+                    let ptr = self.compile_struct_to_memory(arg);
+                    let coerced = self.load_coerced(ptr, self.coerced_type(parts), &arg.ty);
+                    if parts.len() == 1 {
+                        values.push(coerced.into());
+                        continue;
+                    }
+                    let coerced = coerced.into_struct_value();
+                    for index in 0..parts.len() as u32 {
+                        let part = self
+                            .builder()
+                            .build_extract_value(coerced, index, "coerced_part")
+                            .unwrap();
+                        values.push(part.into());
+                    }
+                }
+                ParamAbi::Indirect { byval: true } => {
+                    values.push(self.compile_struct_to_memory(arg).into())
+                }
+                ParamAbi::Indirect { byval: false } => {
+                    let ptr = self.compile_struct_to_memory(arg);
+                    // a struct that isn't stored anywhere is already a copy
+                    let copy = if arg.is_lval {
+                        let copy = self
+                            .create_entry_block_allocation(self.convert_type(&arg.ty), "arg_copy");
+                        self.copy_struct(copy, ptr, &arg.ty);
+                        copy
+                    } else {
+                        ptr
+                    };
+                    values.push(copy.into());
+                }
+            }
+        }
+        (values, sret)
+    }
+
+    /// The value a call returns, a struct is given back as a value whichever way it was
+    /// returned.
+    pub(in crate::codegen) fn compile_call_result(
+        &mut self,
+        call_site_value: CallSiteValue<'ctx>,
+        attributes: Vec<(u32, Attribute)>,
+        abi: &FunctionAbi<'ctx>,
+        return_ty: &MlirType,
+        sret: Option<PointerValue<'ctx>>,
+    ) -> BasicValueEnum<'ctx> {
+        for (index, attribute) in attributes {
+            call_site_value.add_attribute(AttributeLoc::Param(index), attribute);
+        }
+        let const_i8 = BasicValueEnum::from(self.context.i8_type().const_int(0, false));
+        let ptr = match &abi.ret {
+            ReturnAbi::Direct => return call_site_value.try_as_basic_value().left_or(const_i8),
+            ReturnAbi::Coerced(_) => {
+                let value = call_site_value.try_as_basic_value().left().unwrap();
+                let ptr = self
+                    .create_entry_block_allocation(self.convert_type(return_ty), "returned_struct");
+                self.store_coerced(ptr, value, return_ty);
+                ptr
+            }
+            ReturnAbi::Sret => sret.unwrap(),
+        };
+        self.builder()
+            .build_load(self.convert_type(return_ty), ptr, "returned_struct_val")
+            .unwrap()
+    }
+
+    /// Stores the coerced pieces a struct parameter arrives in into its memory.
+    pub(in crate::codegen) fn store_coerced_param(
+        &mut self,
+        ptr: PointerValue<'ctx>,
+        parts: &[BasicValueEnum<'ctx>],
+        ty: &MlirType,
+    ) {
+        let value = if let [part] = parts {
+            *part
+        } else {
+            let part_types = parts.iter().map(|part| part.get_type()).collect::<Vec<_>>();
+            let mut value = self.context.struct_type(&part_types, false).get_undef();
+            for (index, part) in parts.iter().enumerate() {
+                value = self
+                    .builder()
+                    .build_insert_value(value, *part, index as u32, "coerced_param")
+                    .unwrap()
+                    .into_struct_value();
+            }
+            value.as_basic_value_enum()
+        };
+        self.store_coerced(ptr, value, ty);
+    }
+
+    /// Reads a struct's memory as the type it is coerced to. The coerced type can be larger
+    /// than the struct, so only the struct's bytes are copied through a temporary.
+    pub(in crate::codegen) fn load_coerced(
+        &mut self,
+        ptr: PointerValue<'ctx>,
+        coerced_type: BasicTypeEnum<'ctx>,
+        ty: &MlirType,
+    ) -> BasicValueEnum<'ctx> {
+        let temp = self.create_entry_block_allocation(coerced_type, "coerced");
+        self.copy_struct(temp, ptr, ty);
+        self.builder()
+            .build_load(coerced_type, temp, "coerced_val")
+            .unwrap()
+    }
+
+    fn store_coerced(
+        &mut self,
+        ptr: PointerValue<'ctx>,
+        value: BasicValueEnum<'ctx>,
+        ty: &MlirType,
+    ) {
+        let temp = self.create_entry_block_allocation(value.get_type(), "coerced");
+        self.builder().build_store(temp, value).unwrap();
+        self.copy_struct(ptr, temp, ty);
+    }
+
+    /// The memory of a struct value, a struct that isn't stored anywhere, like one returned
+    /// by a call, is stored in a temporary.
+    pub(in crate::codegen) fn compile_struct_to_memory(
+        &mut self,
+        expr: &MlirExpr,
+    ) -> PointerValue<'ctx> {
+        if Self::is_struct_copy(expr) {
+            return self.get_lval_as_pointer(expr);
+        }
+        let value = self.compile_expression(expr);
+        let temp = self.create_entry_block_allocation(value.get_type(), "struct_temp");
+        self.builder().build_store(temp, value).unwrap();
+        temp
+    }
+
+    fn coerced_type(&self, parts: &[BasicTypeEnum<'ctx>]) -> BasicTypeEnum<'ctx> {
+        match parts {
+            [part] => *part,
+            parts => self.context.struct_type(parts, false).into(),
+        }
+    }
+
+    /// The System V classification: a struct of at most 16 bytes is passed in the registers of
+    /// its eightbytes, as long as there are enough left for all of them. Anything else goes
+    /// through memory, and a returned struct is written to the `sret` pointer, which takes up
+    /// the first integer register.
+    fn x86_64_function_abi(&self, return_ty: &MlirType, params: &[MlirType]) -> FunctionAbi<'ctx> {
+        let ret = if !Self::is_struct_value(return_ty) {
+            ReturnAbi::Direct
+        } else if let Some(parts) = self.x86_64_eightbytes(return_ty) {
+            ReturnAbi::Coerced(self.coerced_type(&parts))
+        } else {
+            ReturnAbi::Sret
+        };
+        let mut int_registers = X86_64_INT_REGISTERS;
+        if ret == ReturnAbi::Sret {
+            int_registers -= 1;
+        }
+        let mut sse_registers = X86_64_SSE_REGISTERS;
+
+        let params = params
+            .iter()
+            .map(|param| {
+                if !Self::is_struct_value(param) {
+                    // scalars take a register while there are any left
+                    if param.is_float() {
+                        sse_registers = sse_registers.saturating_sub(1);
+                    } else {
+                        int_registers = int_registers.saturating_sub(1);
+                    }
+                    return ParamAbi::Direct;
+                }
+                let Some(parts) = self.x86_64_eightbytes(param) else {
+                    return ParamAbi::Indirect { byval: true };
+                };
+                let sse_parts = parts.iter().filter(|part| !part.is_int_type()).count();
+                let int_parts = parts.len() - sse_parts;
+                if int_parts > int_registers || sse_parts > sse_registers {
+                    return ParamAbi::Indirect { byval: true };
+                }
+                int_registers -= int_parts;
+                sse_registers -= sse_parts;
+                ParamAbi::Coerced(parts)
+            })
+            .collect();
+        FunctionAbi { ret, params }
+    }
+
+    /// The registers of each eightbyte of a struct: an eightbyte of only floating point
+    /// members goes in a vector register, others in an integer register. `None` when the struct
+    /// is larger than 16 bytes and goes through memory.
+    fn x86_64_eightbytes(&self, ty: &MlirType) -> Option<Vec<BasicTypeEnum<'ctx>>> {
+        let size = self.size_of(ty);
+        if size > 16 {
+            return None;
+        }
+        let mut scalars = Vec::new();
+        self.flatten_scalars(ty, 0, &mut scalars);
+
+        let eightbytes = (0..size.div_ceil(8)).map(|eightbyte| {
+            let start = eightbyte * 8;
+            let members = scalars
+                .iter()
+                .filter(|(offset, _)| (start..start + 8).contains(offset))
+                .collect::<Vec<_>>();
+            let float_type = self.context.f32_type();
+            if members.iter().any(|(_, scalar)| *scalar == Scalar::Integer) {
+                self.context.i64_type().into()
+            } else if members.iter().any(|(_, scalar)| *scalar == Scalar::Double) {
+                self.context.f64_type().into()
+            } else if members.iter().any(|(offset, _)| *offset == start + 4) {
+                float_type.vec_type(2).into()
+            } else {
+                float_type.into()
+            }
+        });
+        Some(eightbytes.collect())
+    }
+
+    /// The AArch64 procedure call standard: a struct of one to four members of the same
+    /// floating point type is passed in vector registers, other structs of at most 16 bytes
+    /// in one or two integer registers. Larger structs are passed as a pointer to a copy and
+    /// returned through the `sret` pointer.
+    fn aarch64_function_abi(&self, return_ty: &MlirType, params: &[MlirType]) -> FunctionAbi<'ctx> {
+        let ret = if !Self::is_struct_value(return_ty) {
+            ReturnAbi::Direct
+        } else if let Some(ty) = self.aarch64_registers(return_ty) {
+            ReturnAbi::Coerced(ty)
+        } else {
+            ReturnAbi::Sret
+        };
+        let params = params
+            .iter()
+            .map(|param| {
+                if !Self::is_struct_value(param) {
+                    ParamAbi::Direct
+                } else if let Some(ty) = self.aarch64_registers(param) {
+                    ParamAbi::Coerced(vec![ty])
+                } else {
+                    ParamAbi::Indirect { byval: false }
+                }
+            })
+            .collect();
+        FunctionAbi { ret, params }
+    }
+
+    /// The type a struct is passed in registers as. LLVM assigns the elements of an array to
+    /// consecutive registers, or puts all of them on the stack when there aren't enough left.
+    fn aarch64_registers(&self, ty: &MlirType) -> Option<BasicTypeEnum<'ctx>> {
+        let size = self.size_of(ty);
+        let mut scalars = Vec::new();
+        self.flatten_scalars(ty, 0, &mut scalars);
+
+        let first = scalars.first().map(|(_, scalar)| *scalar);
+        let homogeneous = scalars.iter().all(|(_, scalar)| Some(*scalar) == first);
+        let float_type: Option<BasicTypeEnum> = match first {
+            Some(Scalar::Float) => Some(self.context.f32_type().into()),
+            Some(Scalar::Double) => Some(self.context.f64_type().into()),
+            _ => None,
+        };
+        if let (true, Some(float_type)) = (homogeneous, float_type) {
+            let element_size = if first == Some(Scalar::Float) {
+                FLOAT_SIZE
+            } else {
+                DOUBLE_SIZE
+            };
+            let count = size / element_size;
+            if (1..=4).contains(&count) {
+                return Some(float_type.array_type(count as u32).into());
+            }
+        }
+
+        let i64_type = self.context.i64_type();
+        match size {
+            0..=8 => Some(i64_type.into()),
+            9..=16 => Some(i64_type.array_type(2).into()),
+            _ => None,
+        }
+    }
+
+    fn is_struct_value(ty: &MlirType) -> bool {
+        ty.is_basic() && matches!(ty.kind, MlirTypeKind::Struct(_))
+    }
+
+    /// Collects the scalar members of a type with their byte offsets, recursing into structs
+    /// and arrays. The members of a union all start at its own offset.
+    fn flatten_scalars(&self, ty: &MlirType, offset: u64, scalars: &mut Vec<(u64, Scalar)>) {
+        if ty.is_pointer() {
+            scalars.push((offset, Scalar::Integer));
+            return;
+        }
+        if let MlirTypeDecl::Array(length) = ty.decl {
+            let element = ty.deref_type();
+            let element_size = self.size_of(&element);
+            for index in 0..length {
+                self.flatten_scalars(&element, offset + index * element_size, scalars);
+            }
+            return;
+        }
+        match &ty.kind {
+            MlirTypeKind::Float => scalars.push((offset, Scalar::Float)),
+            MlirTypeKind::Double => scalars.push((offset, Scalar::Double)),
+            MlirTypeKind::Struct(ident) => {
+                let _struct = self
+                    .mlir
+                    .get_struct(ident)
+                    .expect("Struct not found in module!");
+                let mut member_offset: u64 = 0;
+                for member in &_struct.members {
+                    let start = if _struct.is_union {
+                        0
+                    } else {
+                        member_offset.next_multiple_of(self.align_of(&member.ty))
+                    };
+                    self.flatten_scalars(&member.ty, offset + start, scalars);
+                    member_offset = start + self.size_of(&member.ty);
+                }
+            }
+            _ => scalars.push((offset, Scalar::Integer)),
+        }
+    }
+
+    /// The size of a type in bytes, the same layout the analyzer gives structs.
+    fn size_of(&self, ty: &MlirType) -> u64 {
+        if ty.is_pointer() {
+            return POINTER_SIZE;
+        }
+        if let MlirTypeDecl::Array(length) = ty.decl {
+            return self.size_of(&ty.deref_type()) * length;
+        }
+        match &ty.kind {
+            MlirTypeKind::Struct(ident) => {
+                let _struct = self
+                    .mlir
+                    .get_struct(ident)
+                    .expect("Struct not found in module!");
+                _struct.size
+            }
+            _ => self.align_of(ty),
+        }
+    }
+
+    /// Scalars are aligned to their size, arrays like their elements.
+    fn align_of(&self, ty: &MlirType) -> u64 {
+        if ty.is_pointer() {
+            return POINTER_SIZE;
+        }
+        if ty.is_array() {
+            return self.align_of(&ty.deref_type());
+        }
+        match &ty.kind {
+            MlirTypeKind::Char(_) => CHAR_SIZE,
+            MlirTypeKind::Short(_) => SHORT_SIZE,
+            MlirTypeKind::Int(_) => INT_SIZE,
+            MlirTypeKind::Float => FLOAT_SIZE,
+            MlirTypeKind::Struct(ident) => {
+                let _struct = self
+                    .mlir
+                    .get_struct(ident)
+                    .expect("Struct not found in module!");
+                _struct.align
+            }
+            // long, double and pointers
+            _ => LONG_SIZE,
+        }
+    }
+}
@@ -1,8 +1,9 @@
+use inkwell::module::Linkage;
 use inkwell::types::{BasicType, BasicTypeEnum};
 use inkwell::values::{BasicValueEnum, PointerValue};
 
 use crate::codegen::Compiler;
-use crate::data::mlir::{MlirExpr, MlirTypeDecl, MlirVariable, MlirVarInit};
+use crate::data::mlir::{MlirExpr, MlirType, MlirTypeDecl, MlirVariable, MlirVarInit};
 
 impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
     pub fn compile_global_variable_declaration(&mut self, var: &'mlir MlirVariable) {
@@ -12,30 +13,52 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             ty: mlir_type,
             ident,
             is_const,
+            is_static,
             initializer,
         } = var;
 
-        let mut ty = self.convert_type(&if matches!(&mlir_type.decl, MlirTypeDecl::Array(_)) {
+        let ty = self.convert_type(&if matches!(&mlir_type.decl, MlirTypeDecl::Array(_)) {
             mlir_type.as_basic()
         } else {
             mlir_type.value.clone()
         });
 
-        let global = match &mlir_type.decl {
-            MlirTypeDecl::Array(size) => {
-                ty = ty.array_type(*size as u32).into();
-                self.module.add_global(ty, None, ident)
-            }
-            _ => self.module.add_global(ty, None, ident),
+        let global_ty = match &mlir_type.decl {
+            MlirTypeDecl::Array(size) => ty.array_type(*size as u32).into(),
+            _ => ty,
         };
+        let global = self.module.add_global(global_ty, None, ident);
+        if *is_static {
+            global.set_linkage(Linkage::Internal);
+        }
 
-        global.set_initializer(&self.create_default_value_for_type(ty));
+        global.set_initializer(&self.create_default_value_for_type(global_ty));
 
         self.insert_pointer(*uid, global.as_pointer_value());
 
         let initializer = initializer.as_ref().map(|val| &val.value);
 
-        self.init_in_main.push((*uid, ty, initializer));
+        // arrays are initialized element by element, like local arrays
+        self.init_in_main.push((*uid, mlir_type, initializer));
+    }
+
+    /// A global defined in another translation unit, it has no initializer so the linker
+    /// resolves it.
+    pub fn compile_external_global_declaration(&mut self, var: &'mlir MlirVariable) {
+        let mlir_type = &var.ty;
+        let ty = self.convert_type(&if matches!(&mlir_type.decl, MlirTypeDecl::Array(_)) {
+            mlir_type.as_basic()
+        } else {
+            mlir_type.value.clone()
+        });
+
+        let global_ty = match &mlir_type.decl {
+            MlirTypeDecl::Array(size) => ty.array_type(*size as u32).into(),
+            _ => ty,
+        };
+        let global = self.module.add_global(global_ty, None, &var.ident);
+
+        self.insert_pointer(var.uid, global.as_pointer_value());
     }
 
     fn create_default_value_for_type(&mut self, ty: BasicTypeEnum<'ctx>) -> BasicValueEnum<'ctx> {
@@ -58,6 +81,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             ty: mlir_type,
             ident,
             is_const,
+            is_static,
             initializer,
         } = var;
 
@@ -69,7 +93,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
 
         let var_ptr = match &mlir_type.decl {
             MlirTypeDecl::Array(size) => self.create_entry_block_array_allocation(ty, *size),
-            MlirTypeDecl::Pointer | MlirTypeDecl::Basic => {
+            MlirTypeDecl::Pointer(_) | MlirTypeDecl::Basic => {
                 self.create_entry_block_allocation(ty, ident)
             }
         };
@@ -78,42 +102,56 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
 
         let initializer = initializer.as_ref().map(|val| &val.value);
 
-        self.initialize_variable(ty, var_ptr, initializer);
+        if matches!(
+            initializer,
+            Some(MlirVarInit::Array(_) | MlirVarInit::Struct(_))
+        ) {
+            // elements and members left out of the initializer are zero
+            let zero = self.create_default_value_for_type(self.convert_type(mlir_type));
+            self.builder().build_store(var_ptr, zero).unwrap();
+        }
+
+        self.initialize_variable(mlir_type, var_ptr, initializer);
     }
 
     pub fn initialize_variable(
         &mut self,
-        ty: BasicTypeEnum<'ctx>,
+        ty: &MlirType,
         var_ptr: PointerValue<'ctx>,
         initializer: Option<&MlirVarInit>,
     ) {
-        if let Some(initializer) = initializer {
-            match initializer {
-                MlirVarInit::Array(array) => {
-                    self.compile_array_initializer(var_ptr, ty, array);
+        let Some(initializer) = initializer else {
+            return;
+        };
+        match initializer {
+            MlirVarInit::Expr(expr) => self.compile_store(var_ptr, expr),
+            // element arrays and structs are initialized in place
+            MlirVarInit::Array(elements) => {
+                let element_ty = ty.deref_type();
+                let llvm_element_ty = self.convert_type(&element_ty);
+                for (index, init) in elements {
+                    let index = self.context.i32_type().const_int(*index, false);
+                    let element_ptr = self.get_array_index_pointer(llvm_element_ty, var_ptr, index);
+                    self.initialize_variable(&element_ty, element_ptr, Some(init));
                 }
-                MlirVarInit::Expr(expr) => {
-                    let expr = self.compile_expression(expr);
-                    self.builder().build_store(var_ptr, expr).unwrap();
+            }
+            MlirVarInit::Struct(members) => {
+                let struct_ident = ty.get_struct_ident();
+                for (member, init) in members {
+                    let member_ty = self.mlir.get_struct_member_type(struct_ident, member);
+                    // every member of a union starts at its first byte
+                    let member_ptr = if self.is_union(struct_ident) {
+                        var_ptr
+                    } else {
+                        let index = self.mlir.get_struct_member_index(struct_ident, member);
+                        let struct_type = self.get_struct_type(struct_ident);
+                        self.builder()
+                            .build_struct_gep(struct_type, var_ptr, index, "init_member")
+                            .unwrap()
+                    };
+                    self.initialize_variable(member_ty, member_ptr, Some(init));
                 }
             }
         }
     }
-
-    fn compile_array_initializer(
-        &mut self,
-        ptr: PointerValue<'ctx>,
-        element_type: BasicTypeEnum<'ctx>,
-        array: &[MlirExpr],
-    ) {
-        let elements = array
-            .iter()
-            .map(|expr| self.compile_expression(expr))
-            .collect::<Vec<_>>();
-        for (idx, e) in elements.iter().enumerate() {
-            let idx = self.context.i32_type().const_int(idx as u64, false);
-            let ptr = self.get_array_index_pointer(element_type, ptr, idx);
-            self.builder().build_store(ptr, *e).unwrap();
-        }
-    }
 }
@@ -1,8 +1,9 @@
 use inkwell::{FloatPredicate, IntPredicate};
-use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum};
+use inkwell::types::BasicMetadataTypeEnum;
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, IntValue};
 
 use crate::codegen::Compiler;
-use crate::data::mlir::{CastType, MlirExpr, MlirExprKind, MlirType};
+use crate::data::mlir::{CastType, MlirExpr, MlirExprKind, MlirType, MlirTypeKind};
 use crate::util::str_intern::InternedStr;
 
 impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
@@ -53,34 +54,146 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             MlirExprKind::Cast(cast_to, cast_type, expr) => {
                 self.compile_cast(cast_to, cast_type, expr, &expr.ty)
             }
+            MlirExprKind::Ternary(condition, then, otherwise) => {
+                self.compile_ternary(condition, then, otherwise)
+            }
             MlirExprKind::FunctionCall { ident, args, .. } => {
-                self.compile_function_call(ident, args)
+                self.compile_function_call(ident, args, &expr.ty)
+            }
+            MlirExprKind::Function(ident) => {
+                let function = self.functions[ident];
+                BasicValueEnum::from(function.as_global_value().as_pointer_value())
+            }
+            MlirExprKind::IndirectCall(callee, args) => {
+                self.compile_indirect_call(callee, args, &expr.ty)
             }
+            MlirExprKind::VaStart(va_list) => self.compile_va_start(va_list),
+            MlirExprKind::VaArg(va_list) => self.compile_va_arg(va_list, &expr.ty),
+            MlirExprKind::VaEnd(va_list) => self.compile_va_end(va_list),
+            MlirExprKind::VaCopy(dest, src) => self.compile_va_copy(dest, src),
         }
     }
 
+    /// Compiles a scalar condition to an `i1` that is set when the condition is non-zero.
+    pub(in crate::codegen) fn compile_condition(&mut self, condition: &MlirExpr) -> IntValue<'ctx> {
+        match self.compile_expression(condition) {
+            BasicValueEnum::IntValue(condition) => {
+                let zero = condition.get_type().const_zero();
+                self.builder()
+                    .build_int_compare(IntPredicate::NE, condition, zero, "is_true")
+                    .unwrap()
+            }
+            // NaN is unequal to zero as well, so it is true
+            BasicValueEnum::FloatValue(condition) => {
+                let zero = condition.get_type().const_zero();
+                self.builder()
+                    .build_float_compare(FloatPredicate::UNE, condition, zero, "is_true")
+                    .unwrap()
+            }
+            BasicValueEnum::PointerValue(condition) => self
+                .builder()
+                .build_is_not_null(condition, "is_true")
+                .unwrap(),
+            _ => unreachable!("Conditions are scalars."),
+        }
+    }
+
+    fn compile_ternary(
+        &mut self,
+        condition: &MlirExpr,
+        then: &MlirExpr,
+        otherwise: &MlirExpr,
+    ) -> BasicValueEnum<'ctx> {
+        let condition = self.compile_condition(condition);
+
+        // the arms get their own blocks, so only the selected one is evaluated
+        let current_block = self.builder().get_insert_block().unwrap();
+        let then_block = self
+            .context
+            .insert_basic_block_after(current_block, "ternary_then");
+        let else_block = self
+            .context
+            .insert_basic_block_after(then_block, "ternary_else");
+        let end_block = self.context.insert_basic_block_after(else_block, "ternary_end");
+
+        self.builder()
+            .build_conditional_branch(condition, then_block, else_block)
+            .unwrap();
+
+        // nested conditionals move the builder, so the incoming blocks are taken after each arm
+        self.builder().position_at_end(then_block);
+        let then_value = self.compile_expression(then);
+        let then_block = self.builder().get_insert_block().unwrap();
+        self.builder().build_unconditional_branch(end_block).unwrap();
+
+        self.builder().position_at_end(else_block);
+        let else_value = self.compile_expression(otherwise);
+        let else_block = self.builder().get_insert_block().unwrap();
+        self.builder().build_unconditional_branch(end_block).unwrap();
+
+        self.builder().position_at_end(end_block);
+        let phi = self
+            .builder()
+            .build_phi(then_value.get_type(), "ternary_value")
+            .unwrap();
+        phi.add_incoming(&[(&then_value, then_block), (&else_value, else_block)]);
+        phi.as_basic_value()
+    }
+
+    /// The ABI of a call is decided by the types of its arguments, so that variadic arguments
+    /// are lowered the same way as the fixed ones.
     fn compile_function_call(
         &mut self,
         ident: &InternedStr,
         args: &[MlirExpr],
+        return_ty: &MlirType,
     ) -> BasicValueEnum<'ctx> {
         let function = *self
             .functions
             .get(ident)
             .unwrap_or_else(|| panic!("Function '{ident}' does not exist in memory."));
 
-        let compiled_args: Vec<BasicMetadataValueEnum> = args
-            .iter()
-            .map(|arg| self.compile_expression(arg).into())
-            .collect();
+        let arg_types = args.iter().map(|arg| arg.ty.clone()).collect::<Vec<_>>();
+        let abi = self.function_abi(return_ty, &arg_types);
+        let (compiled_args, sret) = self.compile_call_arguments(&abi, return_ty, args);
 
         let call_site_value = self
             .builder()
             .build_call(function, &compiled_args, "function_call")
             .unwrap();
 
-        let const_i8 = BasicValueEnum::from(self.context.i8_type().const_int(0, false));
-        call_site_value.try_as_basic_value().left_or(const_i8)
+        let attributes = self.abi_attributes(return_ty, &arg_types, &abi);
+        self.compile_call_result(call_site_value, attributes, &abi, return_ty, sret)
+    }
+
+    fn compile_indirect_call(
+        &mut self,
+        callee: &MlirExpr,
+        args: &[MlirExpr],
+        return_ty: &MlirType,
+    ) -> BasicValueEnum<'ctx> {
+        let MlirTypeKind::FunctionPointer(signature) = &callee.ty.kind else {
+            panic!("Indirect call through '{}', which is not a function pointer.", callee.ty);
+        };
+        let arg_types = args.iter().map(|arg| arg.ty.clone()).collect::<Vec<_>>();
+        let abi = self.function_abi(return_ty, &arg_types);
+        let fn_type = self.lower_function_type(
+            &signature.return_ty,
+            &signature.params,
+            signature.varargs,
+            &abi,
+        );
+
+        let function_pointer = self.compile_expression(callee).into_pointer_value();
+        let (compiled_args, sret) = self.compile_call_arguments(&abi, return_ty, args);
+
+        let call_site_value = self
+            .builder()
+            .build_indirect_call(fn_type, function_pointer, &compiled_args, "indirect_call")
+            .unwrap();
+
+        let attributes = self.abi_attributes(return_ty, &arg_types, &abi);
+        self.compile_call_result(call_site_value, attributes, &abi, return_ty, sret)
     }
 
     fn compile_cast(
@@ -91,6 +204,11 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
         mlir_type: &MlirType,
     ) -> BasicValueEnum<'ctx> {
         let unsigned_int = expr.ty.is_unsigned_int() || mlir_type.is_unsigned_int();
+        let unsigned_source = expr.ty.is_unsigned_int();
+        if matches!(cast_type, CastType::ArrayToPointer) && expr.is_lval {
+            // arrays decay to the address of their first element
+            return BasicValueEnum::from(self.get_lval_as_pointer(expr));
+        }
         let expr = self.compile_expression(expr);
 
         if matches!(
@@ -116,7 +234,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             }
 
             CastType::IntToPointer => {
-                let ptr_type = self.convert_type(mlir_type).into_pointer_type();
+                let ptr_type = self.convert_type(cast_to).into_pointer_type();
                 let long = expr.into_int_value();
                 let value = self
                     .builder()
@@ -130,7 +248,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
                 let int_type = self.convert_type(cast_to).into_int_type();
                 let value = self
                     .builder()
-                    .build_int_cast(int, int_type, "int_to_int")
+                    .build_int_cast_sign_flag(int, int_type, !unsigned_source, "int_to_int")
                     .unwrap();
                 BasicValueEnum::from(value)
             }
@@ -1,15 +1,15 @@
 use inkwell::AddressSpace;
-use inkwell::types::BasicTypeEnum;
+use inkwell::types::{BasicType, BasicTypeEnum};
 use inkwell::values::{BasicValueEnum, IntValue, PointerValue};
 
 use crate::codegen::Compiler;
-use crate::data::mlir::{MlirExpr, MlirExprKind, MlirType};
+use crate::data::mlir::{MlirExpr, MlirExprKind, MlirType, MlirTypeKind};
 use crate::util::str_intern::InternedStr;
 
 impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
     pub fn compile_deref(&mut self, expr: &MlirExpr) -> BasicValueEnum<'ctx> {
         let ptr = self.compile_expression(expr).into_pointer_value();
-        let ptr_type = self.convert_type(&expr.ty.as_basic());
+        let ptr_type = self.convert_type(&expr.ty.deref_type());
         self.builder()
             .build_load(ptr_type, ptr, "ptr_deref_val")
             .unwrap()
@@ -23,7 +23,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
                 .compile_assignment(left, right, true)
                 .into_pointer_value(),
             MlirExprKind::Index(array, index) => self.compile_array_index_pointer(
-                self.convert_type(&array.ty.as_basic()),
+                self.convert_type(&array.ty.deref_type()),
                 array,
                 index,
             ),
@@ -43,6 +43,17 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
     ) -> BasicValueEnum<'ctx> {
         debug_assert!(left.is_lval);
         let assign_ptr = self.get_lval_as_pointer(left);
+        if Self::is_struct_copy(right) {
+            self.compile_store(assign_ptr, right);
+            return if return_ptr {
+                BasicValueEnum::from(assign_ptr)
+            } else {
+                let struct_type = self.convert_type(&left.ty);
+                self.builder()
+                    .build_load(struct_type, assign_ptr, "struct_assign_val")
+                    .unwrap()
+            };
+        }
         let assign_value = self.compile_expression(right);
 
         self.builder()
@@ -56,7 +67,43 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
         }
     }
 
-    fn get_lval_as_pointer(&mut self, lval: &MlirExpr) -> PointerValue<'ctx> {
+    /// Stores the value of `expr` at `ptr`, structs stored in memory are copied with memcpy
+    /// instead of being loaded as a whole.
+    pub(in crate::codegen) fn compile_store(&mut self, ptr: PointerValue<'ctx>, expr: &MlirExpr) {
+        if !Self::is_struct_copy(expr) {
+            let value = self.compile_expression(expr);
+            self.builder().build_store(ptr, value).unwrap();
+            return;
+        }
+        let src_ptr = self.get_lval_as_pointer(expr);
+        self.copy_struct(ptr, src_ptr, &expr.ty);
+    }
+
+    pub(in crate::codegen) fn copy_struct(
+        &mut self,
+        dest: PointerValue<'ctx>,
+        src: PointerValue<'ctx>,
+        ty: &MlirType,
+    ) {
+        let _struct = self
+            .mlir
+            .get_struct(ty.get_struct_ident())
+            .expect("Struct not found in module!");
+        let align = _struct.align as u32;
+        let size = self.context.i64_type().const_int(_struct.size, false);
+        self.builder()
+            .build_memcpy(dest, align, src, align, size)
+            .unwrap();
+    }
+
+    pub(in crate::codegen) fn is_struct_copy(expr: &MlirExpr) -> bool {
+        expr.is_lval && expr.ty.is_basic() && matches!(expr.ty.kind, MlirTypeKind::Struct(_))
+    }
+
+    pub(in crate::codegen) fn get_lval_as_pointer(
+        &mut self,
+        lval: &MlirExpr,
+    ) -> PointerValue<'ctx> {
         match &*lval.kind {
             MlirExprKind::Deref(expr) => self.compile_expression(expr).into_pointer_value(),
 
@@ -67,7 +114,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             }
 
             MlirExprKind::Index(array, index) => self.compile_array_index_pointer(
-                self.convert_type(&lval.ty.as_basic()),
+                self.convert_type(&lval.ty),
                 array,
                 index,
             ),
@@ -82,6 +129,17 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
     ) -> PointerValue<'ctx> {
         let struct_ptr = self.get_lval_as_pointer(_struct);
         let struct_ident = _struct.ty.get_struct_ident();
+        if self.is_union(struct_ident) {
+            // every member of a union starts at its first byte
+            return self
+                .builder()
+                .build_pointer_cast(
+                    struct_ptr,
+                    access_ty.ptr_type(AddressSpace::default()),
+                    &format!("member_access_{}_{}", struct_ident, member),
+                )
+                .unwrap();
+        }
         let member_index = self.mlir.get_struct_member_index(struct_ident, member);
 
         let struct_type = self.get_struct_type(struct_ident);
@@ -103,7 +161,12 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
         array: &MlirExpr,
         index: &MlirExpr,
     ) -> PointerValue<'ctx> {
-        let array_ptr = self.get_lval_as_pointer(array);
+        // arrays are indexed in place, pointers are indexed from the address they hold
+        let array_ptr = if array.ty.is_pointer() {
+            self.compile_expression(array).into_pointer_value()
+        } else {
+            self.get_lval_as_pointer(array)
+        };
         let index_value = self.compile_expression(index).into_int_value();
         self.get_array_index_pointer(access_ty, array_ptr, index_value)
     }
@@ -140,6 +203,9 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
         member: &InternedStr,
     ) -> BasicValueEnum<'ctx> {
         let struct_ident = _struct.ty.get_struct_ident();
+        if self.is_union(struct_ident) {
+            return self.compile_union_member_access(_struct, member);
+        }
         let member_index = self.mlir.get_struct_member_index(struct_ident, member);
         let compiled_struct = self.compile_expression(_struct).into_struct_value();
         self.builder()
@@ -151,6 +217,36 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             .unwrap()
     }
 
+    fn compile_union_member_access(
+        &mut self,
+        union: &MlirExpr,
+        member: &InternedStr,
+    ) -> BasicValueEnum<'ctx> {
+        let union_ident = union.ty.get_struct_ident();
+        let member_type = self
+            .mlir
+            .get_struct_member_type(union_ident, member)
+            .clone();
+        let member_type = self.convert_type(&member_type);
+        let member_ptr = if union.is_lval {
+            self.compile_struct_member_pointer(member_type, union, member)
+        } else {
+            // a union value has no address, so it is spilled to read a member from it
+            let union_value = self.compile_expression(union);
+            let union_ptr =
+                self.create_entry_block_allocation(union_value.get_type(), "union_temp");
+            self.builder().build_store(union_ptr, union_value).unwrap();
+            union_ptr
+        };
+        self.builder()
+            .build_load(
+                member_type,
+                member_ptr,
+                &format!("{union_ident}_{member}_load"),
+            )
+            .unwrap()
+    }
+
     pub fn compile_variable_access(&mut self, ty: &MlirType, id: usize) -> BasicValueEnum<'ctx> {
         let pointee_ty = self.convert_type(ty);
         let ptr = self.get_pointer(id);
@@ -5,11 +5,14 @@ use std::rc::Rc;
 
 use derive_new::new;
 use inkwell::{AddressSpace, FloatPredicate, IntPredicate};
+use inkwell::attributes::AttributeLoc;
 use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
 use inkwell::support::LLVMString;
+use inkwell::targets::{FileType, TargetMachine};
 use inkwell::types::{
     AnyType, AnyTypeEnum, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, StructType,
 };
@@ -25,16 +28,22 @@ use crate::data::mlir::{
     MlirBlock, MlirExpr, MlirExprKind, MlirFunction, MlirLiteral, MlirModule, MlirStmt, MlirStruct,
     MlirType, MlirTypeDecl, MlirTypeKind, MlirVariable, MlirVarInit, VOID_PTR, VOID_TYPE,
 };
-use crate::data::symbols::BUILTINS;
+use crate::codegen::abi::{ParamAbi, ReturnAbi};
+use crate::codegen::passes::OptLevel;
+use crate::data::symbols::FunctionSymbol;
+use crate::target::{Arch, TargetTriple, VaListKind};
 use crate::util::{Locatable, str_intern};
 use crate::util::str_intern::InternedStr;
 
+pub(in crate::codegen) mod abi;
 pub(in crate::codegen) mod binary_expressions;
 pub(in crate::codegen) mod declarations;
 pub(in crate::codegen) mod expressions;
 pub(in crate::codegen) mod literal_expressions;
 pub(in crate::codegen) mod lvals;
+pub mod passes;
 pub(in crate::codegen) mod statements;
+pub(in crate::codegen) mod varargs;
 
 pub struct Compiler<'a, 'mlir, 'ctx> {
     pub(in crate::codegen) mlir: &'mlir MlirModule,
@@ -44,18 +53,32 @@ pub struct Compiler<'a, 'mlir, 'ctx> {
     pub(in crate::codegen) fn_value_opt: Option<FunctionValue<'ctx>>,
     pub(in crate::codegen) struct_types: HashMap<InternedStr, StructType<'ctx>>,
     pub(in crate::codegen) block_has_jumped: bool,
-    pub(in crate::codegen) init_in_main:
-        Vec<(usize, BasicTypeEnum<'ctx>, Option<&'mlir MlirVarInit>)>,
+    pub(in crate::codegen) init_in_main: Vec<(usize, &'mlir MlirType, Option<&'mlir MlirVarInit>)>,
+    pub(in crate::codegen) va_list_kind: VaListKind,
+    pub(in crate::codegen) arch: Arch,
+    /// How the function being compiled returns its value, and the `sret` pointer it returns
+    /// a struct through.
+    pub(in crate::codegen) return_abi: ReturnAbi<'ctx>,
+    pub(in crate::codegen) sret_ptr: Option<PointerValue<'ctx>>,
     functions: HashMap<InternedStr, FunctionValue<'ctx>>,
     variables: HashMap<usize, PointerValue<'ctx>>,
 }
 
 impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
-    pub fn new(mlir: &'mlir MlirModule, context: &'ctx Context, module: &'a Module<'ctx>) -> Self {
+    pub fn new(
+        mlir: &'mlir MlirModule,
+        context: &'ctx Context,
+        module: &'a Module<'ctx>,
+        target: &TargetTriple,
+    ) -> Self {
         let mut compiler = Self {
             mlir,
             context,
             module,
+            va_list_kind: target.va_list_kind(),
+            arch: target.arch,
+            return_abi: ReturnAbi::Direct,
+            sret_ptr: None,
             functions: Default::default(),
             variables: Default::default(),
             struct_types: Default::default(),
@@ -65,11 +88,22 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             init_in_main: vec![],
         };
         compiler.builder = Some(compiler.context.create_builder());
-        compiler.compile_builtins();
         compiler
     }
 
-    pub fn compile(mut self) -> Result<LLVMString, LLVMString> {
+    /// Lowers the mlir into the module, runs the optimization pipeline for the given level
+    /// and emits the module as an object or assembly file for the given target machine.
+    pub fn compile(
+        mut self,
+        machine: &TargetMachine,
+        file_type: FileType,
+        opt_level: OptLevel,
+        print_passes: bool,
+    ) -> Result<MemoryBuffer, LLVMString> {
+        self.module.set_triple(&machine.get_triple());
+        self.module
+            .set_data_layout(&machine.get_target_data().get_data_layout());
+
         for _struct in self.mlir.structs.iter() {
             self.create_struct_type(_struct);
         }
@@ -78,13 +112,31 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             self.compile_global_variable_declaration(global);
         }
 
+        for global in self.mlir.external_globals.iter() {
+            self.compile_external_global_declaration(global);
+        }
+
+        for function in self.mlir.external_functions.iter() {
+            self.compile_external_function(function);
+        }
+
+        // all signatures are needed up front so that bodies can call functions defined later
+        for function in self.mlir.functions.iter() {
+            self.compile_function_signature(function);
+        }
+
         for function in self.mlir.functions.iter() {
             self.compile_function(function);
         }
 
+        // main is in another translation unit
+        if !self.init_in_main.is_empty() {
+            self.compile_global_constructor();
+        }
+
         self.module.verify()?;
-        let llir = self.module.print_to_string();
-        Ok(llir)
+        self.run_passes(machine, opt_level, print_passes)?;
+        machine.write_to_memory_buffer(self.module, file_type)
     }
 
     #[inline(always)]
@@ -133,49 +185,48 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             .unwrap_or_else(|| panic!("Struct '{}' does not exist!", ident))
     }
 
-    fn compile_builtins(&mut self) {
-        for (ident, builtin) in BUILTINS.iter() {
-            let param_types = builtin
-                .params
-                .iter()
-                .map(|param_type| self.convert_type(param_type).into())
-                .collect::<Vec<BasicMetadataTypeEnum>>();
-
-            let fn_type =
-                self.convert_function_type(&builtin.return_ty, &param_types, builtin.varargs);
-
-            let fn_val = self
-                .module
-                .add_function(ident, fn_type, Some(Linkage::External));
-
-            self.functions.insert(str_intern::intern(ident), fn_val);
-        }
+    fn compile_external_function(&mut self, function: &FunctionSymbol) {
+        self.add_function(
+            &function.ident,
+            &function.return_ty,
+            &function.params,
+            function.varargs,
+            Some(Linkage::External),
+        );
     }
 
     fn compile_function_signature(&mut self, function: &MlirFunction) -> FunctionValue<'ctx> {
-        let param_types: Vec<BasicMetadataTypeEnum> = function
+        let param_types = function
             .parameters
             .iter()
-            .map(|variable_param| {
-                let param_type = &variable_param.ty;
-                let ty_enum = self.convert_type(param_type).into();
-                ty_enum
-            })
-            .collect();
-
-        // void types 'fn_type' method is only accessible via VoidType directly
-        let fn_type = self.convert_function_type(&function.ty, &param_types, false);
-
-        let fn_val = self
-            .module
-            .add_function(function.ident.as_ref(), fn_type, None);
-
-        for (i, arg) in fn_val.get_param_iter().enumerate() {
-            arg.set_name(function.parameters[i].ident.as_ref());
-        }
-
-        self.functions.insert(function.ident.clone(), fn_val);
+            .map(|param| param.ty.clone())
+            .collect::<Vec<_>>();
+        let linkage = function.is_static.then_some(Linkage::Internal);
+        self.add_function(
+            &function.ident,
+            &function.ty,
+            &param_types,
+            function.varargs,
+            linkage,
+        )
+    }
 
+    /// Adds a function to the module with its signature lowered by the target's ABI.
+    fn add_function(
+        &mut self,
+        ident: &InternedStr,
+        return_ty: &MlirType,
+        params: &[MlirType],
+        varargs: bool,
+        linkage: Option<Linkage>,
+    ) -> FunctionValue<'ctx> {
+        let abi = self.function_abi(return_ty, params);
+        let fn_type = self.lower_function_type(return_ty, params, varargs, &abi);
+        let fn_val = self.module.add_function(ident.as_ref(), fn_type, linkage);
+        for (index, attribute) in self.abi_attributes(return_ty, params, &abi) {
+            fn_val.add_attribute(AttributeLoc::Param(index), attribute);
+        }
+        self.functions.insert(ident.clone(), fn_val);
         fn_val
     }
 
@@ -187,8 +238,39 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
         }
     }
 
+    /// Initializes the globals in a function that runs before main, registered in
+    /// `llvm.global_ctors` as `{ priority, function, data }`.
+    fn compile_global_constructor(&mut self) {
+        let fn_type = self.context.void_type().fn_type(&[], false);
+        let constructor =
+            self.module
+                .add_function("microc.init_globals", fn_type, Some(Linkage::Internal));
+        let entry = self.context.append_basic_block(constructor, "entry");
+        self.builder().position_at_end(entry);
+        self.fn_value_opt = Some(constructor);
+        self.init_static_globals();
+        self.builder().build_return(None).unwrap();
+
+        let priority_type = self.context.i32_type();
+        let ptr_type = self.context.i8_type().ptr_type(AddressSpace::default());
+        let entry_type = self.context.struct_type(
+            &[priority_type.into(), ptr_type.into(), ptr_type.into()],
+            false,
+        );
+        let entry = entry_type.const_named_struct(&[
+            priority_type.const_int(65535, false).into(),
+            constructor.as_global_value().as_pointer_value().into(),
+            ptr_type.const_null().into(),
+        ]);
+        let constructors =
+            self.module
+                .add_global(entry_type.array_type(1), None, "llvm.global_ctors");
+        constructors.set_linkage(Linkage::Appending);
+        constructors.set_initializer(&entry_type.const_array(&[entry]));
+    }
+
     fn compile_function(&mut self, function: &'mlir MlirFunction) {
-        let context_function = self.compile_function_signature(function);
+        let context_function = self.functions[&function.ident.value];
         let entry = self.context.append_basic_block(context_function, "entry");
 
         self.builder().position_at_end(entry);
@@ -199,19 +281,61 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             self.init_static_globals();
         }
 
-        for (llvm_param, mlir_param) in context_function
-            .get_param_iter()
-            .zip(function.parameters.iter())
-        {
+        let param_types = function
+            .parameters
+            .iter()
+            .map(|param| param.ty.clone())
+            .collect::<Vec<_>>();
+        let abi = self.function_abi(&function.ty, &param_types);
+        let mut llvm_params = context_function.get_param_iter();
+        self.sret_ptr = (abi.ret == ReturnAbi::Sret).then(|| {
+            let sret = llvm_params.next().unwrap();
+            sret.set_name("sret");
+            sret.into_pointer_value()
+        });
+        self.return_abi = abi.ret;
+
+        for (mlir_param, param_abi) in function.parameters.iter().zip(abi.params) {
             let MlirVariable {
                 uid,
                 span,
                 ty: mlir_type,
                 ident,
                 is_const,
+                is_static,
                 initializer,
             } = &mlir_param.value;
 
+            match param_abi {
+                ParamAbi::Direct => {}
+                ParamAbi::Coerced(parts) => {
+                    let parts = llvm_params.by_ref().take(parts.len()).collect::<Vec<_>>();
+                    for (index, part) in parts.iter().enumerate() {
+                        part.set_name(&format!("{ident}.coerce{index}"));
+                    }
+                    let allocation =
+                        self.create_entry_block_allocation(self.convert_type(mlir_type), ident);
+                    self.store_coerced_param(allocation, &parts, mlir_type);
+                    self.insert_pointer(*uid, allocation);
+                    continue;
+                }
+                ParamAbi::Indirect { .. } => {
+                    // the copy the argument points to belongs to this call
+                    let llvm_param = llvm_params.next().unwrap();
+                    llvm_param.set_name(ident);
+                    self.insert_pointer(*uid, llvm_param.into_pointer_value());
+                    continue;
+                }
+            }
+            let llvm_param = llvm_params.next().unwrap();
+            llvm_param.set_name(ident);
+
+            if mlir_type.is_va_list() && self.va_list_is_passed_by_reference() {
+                // the list the argument points to belongs to this call
+                self.insert_pointer(*uid, llvm_param.into_pointer_value());
+                continue;
+            }
+
             let ty = if matches!(&mlir_type.decl, MlirTypeDecl::Array(_)) {
                 mlir_type.as_basic()
             } else {
@@ -221,7 +345,7 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
 
             let allocation = match &mlir_param.ty.decl {
                 MlirTypeDecl::Array(size) => self.create_entry_block_array_allocation(ty, *size),
-                MlirTypeDecl::Pointer | MlirTypeDecl::Basic => {
+                MlirTypeDecl::Pointer(_) | MlirTypeDecl::Basic => {
                     self.create_entry_block_allocation(ty, ident)
                 }
             };
@@ -273,6 +397,12 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
             self.block_has_jumped = false;
             self.compile_mlir_basic_block(mlir_bb);
         }
+
+        // only unreachable code can fall off the end of the function, the analyzer has
+        // already checked that every reachable path returns
+        if !self.block_has_jumped {
+            self.builder().build_unreachable().unwrap();
+        }
     }
 
     fn create_entry_builder(&self) -> Builder<'ctx> {
@@ -320,20 +450,20 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
     }
 
     pub(in crate::codegen) fn convert_type(&self, ty: &MlirType) -> BasicTypeEnum<'ctx> {
-        if ty == &VOID_PTR {
+        if ty.is_pointer() {
+            // pointers are opaque, so the pointee type, which may be an incomplete struct,
+            // does not matter
             return self
                 .context
                 .i8_type()
                 .ptr_type(AddressSpace::default())
                 .into();
         }
-
         let basic_type = self.get_type_kind_as_llvm_any_type(&ty.kind);
 
         let finished_type = match &ty.decl {
             MlirTypeDecl::Array(size) => basic_type.array_type(*size as u32).into(),
-            MlirTypeDecl::Pointer => basic_type.ptr_type(AddressSpace::default()).into(),
-            MlirTypeDecl::Basic => basic_type,
+            _ => basic_type,
         };
 
         finished_type
@@ -342,21 +472,57 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
     fn get_type_kind_as_llvm_any_type(&self, kind: &MlirTypeKind) -> BasicTypeEnum<'ctx> {
         match kind {
             MlirTypeKind::Char(_) => self.context.i8_type().into(),
+            MlirTypeKind::Short(_) => self.context.i16_type().into(),
             MlirTypeKind::Int(_) => self.context.i32_type().into(),
             MlirTypeKind::Long(_) => self.context.i64_type().into(),
             MlirTypeKind::Float => self.context.f32_type().into(),
             MlirTypeKind::Double => self.context.f64_type().into(),
             MlirTypeKind::Struct(ident) => self.get_struct_type(ident).into(),
+            // pointers are opaque, so the pointee type does not matter
+            MlirTypeKind::FunctionPointer(_) => self
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::default())
+                .into(),
+            MlirTypeKind::Array(element) => self.convert_type(element),
+            MlirTypeKind::VaList => self.va_list_type(),
             _ => panic!(),
         }
     }
 
+    /// The type a parameter is passed as, which is its own type except for a va_list that is
+    /// passed by reference.
+    pub(in crate::codegen) fn convert_param_type(&self, ty: &MlirType) -> BasicTypeEnum<'ctx> {
+        if ty.is_va_list() && self.va_list_is_passed_by_reference() {
+            self.context
+                .i8_type()
+                .ptr_type(AddressSpace::default())
+                .into()
+        } else {
+            self.convert_type(ty)
+        }
+    }
+
+    pub(in crate::codegen) fn is_union(&self, ident: &InternedStr) -> bool {
+        self.mlir
+            .get_struct(ident)
+            .is_some_and(|_struct| _struct.is_union)
+    }
+
     fn create_struct_type(&mut self, _struct: &'mlir MlirStruct) -> StructType<'ctx> {
-        let member_types = _struct
-            .members
-            .iter()
-            .map(|field| self.convert_type(&field.ty))
-            .collect::<Vec<_>>();
+        let member_types = if _struct.is_union {
+            // an aligned blob the size of the largest member, members are read through casts
+            let unit = self.context.custom_width_int_type(_struct.align as u32 * 8);
+            vec![unit
+                .array_type((_struct.size / _struct.align) as u32)
+                .into()]
+        } else {
+            _struct
+                .members
+                .iter()
+                .map(|field| self.convert_type(&field.ty))
+                .collect::<Vec<_>>()
+        };
         let struct_type = self.context.struct_type(&member_types, false);
         self.struct_types.insert(_struct.ident.clone(), struct_type);
         struct_type
@@ -381,7 +547,10 @@ pub fn pre_construct_blocks(function_block: &MlirBlock) -> Vec<MlirBasicBlock<'_
                 label = Some(ident.clone());
             }
 
-            MlirStmt::Goto(_) | MlirStmt::CondGoto(_, _, _) | MlirStmt::Return(_) => {
+            MlirStmt::Goto(_)
+            | MlirStmt::CondGoto(_, _, _)
+            | MlirStmt::Switch(_, _, _)
+            | MlirStmt::Return(_) => {
                 stmts.push(stmt);
                 new_block!();
             }
@@ -0,0 +1,65 @@
+use clap::ValueEnum;
+use inkwell::passes::PassBuilderOptions;
+use inkwell::support::LLVMString;
+use inkwell::targets::TargetMachine;
+use inkwell::OptimizationLevel;
+
+use crate::codegen::Compiler;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OptLevel {
+    #[default]
+    #[value(name = "0")]
+    O0,
+    #[value(name = "1")]
+    O1,
+    #[value(name = "2")]
+    O2,
+    #[value(name = "3")]
+    O3,
+    #[value(name = "s")]
+    Os,
+}
+
+impl OptLevel {
+    /// The new pass manager pipeline that is run on the module before emission.
+    pub fn pipeline(&self) -> &'static str {
+        match self {
+            OptLevel::O0 => "default<O0>",
+            OptLevel::O1 => "default<O1>",
+            OptLevel::O2 => "default<O2>",
+            OptLevel::O3 => "default<O3>",
+            OptLevel::Os => "default<Os>",
+        }
+    }
+
+    /// The optimization level used by the backend when lowering to machine code.
+    pub fn codegen_level(&self) -> OptimizationLevel {
+        match self {
+            OptLevel::O0 => OptimizationLevel::None,
+            OptLevel::O1 => OptimizationLevel::Less,
+            OptLevel::O2 | OptLevel::Os => OptimizationLevel::Default,
+            OptLevel::O3 => OptimizationLevel::Aggressive,
+        }
+    }
+}
+
+impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
+    pub(in crate::codegen) fn run_passes(
+        &self,
+        machine: &TargetMachine,
+        opt_level: OptLevel,
+        print_passes: bool,
+    ) -> Result<(), LLVMString> {
+        let pipeline = opt_level.pipeline();
+        let options = PassBuilderOptions::create();
+
+        if print_passes {
+            // llvm logs every pass as it runs to stderr when debug logging is enabled
+            eprintln!("Pass pipeline: {pipeline}");
+            options.set_debug_logging(true);
+        }
+
+        self.module.run_passes(pipeline, machine, options)
+    }
+}
@@ -1,5 +1,6 @@
 use inkwell::values::BasicValue;
 
+use crate::codegen::abi::ReturnAbi;
 use crate::codegen::{Compiler, MlirBasicBlock};
 use crate::data::mlir::{MlirExpr, MlirStmt};
 use crate::util::str_intern::InternedStr;
@@ -27,6 +28,11 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
                     self.compile_cond_goto(condition, then, _else);
                 }
 
+                MlirStmt::Switch(value, cases, default) => {
+                    self.block_has_jumped = true;
+                    self.compile_switch(value, cases, default);
+                }
+
                 MlirStmt::Return(expression) => {
                     self.block_has_jumped = true;
                     self.compile_return_statement(expression);
@@ -52,20 +58,53 @@ impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
 
     #[inline(always)]
     fn compile_cond_goto(&mut self, condition: &MlirExpr, then: &InternedStr, _else: &InternedStr) {
-        let condition = self.compile_expression(condition).into_int_value();
-        let i1_type = self.context.bool_type();
-        let condition = self
-            .builder()
-            .build_int_cast(condition, i1_type, "conv_to_i1_type")
-            .unwrap();
+        let condition = self.compile_condition(condition);
         let then_block = self.get_block_by_name(then);
         let else_block = self.get_block_by_name(_else);
         self.builder()
             .build_conditional_branch(condition, then_block, else_block);
     }
 
+    fn compile_switch(
+        &mut self,
+        value: &MlirExpr,
+        cases: &[(i64, InternedStr)],
+        default: &InternedStr,
+    ) {
+        let value = self.compile_expression(value).into_int_value();
+        let int_type = value.get_type();
+        let cases = cases
+            .iter()
+            .map(|(case, label)| {
+                (
+                    int_type.const_int(*case as u64, true),
+                    self.get_block_by_name(label),
+                )
+            })
+            .collect::<Vec<_>>();
+        let default_block = self.get_block_by_name(default);
+        self.builder()
+            .build_switch(value, default_block, &cases)
+            .unwrap();
+    }
+
     #[inline(always)]
     fn compile_return_statement(&mut self, expression_opt: &Option<MlirExpr>) {
+        match (self.return_abi.clone(), expression_opt) {
+            (ReturnAbi::Sret, Some(expression)) => {
+                let sret = self.sret_ptr.unwrap();
+                self.compile_store(sret, expression);
+                self.builder().build_return(None).unwrap();
+                return;
+            }
+            (ReturnAbi::Coerced(coerced_type), Some(expression)) => {
+                let ptr = self.compile_struct_to_memory(expression);
+                let value = self.load_coerced(ptr, coerced_type, &expression.ty);
+                self.builder().build_return(Some(&value)).unwrap();
+                return;
+            }
+            _ => {}
+        }
         let expression = expression_opt
             .as_ref()
             .map(|expression| self.compile_expression(expression));
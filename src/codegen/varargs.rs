@@ -0,0 +1,220 @@
+use inkwell::intrinsics::Intrinsic;
+use inkwell::types::{BasicType, BasicTypeEnum};
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, PointerValue};
+use inkwell::{AddressSpace, IntPredicate};
+
+use crate::codegen::Compiler;
+use crate::data::mlir::{MlirExpr, MlirType};
+use crate::target::VaListKind;
+
+impl<'a, 'mlir, 'ctx> Compiler<'a, 'mlir, 'ctx> {
+    pub(in crate::codegen) fn va_list_type(&self) -> BasicTypeEnum<'ctx> {
+        let ptr_type = self.context.i8_type().ptr_type(AddressSpace::default());
+        let i32_type = self.context.i32_type();
+        match self.va_list_kind {
+            VaListKind::X86_64 => self
+                .context
+                .struct_type(
+                    &[
+                        i32_type.into(),
+                        i32_type.into(),
+                        ptr_type.into(),
+                        ptr_type.into(),
+                    ],
+                    false,
+                )
+                .array_type(1)
+                .into(),
+            VaListKind::Aapcs64 => self
+                .context
+                .struct_type(
+                    &[
+                        ptr_type.into(),
+                        ptr_type.into(),
+                        ptr_type.into(),
+                        i32_type.into(),
+                        i32_type.into(),
+                    ],
+                    false,
+                )
+                .into(),
+            VaListKind::CharPointer => ptr_type.into(),
+        }
+    }
+
+    /// An x86_64 va_list is an array, so it decays to a pointer when passed, while AAPCS
+    /// passes a pointer to a copy because the struct is larger than 16 bytes.
+    pub(in crate::codegen) fn va_list_is_passed_by_reference(&self) -> bool {
+        self.va_list_kind != VaListKind::CharPointer
+    }
+
+    pub(in crate::codegen) fn compile_argument(&mut self, arg: &MlirExpr) -> BasicValueEnum<'ctx> {
+        if !arg.ty.is_va_list() || !self.va_list_is_passed_by_reference() {
+            return self.compile_expression(arg);
+        }
+        let va_list = self.get_lval_as_pointer(arg);
+        if self.va_list_kind == VaListKind::X86_64 {
+            return BasicValueEnum::from(va_list);
+        }
+        let list_type = self.va_list_type();
+        let copy = self.create_entry_block_allocation(list_type, "va_list_copy");
+        let value = self
+            .builder()
+            .build_load(list_type, va_list, "va_list")
+            .unwrap();
+        self.builder().build_store(copy, value).unwrap();
+        BasicValueEnum::from(copy)
+    }
+
+    pub(in crate::codegen) fn compile_va_start(
+        &mut self,
+        va_list: &MlirExpr,
+    ) -> BasicValueEnum<'ctx> {
+        let va_list = self.get_lval_as_pointer(va_list);
+        self.call_va_intrinsic("llvm.va_start", &[va_list])
+    }
+
+    pub(in crate::codegen) fn compile_va_end(
+        &mut self,
+        va_list: &MlirExpr,
+    ) -> BasicValueEnum<'ctx> {
+        let va_list = self.get_lval_as_pointer(va_list);
+        self.call_va_intrinsic("llvm.va_end", &[va_list])
+    }
+
+    pub(in crate::codegen) fn compile_va_copy(
+        &mut self,
+        dest: &MlirExpr,
+        src: &MlirExpr,
+    ) -> BasicValueEnum<'ctx> {
+        let dest = self.get_lval_as_pointer(dest);
+        let src = self.get_lval_as_pointer(src);
+        self.call_va_intrinsic("llvm.va_copy", &[dest, src])
+    }
+
+    pub(in crate::codegen) fn compile_va_arg(
+        &mut self,
+        va_list: &MlirExpr,
+        ty: &MlirType,
+    ) -> BasicValueEnum<'ctx> {
+        let va_list = self.get_lval_as_pointer(va_list);
+        let ty = self.convert_type(ty);
+        if self.va_list_kind == VaListKind::Aapcs64 {
+            return self.compile_aapcs_va_arg(va_list, ty);
+        }
+        self.builder().build_va_arg(va_list, ty, "va_arg").unwrap()
+    }
+
+    /// LLVM only lowers the `va_arg` instruction for a va_list that is a plain pointer on
+    /// AArch64, so the AAPCS list is read by hand. Arguments are taken from the saved
+    /// registers while the offset of the register class is negative, then from the stack.
+    fn compile_aapcs_va_arg(
+        &mut self,
+        va_list: PointerValue<'ctx>,
+        ty: BasicTypeEnum<'ctx>,
+    ) -> BasicValueEnum<'ctx> {
+        let list_type = self.va_list_type().into_struct_type();
+        let ptr_type = self.context.i8_type().ptr_type(AddressSpace::default());
+        let i8_type = self.context.i8_type();
+        let i32_type = self.context.i32_type();
+        // floating point arguments are saved in the 16 byte vector registers
+        let (top_index, offs_index, register_size) = if ty.is_float_type() {
+            (2, 4, 16)
+        } else {
+            (1, 3, 8)
+        };
+
+        let current_block = self.builder().get_insert_block().unwrap();
+        let maybe_reg_block = self
+            .context
+            .insert_basic_block_after(current_block, "va_arg_maybe_reg");
+        let reg_block = self
+            .context
+            .insert_basic_block_after(maybe_reg_block, "va_arg_in_reg");
+        let stack_block = self
+            .context
+            .insert_basic_block_after(reg_block, "va_arg_on_stack");
+        let end_block = self
+            .context
+            .insert_basic_block_after(stack_block, "va_arg_end");
+
+        let builder = self.builder();
+        let offs_ptr = builder
+            .build_struct_gep(list_type, va_list, offs_index, "va_offs_ptr")
+            .unwrap();
+        let offs = builder
+            .build_load(i32_type, offs_ptr, "va_offs")
+            .unwrap()
+            .into_int_value();
+        let zero = i32_type.const_zero();
+        let used_up = builder
+            .build_int_compare(IntPredicate::SGE, offs, zero, "va_regs_used_up")
+            .unwrap();
+        builder
+            .build_conditional_branch(used_up, stack_block, maybe_reg_block)
+            .unwrap();
+
+        builder.position_at_end(maybe_reg_block);
+        let register_size = i32_type.const_int(register_size, false);
+        let next_offs = builder
+            .build_int_add(offs, register_size, "va_next_offs")
+            .unwrap();
+        builder.build_store(offs_ptr, next_offs).unwrap();
+        let in_reg = builder
+            .build_int_compare(IntPredicate::SLE, next_offs, zero, "va_in_reg")
+            .unwrap();
+        builder
+            .build_conditional_branch(in_reg, reg_block, stack_block)
+            .unwrap();
+
+        builder.position_at_end(reg_block);
+        let top_ptr = builder
+            .build_struct_gep(list_type, va_list, top_index, "va_top_ptr")
+            .unwrap();
+        let top = builder
+            .build_load(ptr_type, top_ptr, "va_top")
+            .unwrap()
+            .into_pointer_value();
+        let reg_addr = unsafe { builder.build_gep(i8_type, top, &[offs], "va_reg_addr") }.unwrap();
+        builder.build_unconditional_branch(end_block).unwrap();
+
+        builder.position_at_end(stack_block);
+        let stack_ptr = builder
+            .build_struct_gep(list_type, va_list, 0, "va_stack_ptr")
+            .unwrap();
+        let stack = builder
+            .build_load(ptr_type, stack_ptr, "va_stack")
+            .unwrap()
+            .into_pointer_value();
+        // every argument on the stack takes at least a slot of 8 bytes
+        let slot_size = i32_type.const_int(8, false);
+        let next_stack =
+            unsafe { builder.build_gep(i8_type, stack, &[slot_size], "va_next_stack") }.unwrap();
+        builder.build_store(stack_ptr, next_stack).unwrap();
+        builder.build_unconditional_branch(end_block).unwrap();
+
+        builder.position_at_end(end_block);
+        let addr = builder.build_phi(ptr_type, "va_arg_addr").unwrap();
+        addr.add_incoming(&[(&reg_addr, reg_block), (&stack, stack_block)]);
+        builder
+            .build_load(ty, addr.as_basic_value().into_pointer_value(), "va_arg")
+            .unwrap()
+    }
+
+    /// The stdarg intrinsics take the address of the va_list and return nothing.
+    fn call_va_intrinsic(
+        &mut self,
+        name: &str,
+        args: &[PointerValue<'ctx>],
+    ) -> BasicValueEnum<'ctx> {
+        let function = Intrinsic::find(name)
+            .and_then(|intrinsic| intrinsic.get_declaration(self.module, &[]))
+            .unwrap_or_else(|| panic!("Intrinsic '{name}' does not exist."));
+        let args = args
+            .iter()
+            .map(|arg| (*arg).into())
+            .collect::<Vec<BasicMetadataValueEnum>>();
+        self.builder().build_call(function, &args, name).unwrap();
+        BasicValueEnum::from(self.context.i8_type().const_int(0, false))
+    }
+}
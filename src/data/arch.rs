@@ -3,6 +3,8 @@ pub const CHAR_MAX: u64 = i8::MAX as u64;
 pub const CHAR_MIN: u64 = i8::MIN as u64;
 pub const UNSIGNED_CHAR_MAX: u64 = u8::MAX as u64;
 
+pub const SHORT_SIZE: u64 = 2;
+
 pub const LONG_SIZE: u64 = 8;
 pub const LONG_MAX: i64 = i64::MAX;
 pub const LONG_MIN: i64 = i64::MIN;
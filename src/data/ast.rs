@@ -51,7 +51,8 @@ pub struct StructDeclaration {
 pub struct FunctionDeclaration {
     pub declaration: Locatable<Declaration>,
     pub parameters: Vec<Locatable<Declaration>>,
-    pub body: Locatable<Block>, // don't support function prototypes
+    pub varargs: bool, // the parameters end with '...'
+    pub body: Option<Locatable<Block>>, // none for prototypes
 }
 
 #[derive(Debug)]
@@ -59,6 +60,7 @@ pub struct VariableDeclaration {
     pub declaration: Locatable<Declaration>,
     pub is_array: bool,
     pub array_size: Option<usize>,
+    pub inner_array_sizes: Vec<usize>, // the 20 in 'int grid[10][20]'
     pub initializer: Option<Locatable<Expression>>,
 }
 
@@ -73,19 +75,66 @@ pub struct DeclarationSpecifier {
     pub specifiers: Vec<StorageSpecifier>,
     pub qualifiers: Vec<TypeQualifier>,
     pub ty: Vec<TypeSpecifier>,
-    pub pointer: bool, // only supporting one pointer depth
+    pub pointer_depth: u8,
+    pub function_pointer: Option<Box<FunctionPointerDeclarator>>,
+    pub array_pointer: Option<ArrayPointerDeclarator>,
+    pub parameter_array: Option<ParameterArrayDeclarator>,
+}
+
+/// The `(*ident[size])(params)` part of `int (*ident[size])(params)`, the rest of the
+/// declaration specifier is the return type of the function pointed to.
+#[derive(Debug)]
+pub struct FunctionPointerDeclarator {
+    pub pointer_depth: u8,
+    pub array_size: Option<usize>,
+    pub parameters: Vec<Locatable<Declaration>>,
+    pub varargs: bool,
+}
+
+/// The `(*ident)[size]` part of `int (*ident)[size]`, the rest of the declaration specifier
+/// is the element type of the arrays pointed to.
+#[derive(Debug)]
+pub struct ArrayPointerDeclarator {
+    pub pointer_depth: u8,
+    pub array_sizes: Vec<usize>,
+}
+
+/// The `[size][20]` part of a parameter `int ident[size][20]`, where the first size can be
+/// left out. The parameter is a pointer to the first element, `int (*ident)[20]`.
+#[derive(Debug)]
+pub struct ParameterArrayDeclarator {
+    pub array_size: Option<usize>,
+    pub inner_array_sizes: Vec<usize>,
+}
+
+impl DeclarationSpecifier {
+    pub fn is_typedef(&self) -> bool {
+        self.specifiers.contains(&StorageSpecifier::Typedef)
+    }
 }
 
 #[derive(Debug)]
 pub enum TypeSpecifier {
     Void,
     Char,
+    Short,
     Int,
     Long,
+    Float,
     Double,
     Signed,
     Unsigned,
+    VaList,
     Struct(InternedStr),
+    Union(InternedStr),
+    Enum(Option<InternedStr>, Option<Vec<Locatable<Enumerator>>>), // tag, enumerators if defined here
+    TypedefName(InternedStr),
+}
+
+#[derive(Debug)]
+pub struct Enumerator {
+    pub ident: Locatable<InternedStr>,
+    pub value: Option<Locatable<Expression>>,
 }
 
 impl TryFrom<&Token> for TypeSpecifier {
@@ -95,20 +144,25 @@ impl TryFrom<&Token> for TypeSpecifier {
         use TypeSpecifier::*;
         match value {
             Token::Keyword(Keyword::Int) => Ok(Int),
+            Token::Keyword(Keyword::Float) => Ok(Float),
             Token::Keyword(Keyword::Double) => Ok(Double),
             Token::Keyword(Keyword::Void) => Ok(Void),
             Token::Keyword(Keyword::Char) => Ok(Char),
+            Token::Keyword(Keyword::Short) => Ok(Short),
             Token::Keyword(Keyword::Long) => Ok(Long),
             Token::Keyword(Keyword::Signed) => Ok(Signed),
             Token::Keyword(Keyword::Unsigned) => Ok(Unsigned),
+            Token::Keyword(Keyword::VaList) => Ok(VaList),
             _ => Err(()),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum StorageSpecifier {
     Static,
+    Extern,
+    Typedef,
 }
 
 impl TryFrom<&Token> for StorageSpecifier {
@@ -118,6 +172,8 @@ impl TryFrom<&Token> for StorageSpecifier {
         use StorageSpecifier::*;
         match value {
             Token::Keyword(Keyword::Static) => Ok(Static),
+            Token::Keyword(Keyword::Extern) => Ok(Extern),
+            Token::Keyword(Keyword::Typedef) => Ok(Typedef),
             _ => Err(()),
         }
     }
@@ -150,12 +206,18 @@ pub enum Statement {
         Option<Box<Locatable<Statement>>>,
     ),
     While(Locatable<Expression>, Box<Locatable<Statement>>),
+    DoWhile(Box<Locatable<Statement>>, Locatable<Expression>),
     For(
         Option<Locatable<VariableDeclaration>>,
         Option<Locatable<Expression>>,
         Option<Locatable<Expression>>,
         Box<Locatable<Statement>>,
     ),
+    Switch(Locatable<Expression>, Box<Locatable<Statement>>),
+    Case(Locatable<Expression>),
+    Default,
+    Label(InternedStr),
+    Goto(InternedStr),
     Break,
     Continue,
     Return(Option<Locatable<Expression>>),
@@ -177,17 +239,40 @@ pub enum Expression {
         Locatable<Box<Expression>>,
         Locatable<Box<Expression>>,
     ),
-    FunctionCall(Locatable<InternedStr>, Vec<Locatable<Expression>>),
+    Ternary(
+        Locatable<Box<Expression>>,
+        Locatable<Box<Expression>>,
+        Locatable<Box<Expression>>,
+    ),
+    FunctionCall(Locatable<Box<Expression>>, Vec<Locatable<Expression>>),
     Index(Locatable<Box<Expression>>, Locatable<Box<Expression>>),
     Member(Locatable<Box<Expression>>, Locatable<InternedStr>),
     PointerMember(Locatable<Box<Expression>>, Locatable<InternedStr>),
     Cast(Locatable<Declaration>, Locatable<Box<Expression>>),
-    ArrayInitializer(Vec<Locatable<Expression>>),
+    ArrayInitializer(Vec<InitializerElement>),
+    // the builtins behind the <stdarg.h> macros
+    VaStart(Locatable<Box<Expression>>, Locatable<InternedStr>),
+    VaArg(Locatable<Box<Expression>>, Locatable<Declaration>),
+    VaEnd(Locatable<Box<Expression>>),
+    VaCopy(Locatable<Box<Expression>>, Locatable<Box<Expression>>),
+}
+
+/// An element of a braced initializer, `.x = 1` and `[3] = 7` name the element they initialize.
+#[derive(Debug)]
+pub struct InitializerElement {
+    pub designator: Option<Designator>,
+    pub value: Locatable<Expression>,
+}
+
+#[derive(Debug)]
+pub enum Designator {
+    Member(Locatable<InternedStr>),
+    Index(Locatable<Box<Expression>>),
 }
 
 #[derive(Debug)]
 pub enum TypeOrExpression {
-    Type(Declaration),
+    Type(Box<Declaration>),
     Expr(Box<Expression>),
 }
 
@@ -310,6 +395,7 @@ impl TryFrom<&Token> for BinaryOp {
 
             Token::Symbol(Symbol::Ampersand) => Ok(BitwiseAnd),
             Token::Symbol(Symbol::DoubleAmpersand) => Ok(LogicalAnd),
+            Token::Symbol(Symbol::DoublePipe) => Ok(LogicalOr),
             Token::Symbol(Symbol::Pipe) => Ok(BitwiseOr),
             Token::Symbol(Symbol::Caret) => Ok(BitwiseXor),
             Token::Symbol(Symbol::LeftShift) => Ok(LeftShift),
@@ -1,5 +1,8 @@
+use std::collections::HashSet;
+
 use thiserror::Error;
 
+use crate::util::diagnostics::{Diagnostic, Severity};
 use crate::util::str_intern::InternedStr;
 use crate::util::Span;
 
@@ -29,6 +32,10 @@ impl Reporter {
         self.warnings.push(warning);
     }
 
+    pub fn warnings(&self) -> &[CompilerWarning] {
+        &self.warnings
+    }
+
     pub fn status(&self) -> Result<(), ()> {
         if self.errors.is_empty() {
             Ok(())
@@ -45,10 +52,10 @@ pub enum CompilerError {
     #[error("Could not find fn 'main', no entry point!")]
     MissingMain,
 
-    #[error("Invalid integer literal: {0}")]
+    #[error("Invalid integer literal")]
     ParseIntError(Span),
 
-    #[error("Invalid float literal: {0}")]
+    #[error("Invalid float literal")]
     ParseFloatError(Span),
 
     #[error("Invalid integer suffix: {0}")]
@@ -60,37 +67,37 @@ pub enum CompilerError {
     #[error("Invalid symbol: {0}")]
     InvalidSymbol(String, Span),
 
-    #[error("Found `{0}` but expected one of the following: \n\t{1}\n")]
+    #[error("Expected one of the following: `{0}`, but found {1}")]
     ExpectedVariety(String, String, Span),
 
     #[error("Expected `{0}` but found {1}")]
     ExpectedButFound(String, String, Span),
 
-    #[error("Invalid hex literal: {0}")]
+    #[error("Invalid hex literal")]
     InvalidHexLiteral(Span),
 
-    #[error("Invalid octal literal: {0}")]
+    #[error("Invalid octal literal")]
     InvalidOctalLiteral(Span),
 
-    #[error("Invalid binary literal: {0}")]
+    #[error("Invalid binary literal")]
     InvalidBinaryLiteral(Span),
 
-    #[error("Invalid escape sequence: {0}")]
+    #[error("Invalid escape sequence")]
     InvalidEscapeSequence(Span),
 
-    #[error("Invalid character literal: {0}")]
+    #[error("Invalid character literal")]
     InvalidCharacterLiteral(Span),
 
-    #[error("Unclosed string literal: {0}")]
+    #[error("Unclosed string literal")]
     UnclosedStringLiteral(Span),
 
-    #[error("Unclosed char literal: {0}")]
+    #[error("Unclosed char literal")]
     UnclosedCharLiteral(Span),
 
-    #[error("Cannot cast '{0}' to '{1}': {2}")]
+    #[error("Cannot cast '{0}' to '{1}'")]
     CannotCast(String, String, Span),
 
-    #[error("Cannot assign '{1}' to lval with type of '{0}': {2}")]
+    #[error("Cannot assign '{1}' to lval with type of '{0}'")]
     CannotAssign(String, String, Span),
 
     #[error("Unknown identifier \"{0}\"")]
@@ -100,27 +107,27 @@ pub enum CompilerError {
     MustReturn(String, Span),
 
     #[error("Unclosed parenthesis")]
-    UnclosedParenthesis,
+    UnclosedParenthesis(Span),
 
     #[error("Unclosed block")]
-    UnclosedBlock,
+    UnclosedBlock(Span),
 
     #[error("Unclosed array")]
-    UnclosedArray,
+    UnclosedArray(Span),
 
-    #[error("Parenthesis has no opening.")]
-    ParenthesisHasNoOpening,
+    #[error("Parenthesis has no opening")]
+    ParenthesisHasNoOpening(Span),
 
-    #[error("Curly has no opening.")]
-    BlockHasNoOpening,
+    #[error("Curly has no opening")]
+    BlockHasNoOpening(Span),
 
-    #[error("Unexpected end of file.")]
-    UnexpectedEOF,
+    #[error("Unexpected end of file")]
+    UnexpectedEOF(Span),
 
-    #[error("This identifier already exists in this scope and cannot be redeclared: {0}")]
-    IdentifierExists(Span),
+    #[error("This identifier already exists in this scope and cannot be redeclared")]
+    IdentifierExists(Span, Option<Span>), // and the previous declaration
 
-    #[error("The identifier '{0}' cannot be found in the current scope: {1}")]
+    #[error("The identifier '{0}' cannot be found in the current scope")]
     IdentNotFound(InternedStr, Span),
 
     #[error("{0}")]
@@ -129,198 +136,831 @@ pub enum CompilerError {
     #[error("Else without if")]
     ElseWithNoIf(Span),
 
-    #[error("The arguments to this function are of incorrect types.")]
+    #[error("The arguments to this function are of incorrect types")]
     FunctionTypeMismatch(Span),
 
-    #[error("This is not a function.")]
+    #[error("This is not a function")]
     NotAFunction(Span),
 
-    #[error("This is not a variable.")]
+    #[error("This is not a variable")]
     NotAVariable(Span),
 
-    #[error("Variable type mismatch. Cannot assign {0} to type {1}")]
+    #[error("Variable type mismatch. Cannot assign {1} to type {2}")]
     VariableTypeMismatch(Span, String, String),
 
-    #[error("Declaration is missing identifier: {0}")]
+    #[error("Declaration is missing identifier")]
     DeclarationMissingIdentifier(Span),
 
-    #[error("Type '{0}' can not be signed or unsigned: {1}")]
+    #[error("Type '{0}' can not be signed or unsigned")]
     TypeCannotBeSignedOrUnsigned(String, Span),
 
-    #[error("Cannot combine signed and unsigned: {0}")]
+    #[error("Cannot combine signed and unsigned")]
     CannotCombineSignedAndUnsigned(Span),
 
-    #[error("Expected a full type specifier here: {0}")]
+    #[error("Expected a full type specifier here")]
     ExpectedTypeSpecifier(Span),
 
-    #[error("Array needs a size: {0}")]
+    #[error("Array needs a size")]
     ArraySizeNotSpecified(Span),
 
-    #[error("Invalid array operation: {0}")]
+    #[error("Invalid array operation")]
     InvalidArrayOperation(Span),
 
-    #[error("Invalid binary operation '{0}' between '{1}' and '{2}': {3}")]
+    #[error("Invalid binary operation '{0}' between '{1}' and '{2}'")]
     InvalidBinaryOperation(String, String, String, Span),
 
-    #[error("Left hand operand is not assignable: {0}")]
+    #[error("Left hand operand is not assignable")]
     LeftHandNotLVal(Span),
 
-    #[error("Invalid type specifier: {0}")]
+    #[error("Invalid type specifier")]
     InvalidTypeSpecifier(Span),
 
-    #[error("Type specifier '{0}' is invalid in this position: {1}")]
+    #[error("Type specifier '{0}' is invalid in this position")]
     InvalidTypeSpecifierOrder(String, Span),
 
-    #[error("Not a struct: {0}")]
+    #[error("Not a struct")]
     NotAStruct(Span),
 
-    #[error("Ident '{0}' is not a member of the struct definition for 'struct {1}': {2}")]
+    #[error("Ident '{0}' is not a member of the struct definition for 'struct {1}'")]
     MemberNotFound(String, String, Span),
 
-    #[error("Cannot assign to a const variable: {0}")]
+    #[error("Cannot assign to a const variable")]
     ConstAssignment(Span),
 
-    #[error("Number to large to be represented with any type: {0}")]
+    #[error("Number to large to be represented with any type")]
     NumberTooLarge(Span),
 
-    #[error("Cannot increment the type `{0}`: {1}")]
+    #[error("Cannot increment the type `{0}`")]
     CannotIncrementType(String, Span),
 
-    #[error("Cannot negate a non-numeric type: {0}")]
+    #[error("Cannot negate a non-numeric type")]
     NonNumericNegation(Span),
 
-    #[error("Cannot perform a bitwise operation on `{0}`: {1}")]
+    #[error("Cannot perform a bitwise operation on `{0}`")]
     CannotBitwise(String, Span),
 
-    #[error("Cannot perform a logical operation on this type '{0}': {1}")]
+    #[error("Cannot perform a logical operation on this type '{0}'")]
     NotLogicalType(String, Span),
 
-    #[error("Type `{0}` on left side of subscript cannot be indexed: {1}")]
+    #[error("Type `{0}` on left side of subscript cannot be indexed")]
     InvalidLeftOfSubScript(String, Span),
 
-    #[error("Cannot index with non integer type `{0}`: {1}")]
+    #[error("Cannot index with non integer type `{0}`")]
     CannotIndexWith(String, Span),
 
-    #[error("Cannot use '.' operator on type `{0}`: {1}")]
+    #[error("Cannot use '.' operator on type `{0}`")]
     CannotMemberAccessOnType(String, Span),
 
-    #[error("Left hand side is pointer, did you mean to use '->'? : {0}")]
+    #[error("Cannot use '.' on a pointer")]
     DotOperatorOnPointer(Span),
 
-    #[error("Left hand side is not a pointer, did you mean to use '.'? : {0}")]
+    #[error("Cannot use '->' on a value that is not a pointer")]
     ArrowOnNonPointer(Span),
 
-    #[error("Incomplete type: {0}")]
+    #[error("Incomplete type")]
     IncompleteType(Span),
 
-    #[error("Cannot perform equivalence operation between '{0}' and '{1}': {2}")]
+    #[error("Cannot perform equivalence operation between '{0}' and '{1}'")]
     CannotEq(String, String, Span),
 
-    #[error("Function requires an identifier: {0}")]
+    #[error("Function requires an identifier")]
     FunctionRequiresIdentifier(Span),
 
-    #[error("Function cannot have storage specifiers: {0}")]
+    #[error("Function cannot have storage specifiers")]
     FunctionStorageSpecifiers(Span),
 
-    #[error("Parameter for function '{0}' of type '{1}' requires identifier: {2}")]
+    #[error("Parameter for function '{0}' of type '{1}' requires identifier")]
     ParamRequiresIdent(String, String, Span),
 
-    #[error("Parameter cannot have storage specifiers: {0}")]
+    #[error("Parameter cannot have storage specifiers")]
     ParamStorageSpecifiers(Span),
 
-    #[error("Cannot use '->' on type '{0}': {1}")]
+    #[error("Cannot use '->' on type '{0}'")]
     CannotPointerMemberAccess(String, Span),
 
-    #[error("Argument type '{0}' does not match function argument type '{1}': {2}")]
+    #[error("Argument type '{0}' does not match function argument type '{1}'")]
     ArgumentTypeMismatch(String, String, Span),
 
-    #[error("A member with the identifier '{0}' already exists in this scope: {1}")]
+    #[error("A member with the identifier '{0}' already exists in this scope")]
     MemberAlreadyExists(InternedStr, Span),
 
-    #[error("Expected '{{' but found '*': {0}")]
+    #[error("Expected '{{' but found '*'")]
     StructDeclarationPointer(Span),
 
-    #[error("Struct definitions cannot be given declaration qualifiers: {0}")]
+    #[error("Struct definitions cannot be given declaration qualifiers")]
     StructDeclarationQualifiers(Span),
 
-    #[error("Struct definitions cannot possess storage specifiers: {0}")]
+    #[error("Struct definitions cannot possess storage specifiers")]
     StructStorageSpecifiers(Span),
 
-    #[error("Struct must be given an identifier: {0}")]
+    #[error("Struct must be given an identifier")]
     StructMissingIdent(Span),
 
-    #[error("The identifier 'main' is reserved as a function only: {0}")]
+    #[error("The identifier 'main' is reserved as a function only")]
     MainIsReserved(Span),
 
-    #[error("Cannot deref type '{0}' as it is not a pointer: {1}")]
-    DerefOnNonPointer(String, Span),
+    #[error("Invalid signature for 'main'")]
+    InvalidMainSignature(Span),
 
-    #[error("Cannot take an address of a pointer, not in this language, yet: {0}")]
-    AttemptedAddressOfPointer(Span),
+    #[error("Cannot deref type '{0}' as it is not a pointer")]
+    DerefOnNonPointer(String, Span),
 
-    #[error("Function must return type '{0}', cannot return '{1}': {0}")]
+    #[error("Function must return type '{0}', cannot return '{1}'")]
     InvalidReturnType(String, String, Span),
 
-    #[error("Keyword 'continue' has no corresponding loop: {0}")]
+    #[error("Keyword 'continue' has no corresponding loop")]
     ContinueWithoutLoop(Span),
 
-    #[error("Keyword 'break' has no corresponding loop: {0}")]
+    #[error("Keyword 'break' has no corresponding loop or switch")]
     BreakWithoutLoop(Span),
 
-    #[error("Function '{0}' has no return or is not guaranteed to return: {1}")]
+    #[error("Label '{0}' is not within a switch statement")]
+    CaseWithoutSwitch(String, Span),
+
+    #[error("Switch statements require an integer controlling expression, found '{0}'")]
+    SwitchRequiresInteger(String, Span),
+
+    #[error("Case label does not reduce to an integer constant")]
+    CaseRequiresConstant(Span),
+
+    #[error("Duplicate case value '{0}' in switch statement")]
+    DuplicateCaseValue(i64, Span),
+
+    #[error("Multiple default labels in one switch statement")]
+    DuplicateDefaultLabel(Span),
+
+    #[error("Function '{0}' has no return or is not guaranteed to return")]
     FunctionMissingReturn(String, Span),
 
-    #[error("Cannot implicitly cast '{0}' to '{1}': {2}")]
+    #[error("Declaration of function '{0}' does not match its previous declaration")]
+    FunctionSignatureMismatch(String, Span, Option<Span>),
+
+    #[error("Function '{0}' has already been defined")]
+    FunctionRedefinition(String, Span, Option<Span>),
+
+    #[error("Cannot implicitly cast '{0}' to '{1}'")]
     CannotImplicitCast(String, String, Span),
 
-    #[error("Cannot cast '{0}' to '{1}': {2}")]
+    #[error("Cannot cast '{0}' to '{1}'")]
     CannotExplicitCast(String, String, Span),
 
-    #[error("Cannot take address of non lval type: {0}")]
+    #[error("Cannot take address of non lval type")]
     CannotAddressNonLVal(Span),
 
-    #[error("Subscript of pointer with incomplete type '{0}': {1}")]
+    #[error("Subscript of pointer with incomplete type '{0}'")]
     IncompleteSubscript(String, Span),
+
+    #[error("Value of enumerator '{0}' does not reduce to an integer constant")]
+    EnumeratorRequiresConstant(String, Span),
+
+    #[error("Value {1} of enumerator '{0}' cannot be represented by 'int'")]
+    EnumeratorOutOfRange(String, i64, Span),
+
+    #[error("'enum {0}' has not been declared")]
+    EnumNotFound(String, Span),
+
+    #[error("'{0}' is defined as the wrong kind of tag")]
+    WrongKindOfTag(String, Span),
+
+    #[error("Label '{0}' is used but never defined")]
+    UndefinedLabel(String, Span),
+
+    #[error("Label '{0}' has already been defined in this function")]
+    DuplicateLabel(String, Span, Option<Span>),
+
+    #[error("Too many initializers for '{0}'")]
+    TooManyInitializers(String, Span),
+
+    #[error("Designator '{0}' cannot be used to initialize '{1}'")]
+    InvalidDesignator(String, String, Span),
+
+    #[error("Array designator index is not a constant expression")]
+    DesignatorRequiresConstant(Span),
+
+    #[error("Designator index {0} is out of bounds for '{1}'")]
+    DesignatorOutOfBounds(i64, String, Span),
+
+    #[error("'{0}' is an incomplete type")]
+    IncompleteStruct(String, Span),
+
+    #[error("Unknown preprocessor directive '#{0}'")]
+    UnknownDirective(String, Span),
+
+    #[error("Cannot find include file '{0}'")]
+    IncludeNotFound(String, Span),
+
+    #[error("#include nested too deeply")]
+    IncludeDepthExceeded(Span),
+
+    #[error("Unterminated '#{0}'")]
+    UnterminatedConditional(String, Span),
+
+    #[error("'#{0}' without a matching '#if'")]
+    UnmatchedConditional(String, Span),
+
+    #[error("'#{0}' after '#else'")]
+    DirectiveAfterElse(String, Span, Option<Span>),
+
+    #[error("Macro '{0}' is redefined differently")]
+    MacroRedefinition(String, Span, Option<Span>),
+
+    #[error("Wrong number of arguments to macro '{0}', expected {1} but found {2}")]
+    MacroArgumentCount(String, usize, usize, Span),
+
+    #[error("Unterminated call to macro '{0}'")]
+    UnterminatedMacroCall(String, Span),
+
+    #[error("Pasting '{0}' and '{1}' does not give a valid token")]
+    InvalidTokenPaste(String, String, Span),
+
+    #[error("Invalid expression in preprocessor conditional")]
+    InvalidConditionExpression(Span),
+
+    #[error("#error {0}")]
+    ErrorDirective(String, Span),
+
+    #[error("'{0}' is declared static after a non-static declaration")]
+    StaticAfterNonStatic(String, Span, Option<Span>),
+
+    #[error("Declaration of variable '{0}' does not match its previous declaration")]
+    VariableSignatureMismatch(String, Span, Option<Span>),
+
+    #[error("'{0}' is not declared, it is part of '<{1}>'")]
+    MissingLibraryInclude(String, &'static str, Span),
+
+    #[error("A variadic function needs a named parameter before '...'")]
+    VarargsRequireParameter(Span),
+
+    #[error("'va_start' used in a function with fixed arguments")]
+    VaStartOutsideVariadic(Span),
+
+    #[error("The second argument of 'va_start' must be the last parameter '{0}'")]
+    VaStartNotLastParameter(String, Span),
+
+    #[error("Expected a 'va_list' but found '{0}'")]
+    NotAVaList(String, Span),
+
+    #[error("'{0}' is promoted to '{1}' when passed through '...'")]
+    PromotedVaArgType(String, String, Span),
+
+    #[error("'va_list' can only be the type of a local variable or a parameter")]
+    VaListNotAllowed(Span),
+
+    #[error("A 'va_list' cannot be assigned")]
+    VaListAssignment(Span),
+
+    #[error("Pointers cannot have more than {} levels of indirection", u8::MAX)]
+    PointerDepthExceeded(Span),
+
+    #[error("Local variables cannot be '{0}', it is only supported at file scope")]
+    LocalStorageSpecifier(String, Span),
 }
 
 #[derive(Error, Debug)]
 pub enum CompilerWarning {
-    #[error("Item '{0}' is not used: {1}")]
+    #[error("Item '{0}' is not used")]
     UnusedItem(String, Span),
 
-    #[error("This expression has no effect: {0}")]
+    #[error("This expression has no effect")]
     ExprNoEffect(Span),
 
-    #[error("Suffixes are currently ignored: {0}")]
+    #[error("Suffixes are currently ignored")]
     SuffixIgnored(Span),
 
-    #[error("Unused variable: {0}")]
+    #[error("Unused variable")]
     UnusedVariable(Span),
 
-    #[error("Unused function: {0}")]
+    #[error("Unused function")]
     UnusedFunction(Span),
 
-    #[error("Unused parameter: {0}")]
+    #[error("Unused parameter")]
     UnusedParameter(Span),
 
-    #[error("Unused constant: {0}")]
+    #[error("Unused constant")]
     UnusedConstant(Span),
 
-    #[error("Unused struct: {0}")]
+    #[error("Unused struct")]
     UnusedStruct(Span),
 
-    #[error("Unreachable code: {0}")]
+    #[error("Unused label")]
+    UnusedLabel(Span),
+
+    #[error("Unreachable code")]
     UnreachableCode(Span),
 
-    #[error("Variable is not initialized at this point: {0}")]
+    #[error("Variable may not be initialized at this point")]
     UninitializedVariable(Span),
 
-    #[error("This storage specifier '{0}' is currently not supported: {1}")]
+    #[error("This storage specifier '{0}' is currently not supported")]
     UnsupportedStorageSpecifier(String, Span),
 
-    #[error("This type qualifier '{0}' is currently not supported: {1}")]
+    #[error("This type qualifier '{0}' is currently not supported")]
     UnsupportedTypeQualifier(String, Span),
 
-    #[error("Redundant usage of qualifier '{0}: {1}'")]
+    #[error("Redundant usage of qualifier '{0}'")]
     RedundantUsage(String, Span),
+
+    #[error("Enumerator '{0}' has the same value as '{1}'")]
+    DuplicateEnumeratorValue(String, String, Span),
+
+    #[error("Converting '{0}' to '{1}' changes the signedness of the pointed to 'char'")]
+    PointerSign(String, String, Span),
+}
+
+impl CompilerError {
+    /// A stable code for each kind of error, existing codes must never be reused or renumbered.
+    pub fn code(&self) -> &'static str {
+        use CompilerError::*;
+        match self {
+            IoError(..) => "E0001",
+            MissingMain => "E0002",
+            ParseIntError(..) => "E0003",
+            ParseFloatError(..) => "E0004",
+            InvalidIntegerSuffix(..) => "E0005",
+            InvalidFloatSuffix(..) => "E0006",
+            InvalidSymbol(..) => "E0007",
+            ExpectedVariety(..) => "E0008",
+            ExpectedButFound(..) => "E0009",
+            InvalidHexLiteral(..) => "E0010",
+            InvalidOctalLiteral(..) => "E0011",
+            InvalidBinaryLiteral(..) => "E0012",
+            InvalidEscapeSequence(..) => "E0013",
+            InvalidCharacterLiteral(..) => "E0014",
+            UnclosedStringLiteral(..) => "E0015",
+            UnclosedCharLiteral(..) => "E0016",
+            CannotCast(..) => "E0017",
+            CannotAssign(..) => "E0018",
+            UnknownIdentifier(..) => "E0019",
+            MustReturn(..) => "E0020",
+            UnclosedParenthesis(..) => "E0021",
+            UnclosedBlock(..) => "E0022",
+            UnclosedArray(..) => "E0023",
+            ParenthesisHasNoOpening(..) => "E0024",
+            BlockHasNoOpening(..) => "E0025",
+            UnexpectedEOF(..) => "E0026",
+            IdentifierExists(..) => "E0027",
+            IdentNotFound(..) => "E0028",
+            CustomError(..) => "E0029",
+            ElseWithNoIf(..) => "E0030",
+            FunctionTypeMismatch(..) => "E0031",
+            NotAFunction(..) => "E0032",
+            NotAVariable(..) => "E0033",
+            VariableTypeMismatch(..) => "E0034",
+            DeclarationMissingIdentifier(..) => "E0035",
+            TypeCannotBeSignedOrUnsigned(..) => "E0036",
+            CannotCombineSignedAndUnsigned(..) => "E0037",
+            ExpectedTypeSpecifier(..) => "E0038",
+            ArraySizeNotSpecified(..) => "E0039",
+            InvalidArrayOperation(..) => "E0040",
+            InvalidBinaryOperation(..) => "E0041",
+            LeftHandNotLVal(..) => "E0042",
+            InvalidTypeSpecifier(..) => "E0043",
+            InvalidTypeSpecifierOrder(..) => "E0044",
+            NotAStruct(..) => "E0045",
+            MemberNotFound(..) => "E0046",
+            ConstAssignment(..) => "E0047",
+            NumberTooLarge(..) => "E0048",
+            CannotIncrementType(..) => "E0049",
+            NonNumericNegation(..) => "E0050",
+            CannotBitwise(..) => "E0051",
+            NotLogicalType(..) => "E0052",
+            InvalidLeftOfSubScript(..) => "E0053",
+            CannotIndexWith(..) => "E0054",
+            CannotMemberAccessOnType(..) => "E0055",
+            DotOperatorOnPointer(..) => "E0056",
+            ArrowOnNonPointer(..) => "E0057",
+            IncompleteType(..) => "E0058",
+            CannotEq(..) => "E0059",
+            FunctionRequiresIdentifier(..) => "E0060",
+            FunctionStorageSpecifiers(..) => "E0061",
+            ParamRequiresIdent(..) => "E0062",
+            ParamStorageSpecifiers(..) => "E0063",
+            CannotPointerMemberAccess(..) => "E0064",
+            ArgumentTypeMismatch(..) => "E0065",
+            MemberAlreadyExists(..) => "E0066",
+            StructDeclarationPointer(..) => "E0067",
+            StructDeclarationQualifiers(..) => "E0068",
+            StructStorageSpecifiers(..) => "E0069",
+            StructMissingIdent(..) => "E0070",
+            MainIsReserved(..) => "E0071",
+            InvalidMainSignature(..) => "E0072",
+            DerefOnNonPointer(..) => "E0073",
+            InvalidReturnType(..) => "E0074",
+            ContinueWithoutLoop(..) => "E0075",
+            BreakWithoutLoop(..) => "E0076",
+            CaseWithoutSwitch(..) => "E0077",
+            SwitchRequiresInteger(..) => "E0078",
+            CaseRequiresConstant(..) => "E0079",
+            DuplicateCaseValue(..) => "E0080",
+            DuplicateDefaultLabel(..) => "E0081",
+            FunctionMissingReturn(..) => "E0082",
+            FunctionSignatureMismatch(..) => "E0083",
+            FunctionRedefinition(..) => "E0084",
+            CannotImplicitCast(..) => "E0085",
+            CannotExplicitCast(..) => "E0086",
+            CannotAddressNonLVal(..) => "E0087",
+            IncompleteSubscript(..) => "E0088",
+            EnumeratorRequiresConstant(..) => "E0089",
+            EnumeratorOutOfRange(..) => "E0090",
+            EnumNotFound(..) => "E0091",
+            WrongKindOfTag(..) => "E0092",
+            UndefinedLabel(..) => "E0093",
+            DuplicateLabel(..) => "E0094",
+            TooManyInitializers(..) => "E0095",
+            InvalidDesignator(..) => "E0096",
+            DesignatorRequiresConstant(..) => "E0097",
+            DesignatorOutOfBounds(..) => "E0098",
+            IncompleteStruct(..) => "E0099",
+            UnknownDirective(..) => "E0100",
+            IncludeNotFound(..) => "E0101",
+            IncludeDepthExceeded(..) => "E0102",
+            UnterminatedConditional(..) => "E0103",
+            UnmatchedConditional(..) => "E0104",
+            DirectiveAfterElse(..) => "E0105",
+            MacroRedefinition(..) => "E0106",
+            MacroArgumentCount(..) => "E0107",
+            UnterminatedMacroCall(..) => "E0108",
+            InvalidTokenPaste(..) => "E0109",
+            InvalidConditionExpression(..) => "E0110",
+            ErrorDirective(..) => "E0111",
+            StaticAfterNonStatic(..) => "E0112",
+            VariableSignatureMismatch(..) => "E0113",
+            MissingLibraryInclude(..) => "E0114",
+            VarargsRequireParameter(..) => "E0115",
+            VaStartOutsideVariadic(..) => "E0116",
+            VaStartNotLastParameter(..) => "E0117",
+            NotAVaList(..) => "E0118",
+            PromotedVaArgType(..) => "E0119",
+            VaListNotAllowed(..) => "E0120",
+            VaListAssignment(..) => "E0121",
+            PointerDepthExceeded(..) => "E0122",
+            LocalStorageSpecifier(..) => "E0123",
+        }
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        use CompilerError::*;
+        match self {
+            ParseIntError(span)
+            | ParseFloatError(span)
+            | InvalidIntegerSuffix(_, span)
+            | InvalidFloatSuffix(_, span)
+            | InvalidSymbol(_, span)
+            | ExpectedVariety(_, _, span)
+            | ExpectedButFound(_, _, span)
+            | InvalidHexLiteral(span)
+            | InvalidOctalLiteral(span)
+            | InvalidBinaryLiteral(span)
+            | InvalidEscapeSequence(span)
+            | InvalidCharacterLiteral(span)
+            | UnclosedStringLiteral(span)
+            | UnclosedCharLiteral(span)
+            | CannotCast(_, _, span)
+            | CannotAssign(_, _, span)
+            | UnknownIdentifier(_, span)
+            | MustReturn(_, span)
+            | UnclosedParenthesis(span)
+            | UnclosedBlock(span)
+            | UnclosedArray(span)
+            | ParenthesisHasNoOpening(span)
+            | BlockHasNoOpening(span)
+            | UnexpectedEOF(span)
+            | IdentifierExists(span, _)
+            | IdentNotFound(_, span)
+            | CustomError(_, span)
+            | ElseWithNoIf(span)
+            | FunctionTypeMismatch(span)
+            | NotAFunction(span)
+            | NotAVariable(span)
+            | VariableTypeMismatch(span, _, _)
+            | DeclarationMissingIdentifier(span)
+            | TypeCannotBeSignedOrUnsigned(_, span)
+            | CannotCombineSignedAndUnsigned(span)
+            | ExpectedTypeSpecifier(span)
+            | ArraySizeNotSpecified(span)
+            | InvalidArrayOperation(span)
+            | InvalidBinaryOperation(_, _, _, span)
+            | LeftHandNotLVal(span)
+            | InvalidTypeSpecifier(span)
+            | InvalidTypeSpecifierOrder(_, span)
+            | NotAStruct(span)
+            | MemberNotFound(_, _, span)
+            | ConstAssignment(span)
+            | NumberTooLarge(span)
+            | CannotIncrementType(_, span)
+            | NonNumericNegation(span)
+            | CannotBitwise(_, span)
+            | NotLogicalType(_, span)
+            | InvalidLeftOfSubScript(_, span)
+            | CannotIndexWith(_, span)
+            | CannotMemberAccessOnType(_, span)
+            | DotOperatorOnPointer(span)
+            | ArrowOnNonPointer(span)
+            | IncompleteType(span)
+            | CannotEq(_, _, span)
+            | FunctionRequiresIdentifier(span)
+            | FunctionStorageSpecifiers(span)
+            | ParamRequiresIdent(_, _, span)
+            | ParamStorageSpecifiers(span)
+            | CannotPointerMemberAccess(_, span)
+            | ArgumentTypeMismatch(_, _, span)
+            | MemberAlreadyExists(_, span)
+            | StructDeclarationPointer(span)
+            | StructDeclarationQualifiers(span)
+            | StructStorageSpecifiers(span)
+            | StructMissingIdent(span)
+            | MainIsReserved(span)
+            | InvalidMainSignature(span)
+            | DerefOnNonPointer(_, span)
+            | InvalidReturnType(_, _, span)
+            | ContinueWithoutLoop(span)
+            | BreakWithoutLoop(span)
+            | CaseWithoutSwitch(_, span)
+            | SwitchRequiresInteger(_, span)
+            | CaseRequiresConstant(span)
+            | DuplicateCaseValue(_, span)
+            | DuplicateDefaultLabel(span)
+            | FunctionMissingReturn(_, span)
+            | FunctionSignatureMismatch(_, span, _)
+            | FunctionRedefinition(_, span, _)
+            | CannotImplicitCast(_, _, span)
+            | CannotExplicitCast(_, _, span)
+            | CannotAddressNonLVal(span)
+            | IncompleteSubscript(_, span)
+            | EnumeratorRequiresConstant(_, span)
+            | EnumeratorOutOfRange(_, _, span)
+            | EnumNotFound(_, span)
+            | WrongKindOfTag(_, span)
+            | UndefinedLabel(_, span)
+            | DuplicateLabel(_, span, _)
+            | TooManyInitializers(_, span)
+            | InvalidDesignator(_, _, span)
+            | DesignatorRequiresConstant(span)
+            | DesignatorOutOfBounds(_, _, span)
+            | IncompleteStruct(_, span)
+            | UnknownDirective(_, span)
+            | IncludeNotFound(_, span)
+            | IncludeDepthExceeded(span)
+            | UnterminatedConditional(_, span)
+            | UnmatchedConditional(_, span)
+            | DirectiveAfterElse(_, span, _)
+            | MacroRedefinition(_, span, _)
+            | MacroArgumentCount(_, _, _, span)
+            | UnterminatedMacroCall(_, span)
+            | InvalidTokenPaste(_, _, span)
+            | InvalidConditionExpression(span)
+            | ErrorDirective(_, span)
+            | StaticAfterNonStatic(_, span, _)
+            | VariableSignatureMismatch(_, span, _)
+            | MissingLibraryInclude(_, _, span)
+            | VarargsRequireParameter(span)
+            | VaStartOutsideVariadic(span)
+            | VaStartNotLastParameter(_, span)
+            | NotAVaList(_, span)
+            | PromotedVaArgType(_, _, span)
+            | VaListNotAllowed(span)
+            | VaListAssignment(span)
+            | PointerDepthExceeded(span)
+            | LocalStorageSpecifier(_, span) => Some(*span),
+            IoError(_) | MissingMain => None,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        use CompilerError::*;
+        let diagnostic =
+            Diagnostic::new(Severity::Error, self.to_string(), self.span()).with_code(self.code());
+        match self {
+            MissingMain => diagnostic.with_help("add an entry point, 'int main() { return 0; }'"),
+            IdentifierExists(_, previous) => {
+                diagnostic.with_label(*previous, "previously declared here")
+            }
+            FunctionSignatureMismatch(_, _, previous) => {
+                diagnostic.with_label(*previous, "previously declared here")
+            }
+            FunctionRedefinition(_, _, previous) => {
+                diagnostic.with_label(*previous, "previously defined here")
+            }
+            StaticAfterNonStatic(_, _, previous) => {
+                diagnostic.with_label(*previous, "previously declared here")
+            }
+            VariableSignatureMismatch(_, _, previous) => {
+                diagnostic.with_label(*previous, "previously declared here")
+            }
+            DuplicateLabel(_, _, previous) => {
+                diagnostic.with_label(*previous, "previously defined here")
+            }
+            DotOperatorOnPointer(_) => diagnostic.with_help("did you mean to use '->'?"),
+            ArrowOnNonPointer(_) => diagnostic.with_help("did you mean to use '.'?"),
+            InvalidMainSignature(_) => diagnostic
+                .with_note("expected 'int main()' or 'int main(int argc, char **argv)'"),
+            FunctionMissingReturn(..) => {
+                diagnostic.with_note("every path through a non-void function must return a value")
+            }
+            BreakWithoutLoop(_) | ContinueWithoutLoop(_) => {
+                diagnostic.with_note("break and continue must be used inside of a loop, break may also be used in a switch")
+            }
+            CaseRequiresConstant(_) => {
+                diagnostic.with_note("case values must be known at compile time")
+            }
+            EnumeratorOutOfRange(..) => {
+                diagnostic.with_note("enumerators must be between -2147483648 and 2147483647")
+            }
+            IncompleteStruct(..) => diagnostic
+                .with_note("a struct can only be used by value after its definition"),
+            IncludeNotFound(..) => {
+                diagnostic.with_help("add the directory containing it with '-I <dir>'")
+            }
+            MissingLibraryInclude(_, header, _) => {
+                diagnostic.with_help(format!("add '#include <{header}>'"))
+            }
+            PromotedVaArgType(_, promoted, _) => {
+                diagnostic.with_help(format!("read it with 'va_arg(ap, {promoted})'"))
+            }
+            VaListAssignment(_) => diagnostic.with_help("copy it with 'va_copy(dest, src)'"),
+            IncludeDepthExceeded(..) => {
+                diagnostic.with_help("guard the header with '#ifndef' or '#pragma once'")
+            }
+            UnterminatedConditional(..) => diagnostic.with_note("add an '#endif' to close it"),
+            DirectiveAfterElse(_, _, previous) => diagnostic.with_label(*previous, "'#else' is here"),
+            MacroRedefinition(_, _, previous) => diagnostic
+                .with_label(*previous, "previously defined here")
+                .with_help("use '#undef' before defining it again"),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl CompilerWarning {
+    /// With `-Werror` the warning is reported as an error.
+    pub fn diagnostic(&self, as_error: bool) -> Diagnostic {
+        let (severity, flag) = if as_error {
+            (Severity::Error, format!("-Werror={}", self.name()))
+        } else {
+            (Severity::Warning, format!("-W{}", self.name()))
+        };
+        Diagnostic::new(severity, self.to_string(), Some(self.span())).with_code(flag)
+    }
+
+    pub fn span(&self) -> Span {
+        use CompilerWarning::*;
+        match self {
+            UnusedItem(_, span)
+            | ExprNoEffect(span)
+            | SuffixIgnored(span)
+            | UnusedVariable(span)
+            | UnusedFunction(span)
+            | UnusedParameter(span)
+            | UnusedConstant(span)
+            | UnusedStruct(span)
+            | UnusedLabel(span)
+            | UnreachableCode(span)
+            | UninitializedVariable(span)
+            | UnsupportedStorageSpecifier(_, span)
+            | UnsupportedTypeQualifier(_, span)
+            | RedundantUsage(_, span)
+            | DuplicateEnumeratorValue(_, _, span)
+            | PointerSign(_, _, span) => *span,
+        }
+    }
+}
+
+/// Generates `CompilerWarning::name` and the table of every warning name from one list,
+/// `true` marks the warnings that are only reported with `-Wall` or when enabled by name.
+macro_rules! warning_names {
+    ($($variant:ident($($field:tt),*) => $name:literal, $wall_only:literal;)*) => {
+        impl CompilerWarning {
+            /// The name used to toggle this warning with `-W<name>` and `-Wno-<name>`.
+            pub fn name(&self) -> &'static str {
+                use CompilerWarning::*;
+                match self {
+                    $($variant($($field),*) => $name,)*
+                }
+            }
+        }
+
+        static WARNINGS: &[(&str, bool)] = &[$(($name, $wall_only),)*];
+    };
+}
+
+warning_names! {
+    UnusedItem(_, _) => "unused-item", true;
+    ExprNoEffect(_) => "unused-value", false;
+    SuffixIgnored(_) => "suffix-ignored", false;
+    UnusedVariable(_) => "unused-variable", true;
+    UnusedFunction(_) => "unused-function", true;
+    UnusedParameter(_) => "unused-parameter", true;
+    UnusedConstant(_) => "unused-constant", true;
+    UnusedStruct(_) => "unused-struct", true;
+    UnusedLabel(_) => "unused-label", true;
+    UnreachableCode(_) => "unreachable-code", false;
+    UninitializedVariable(_) => "uninitialized", true;
+    UnsupportedStorageSpecifier(_, _) => "unsupported-storage-specifier", false;
+    UnsupportedTypeQualifier(_, _) => "unsupported-type-qualifier", false;
+    RedundantUsage(_, _) => "redundant-usage", false;
+    DuplicateEnumeratorValue(_, _, _) => "duplicate-enum-value", false;
+    PointerSign(_, _, _) => "pointer-sign", false;
+}
+
+/// Which warnings are reported, built from the `-W` flags in the order they were given.
+#[derive(Debug)]
+pub struct WarningOptions {
+    enabled: HashSet<&'static str>,
+    pub as_errors: bool,
+}
+
+impl Default for WarningOptions {
+    fn default() -> Self {
+        let enabled = WARNINGS
+            .iter()
+            .filter(|(_, wall_only)| !wall_only)
+            .map(|(name, _)| *name)
+            .collect();
+        Self {
+            enabled,
+            as_errors: false,
+        }
+    }
+}
+
+impl WarningOptions {
+    pub fn from_flags(flags: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        for flag in flags {
+            match flag.as_str() {
+                "all" => options
+                    .enabled
+                    .extend(WARNINGS.iter().map(|(name, _)| name)),
+                "error" => options.as_errors = true,
+                "no-error" => options.as_errors = false,
+                _ => {
+                    let (name, enable) = match flag.strip_prefix("no-") {
+                        Some(name) => (name, false),
+                        None => (flag.as_str(), true),
+                    };
+                    let (name, _) = WARNINGS
+                        .iter()
+                        .find(|(known, _)| *known == name)
+                        .ok_or_else(|| format!("Unknown warning option '-W{flag}'"))?;
+                    if enable {
+                        options.enabled.insert(name);
+                    } else {
+                        options.enabled.remove(name);
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    pub fn is_enabled(&self, warning: &CompilerWarning) -> bool {
+        self.enabled.contains(warning.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Span;
+
+    #[test]
+    fn warning_flags_apply_in_order() {
+        let unused = CompilerWarning::UnusedVariable(Span::default());
+        let unreachable = CompilerWarning::UnreachableCode(Span::default());
+        let uninitialized = CompilerWarning::UninitializedVariable(Span::default());
+
+        let options = WarningOptions::from_flags(&[]).unwrap();
+        assert!(!options.is_enabled(&unused));
+        assert!(options.is_enabled(&unreachable));
+        assert!(!options.is_enabled(&uninitialized));
+
+        let flags = ["all", "no-unreachable-code", "error"].map(String::from);
+        let options = WarningOptions::from_flags(&flags).unwrap();
+        assert!(options.is_enabled(&unused));
+        assert!(options.is_enabled(&uninitialized));
+        assert!(!options.is_enabled(&unreachable));
+        assert!(options.as_errors);
+
+        let flags = ["no-unused-variable", "unused-variable"].map(String::from);
+        let options = WarningOptions::from_flags(&flags).unwrap();
+        assert!(options.is_enabled(&unused));
+
+        assert!(WarningOptions::from_flags(&["bogus".to_string()]).is_err());
+    }
+
+    #[test]
+    fn unused_value_flags() {
+        let no_effect = CompilerWarning::ExprNoEffect(Span::default());
+
+        let flags = ["no-unused-value"].map(String::from);
+        let options = WarningOptions::from_flags(&flags).unwrap();
+        assert!(!options.is_enabled(&no_effect));
+
+        let flags = ["no-unused-value", "unused-value"].map(String::from);
+        let options = WarningOptions::from_flags(&flags).unwrap();
+        assert!(options.is_enabled(&no_effect));
+
+        // 'no-' turns a warning off, so no warning can be named with it
+        assert!(WARNINGS.iter().all(|(name, _)| !name.starts_with("no-")));
+    }
 }
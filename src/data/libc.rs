@@ -0,0 +1,172 @@
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::data::mlir::{MlirType, MlirTypeDecl, MlirTypeKind};
+use crate::data::symbols::FunctionSymbol;
+use crate::util::str_intern;
+
+// The C library functions the compiler knows the prototypes of, described per header in
+// 'libc/*.ron'. Including one of these headers makes its functions visible, no file is read.
+// Headers made of macros and types rather than functions are kept as source instead.
+
+static DESCRIPTIONS: [(&str, &str); 5] = [
+    ("stdio.h", include_str!("libc/stdio.ron")),
+    ("stdlib.h", include_str!("libc/stdlib.ron")),
+    ("string.h", include_str!("libc/string.ron")),
+    ("math.h", include_str!("libc/math.ron")),
+    ("ctype.h", include_str!("libc/ctype.ron")),
+];
+
+static SOURCES: [(&str, &str); 1] = [("stdarg.h", include_str!("libc/stdarg.h"))];
+
+#[derive(Deserialize)]
+struct HeaderDescription {
+    /// Libraries linked with `-l` when the header is included, such as 'm' for math.h.
+    #[serde(default)]
+    link: Vec<String>,
+    functions: Vec<FunctionDescription>,
+}
+
+#[derive(Deserialize)]
+struct FunctionDescription {
+    name: String,
+    returns: String,
+    params: Vec<String>,
+    #[serde(default)]
+    varargs: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct LibcHeader {
+    pub(crate) name: &'static str,
+    pub(crate) link: Vec<String>,
+    pub(crate) functions: Vec<FunctionSymbol>,
+}
+
+lazy_static! {
+    static ref HEADERS: Vec<LibcHeader> = DESCRIPTIONS
+        .iter()
+        .map(|(name, description)| load_header(name, description))
+        .collect();
+}
+
+pub(crate) fn header(name: &str) -> Option<&'static LibcHeader> {
+    HEADERS.iter().find(|header| header.name == name)
+}
+
+/// The text of a header that is preprocessed like a file, such as stdarg.h.
+pub(crate) fn header_source(name: &str) -> Option<&'static str> {
+    SOURCES
+        .iter()
+        .find(|(source_name, _)| *source_name == name)
+        .map(|(_, source)| *source)
+}
+
+/// The header that declares a library function.
+pub(crate) fn header_of(function: &str) -> Option<&'static LibcHeader> {
+    HEADERS.iter().find(|header| {
+        header
+            .functions
+            .iter()
+            .any(|symbol| symbol.ident.as_ref() == function)
+    })
+}
+
+/// The descriptions are part of the compiler, so an invalid one is a bug rather than an error.
+fn load_header(name: &'static str, description: &str) -> LibcHeader {
+    let description: HeaderDescription = ron::from_str(description)
+        .unwrap_or_else(|error| panic!("Invalid description of '{name}': {error}"));
+    let functions = description
+        .functions
+        .into_iter()
+        .map(|function| FunctionSymbol {
+            ident: str_intern::intern(function.name.as_str()),
+            location: Some(name),
+            return_ty: parse_type(&function.returns),
+            varargs: function.varargs,
+            params: function
+                .params
+                .iter()
+                .map(|param| parse_type(param))
+                .collect(),
+            is_defined: false, // defined by the C library
+            is_static: false,
+        })
+        .collect();
+    LibcHeader {
+        name,
+        link: description.link,
+        functions,
+    }
+}
+
+/// Reads the type names used by the descriptions, such as 'unsigned long' or 'unsigned char *'.
+fn parse_type(name: &str) -> MlirType {
+    let pointer_depth = name.matches('*').count();
+    let words = name.replace('*', " ");
+    let mut words = words.split_whitespace().collect::<Vec<_>>();
+    let unsigned = words.first() == Some(&"unsigned");
+    if unsigned {
+        words.remove(0);
+    }
+
+    let kind = match words.as_slice() {
+        ["void"] => MlirTypeKind::Void,
+        ["char"] => MlirTypeKind::Char(unsigned),
+        ["short"] => MlirTypeKind::Short(unsigned),
+        ["int"] => MlirTypeKind::Int(unsigned),
+        [] if unsigned => MlirTypeKind::Int(true),
+        ["long"] => MlirTypeKind::Long(unsigned),
+        ["float"] => MlirTypeKind::Float,
+        ["double"] => MlirTypeKind::Double,
+        ["va_list"] => MlirTypeKind::VaList,
+        _ => panic!("Unknown type '{name}' in a library description"),
+    };
+    let decl = match pointer_depth {
+        0 => MlirTypeDecl::Basic,
+        depth => MlirTypeDecl::Pointer(depth as u8),
+    };
+    MlirType::new(kind, decl)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::mlir::{MlirType, MlirTypeDecl, MlirTypeKind};
+
+    use super::{header, header_of, header_source, parse_type, HEADERS};
+
+    #[test]
+    fn every_header_description_loads() {
+        for header in HEADERS.iter() {
+            assert!(!header.functions.is_empty(), "'{}' is empty", header.name);
+        }
+        assert_eq!(header("math.h").unwrap().link, vec!["m".to_string()]);
+        assert_eq!(header_of("strlen").unwrap().name, "string.h");
+        assert!(header_of("main").is_none());
+        assert!(header_source("stdarg.h").is_some());
+    }
+
+    #[test]
+    fn type_names_are_parsed() {
+        assert_eq!(
+            parse_type("unsigned char *"),
+            MlirType::new(MlirTypeKind::Char(true), MlirTypeDecl::Pointer(1))
+        );
+        assert_eq!(
+            parse_type("char *"),
+            MlirType::new(MlirTypeKind::Char(false), MlirTypeDecl::Pointer(1))
+        );
+        assert_eq!(
+            parse_type("unsigned long"),
+            MlirType::new(MlirTypeKind::Long(true), MlirTypeDecl::Basic)
+        );
+        assert_eq!(
+            parse_type("void**"),
+            MlirType::new(MlirTypeKind::Void, MlirTypeDecl::Pointer(2))
+        );
+        assert_eq!(
+            parse_type("va_list"),
+            MlirType::new(MlirTypeKind::VaList, MlirTypeDecl::Basic)
+        );
+    }
+}
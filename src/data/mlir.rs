@@ -5,6 +5,7 @@ use std::ops::{Deref, DerefMut};
 use derive_new::new;
 
 use crate::data::ast::BinaryOp;
+use crate::data::symbols::FunctionSymbol;
 use crate::util::{Locatable, Span};
 use crate::util::str_intern::InternedStr;
 
@@ -20,7 +21,7 @@ pub(crate) use basic_ty;
 
 pub const VOID_PTR: MlirType = MlirType {
     kind: MlirTypeKind::Void,
-    decl: MlirTypeDecl::Pointer,
+    decl: MlirTypeDecl::Pointer(1),
 };
 
 pub const UNSIGNED_LONG_TYPE: MlirType = MlirType {
@@ -43,6 +44,9 @@ pub struct MlirModule {
     pub functions: Vec<MlirFunction>,
     pub structs: Vec<MlirStruct>,
     pub globals: Vec<MlirVariable>,
+    /// Globals declared with `extern` and defined by another translation unit.
+    pub external_globals: Vec<MlirVariable>,
+    pub external_functions: Vec<FunctionSymbol>,
 }
 
 impl MlirModule {
@@ -73,6 +77,8 @@ pub struct MlirStruct {
     pub ident: Locatable<InternedStr>,
     pub members: Vec<Locatable<MlirVariable>>,
     pub size: u64,
+    pub align: u64,
+    pub is_union: bool, // all members start at offset zero
 }
 
 impl MlirStruct {
@@ -111,6 +117,8 @@ pub struct MlirFunction {
     pub ident: Locatable<InternedStr>,
     pub parameters: Vec<Locatable<MlirVariable>>,
     pub body: Locatable<MlirBlock>,
+    pub is_static: bool,
+    pub varargs: bool,
 }
 
 #[derive(Debug, PartialEq, Hash, PartialOrd, Eq)]
@@ -120,13 +128,17 @@ pub struct MlirVariable {
     pub ty: Locatable<MlirType>,
     pub ident: Locatable<InternedStr>,
     pub is_const: bool,
+    pub is_static: bool,
     pub initializer: Option<Locatable<MlirVarInit>>,
 }
 
 #[derive(Debug, PartialEq, Hash, PartialOrd, Eq)]
 pub enum MlirVarInit {
     Expr(MlirExpr),
-    Array(Vec<MlirExpr>),
+    // the index of each initialized element, arrays of arrays have an initializer for each
+    // element array
+    Array(Vec<(u64, MlirVarInit)>),
+    Struct(Vec<(InternedStr, MlirVarInit)>), // members left out are zero, like array elements
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, PartialOrd, new, Eq)]
@@ -137,11 +149,44 @@ pub struct MlirType {
 
 impl Display for MlirType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", self.kind, self.decl)
+        let MlirTypeKind::Array(array) = &self.kind else {
+            return write!(f, "{}{}", self.kind, self.decl);
+        };
+        // the dimensions of the element arrays come after the outer declarator,
+        // 'int [10][20]' and 'int (*)[20]', an element pointer comes before it, 'int * [10]'
+        let mut dimensions = String::new();
+        let mut element = &**array;
+        let kind = loop {
+            let MlirTypeDecl::Array(size) = element.decl else {
+                break element.to_string();
+            };
+            dimensions.push_str(&format!("[{}]", size));
+            match &element.kind {
+                MlirTypeKind::Array(inner) => element = inner,
+                kind => break kind.to_string(),
+            }
+        };
+        match self.decl {
+            MlirTypeDecl::Array(size) => write!(f, "{} [{}]{}", kind, size, dimensions),
+            MlirTypeDecl::Pointer(depth) => {
+                write!(f, "{} ({}){}", kind, "*".repeat(depth as usize), dimensions)
+            }
+            MlirTypeDecl::Basic => write!(f, "{}", array),
+        }
     }
 }
 
 impl MlirType {
+    #[inline]
+    pub fn is_function_pointer(&self) -> bool {
+        self.is_basic() && matches!(self.kind, MlirTypeKind::FunctionPointer(_))
+    }
+
+    #[inline]
+    pub fn is_va_list(&self) -> bool {
+        self.is_basic() && matches!(self.kind, MlirTypeKind::VaList)
+    }
+
     #[inline]
     pub fn is_float(&self) -> bool {
         self.is_basic() && matches!(self.kind, MlirTypeKind::Float | MlirTypeKind::Double)
@@ -162,7 +207,10 @@ impl MlirType {
         self.is_basic()
             && matches!(
                 &self.kind,
-                MlirTypeKind::Char(true) | MlirTypeKind::Int(true) | MlirTypeKind::Long(true)
+                MlirTypeKind::Char(true)
+                    | MlirTypeKind::Short(true)
+                    | MlirTypeKind::Int(true)
+                    | MlirTypeKind::Long(true)
             )
     }
     #[inline]
@@ -174,19 +222,80 @@ impl MlirType {
     }
     #[inline]
     pub fn is_array(&self) -> bool {
-        matches!(self.decl, MlirTypeDecl::Array(_)) && !matches!(&self.decl, MlirTypeDecl::Pointer)
+        matches!(self.decl, MlirTypeDecl::Array(_))
     }
     #[inline]
     pub fn is_pointer(&self) -> bool {
-        matches!(self.decl, MlirTypeDecl::Pointer)
+        matches!(self.decl, MlirTypeDecl::Pointer(_))
     }
     #[inline]
     pub fn is_numeric(&self) -> bool {
         self.kind.is_numeric()
-            && !matches!(&self.decl, MlirTypeDecl::Pointer)
+            && !matches!(&self.decl, MlirTypeDecl::Pointer(_))
             && !matches!(&self.decl, MlirTypeDecl::Array(_))
     }
 
+    /// The type produced by dereferencing or subscripting a value of this type.
+    pub fn deref_type(&self) -> Self {
+        let decl = match self.decl {
+            MlirTypeDecl::Pointer(depth) if depth > 1 => MlirTypeDecl::Pointer(depth - 1),
+            _ => MlirTypeDecl::Basic,
+        };
+        match &self.kind {
+            MlirTypeKind::Array(element) if decl == MlirTypeDecl::Basic => (**element).clone(),
+            kind => Self {
+                kind: kind.clone(),
+                decl,
+            },
+        }
+    }
+
+    /// An array of this type, an array of arrays or pointers keeps its element as the kind,
+    /// so 'int [10][20]' is an array of 10 'int [20]'.
+    pub fn array_of(self, size: u64) -> Self {
+        let kind = if self.is_array() || self.is_pointer() {
+            MlirTypeKind::Array(Box::new(self))
+        } else {
+            self.kind
+        };
+        Self {
+            kind,
+            decl: MlirTypeDecl::Array(size),
+        }
+    }
+
+    /// A pointer to this type, pointers to arrays keep the array as the kind.
+    /// None when the pointer would be deeper than a depth can count.
+    pub fn pointer_to(self, depth: u8) -> Option<Self> {
+        let (kind, decl) = match self.decl {
+            MlirTypeDecl::Basic => (self.kind, MlirTypeDecl::Pointer(depth)),
+            MlirTypeDecl::Pointer(inner) => {
+                (self.kind, MlirTypeDecl::Pointer(inner.checked_add(depth)?))
+            }
+            MlirTypeDecl::Array(_) => (
+                MlirTypeKind::Array(Box::new(self)),
+                MlirTypeDecl::Pointer(depth),
+            ),
+        };
+        Some(Self { kind, decl })
+    }
+
+    /// The type produced by taking the address of a value of this type.
+    pub fn address_of_type(&self) -> Option<Self> {
+        if self.is_array() {
+            // a pointer to the whole array, 'int (*)[3]' for an 'int [3]'
+            return self.clone().pointer_to(1);
+        }
+        let decl = match self.decl {
+            MlirTypeDecl::Pointer(depth) => MlirTypeDecl::Pointer(depth.checked_add(1)?),
+            _ => MlirTypeDecl::Pointer(1),
+        };
+        Some(Self {
+            kind: self.kind.clone(),
+            decl,
+        })
+    }
+
     pub fn get_is_unsigned(&self) -> bool {
         self.kind.get_is_unsigned()
     }
@@ -227,7 +336,7 @@ impl MlirType {
             (Long(_), Int(unsigned), Basic, Basic) => Some(Int(*unsigned)),
             (Int(_), Double, Basic, Basic) => Some(Double),
             (Long(_), Double, Basic, Basic) => Some(Double),
-            (_, Long(unsigned), Pointer, Basic) => Some(Long(*unsigned)), // all pointers can cast to long
+            (_, Long(unsigned), Pointer(_), Basic) => Some(Long(*unsigned)), // all pointers can cast to long
             _ => None,
         };
 
@@ -242,7 +351,7 @@ impl MlirType {
 #[derive(Debug, Clone, PartialEq, Hash, PartialOrd, Eq, Copy)]
 pub enum MlirTypeDecl {
     Basic,
-    Pointer, // true if pointer is const
+    Pointer(u8), // pointer depth, always at least 1
     Array(u64),
 }
 
@@ -251,7 +360,7 @@ impl Display for MlirTypeDecl {
         use MlirTypeDecl::*;
         match self {
             Basic => Ok(()),
-            Pointer => write!(f, " *"),
+            Pointer(depth) => write!(f, " {}", "*".repeat(*depth as usize)),
             Array(size) => write!(f, " [{}]", size),
         }
     }
@@ -260,18 +369,32 @@ impl Display for MlirTypeDecl {
 #[derive(Debug, Clone, PartialEq, Hash, PartialOrd, Eq)]
 pub enum MlirTypeKind {
     Void,
-    Char(bool), // 8
-    Int(bool),  // signed/unsigned
-    Long(bool), // i64
+    Char(bool),  // 8
+    Short(bool), // i16
+    Int(bool),   // signed/unsigned
+    Long(bool),  // i64, also used for 'long long'
     Float,
     Double,
     Struct(InternedStr),
+    FunctionPointer(Box<MlirFunctionSignature>), // a pointer even when the decl is basic
+    Array(Box<MlirType>), // the element of an array of arrays or pointers, or a pointed to array
+    VaList,               // its layout depends on the target
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, PartialOrd, Eq)]
+pub struct MlirFunctionSignature {
+    pub return_ty: MlirType,
+    pub params: Vec<MlirType>,
+    pub varargs: bool,
 }
 
 impl MlirTypeKind {
     pub fn is_numeric(&self) -> bool {
         use MlirTypeKind::*;
-        matches!(&self, Char(_) | Int(_) | Long(_) | Float | Double)
+        matches!(
+            &self,
+            Char(_) | Short(_) | Int(_) | Long(_) | Float | Double
+        )
     }
 
     pub fn get_struct_ident(&self) -> &InternedStr {
@@ -286,13 +409,15 @@ impl MlirTypeKind {
 
     pub fn is_integer(&self) -> bool {
         use MlirTypeKind::*;
-        matches!(self, Char(_) | Int(_) | Long(_))
+        matches!(self, Char(_) | Short(_) | Int(_) | Long(_))
     }
 
     pub fn get_is_unsigned(&self) -> bool {
         use MlirTypeKind::*;
         match self {
-            Char(is_unsigned) | Int(is_unsigned) | Long(is_unsigned) => *is_unsigned,
+            Char(is_unsigned) | Short(is_unsigned) | Int(is_unsigned) | Long(is_unsigned) => {
+                *is_unsigned
+            }
             _ => panic!("Type is not integer."),
         }
     }
@@ -374,6 +499,20 @@ impl MlirExpr {
     pub fn is_numeric(&self) -> bool {
         self.ty.is_numeric()
     }
+
+    /// An integer literal zero, or one cast to 'void *', converts to any pointer type.
+    pub fn is_null_pointer_constant(&self) -> bool {
+        match self.kind.as_ref() {
+            MlirExprKind::Literal(
+                MlirLiteral::Int(0)
+                | MlirLiteral::UInt(0)
+                | MlirLiteral::Long(0)
+                | MlirLiteral::ULong(0),
+            ) => true,
+            MlirExprKind::Cast(ty, _, expr) if *ty == VOID_PTR => expr.is_null_pointer_constant(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, PartialOrd, Eq)]
@@ -414,14 +553,22 @@ pub enum MlirExprKind {
     Assign(MlirExpr, MlirExpr),
 
     // other
+    Ternary(MlirExpr, MlirExpr, MlirExpr),
     FunctionCall {
         location: Option<&'static str>,
         ident: InternedStr,
         args: Vec<MlirExpr>,
     },
+    Function(InternedStr), // function names decay to pointers
+    IndirectCall(MlirExpr, Vec<MlirExpr>),
     Index(MlirExpr, MlirExpr),
     Member(MlirExpr, InternedStr),
     Cast(MlirType, CastType, MlirExpr),
+    // the va_list operands are lvals, va_arg reads an argument of the expression's type
+    VaStart(MlirExpr),
+    VaArg(MlirExpr),
+    VaEnd(MlirExpr),
+    VaCopy(MlirExpr, MlirExpr),
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, PartialOrd, Eq, Copy)]
@@ -494,6 +641,7 @@ pub enum MlirStmt {
     Label(InternedStr),
     Goto(InternedStr),
     CondGoto(MlirExpr, InternedStr, InternedStr),
+    Switch(MlirExpr, Vec<(i64, InternedStr)>, InternedStr), // cases and default label
     Return(Option<MlirExpr>),
 }
 
@@ -506,8 +654,23 @@ impl MlirStmt {
             MlirStmt::Label(_) => "label",
             MlirStmt::Goto(_) => "goto",
             MlirStmt::CondGoto(_, _, _) => "goto false",
+            MlirStmt::Switch(_, _, _) => "switch",
             MlirStmt::Return(_) => "return",
         }
         .to_string()
     }
+
+    /// The source location of the statement, labels and jumps created by the analyzer have none.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            MlirStmt::Expression(expr)
+            | MlirStmt::CondGoto(expr, _, _)
+            | MlirStmt::Switch(expr, _, _)
+            | MlirStmt::Return(Some(expr)) => Some(expr.span),
+            MlirStmt::VariableDeclaration(var) => Some(var.span),
+            MlirStmt::Block(_) | MlirStmt::Label(_) | MlirStmt::Goto(_) | MlirStmt::Return(None) => {
+                None
+            }
+        }
+    }
 }
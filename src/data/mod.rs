@@ -1,6 +1,7 @@
 pub mod arch;
 pub mod ast;
 pub mod error;
+pub mod libc;
 pub mod mlir;
 pub mod symbols;
 pub mod tokens;
@@ -1,25 +1,43 @@
 use std::collections::HashMap;
 
-use lazy_static::lazy_static;
-
-use crate::data::mlir::{MlirType, MlirTypeDecl, MlirTypeKind};
-use crate::util::str_intern;
+use crate::data::mlir::{MlirFunctionSignature, MlirType, MlirTypeDecl, MlirTypeKind};
 use crate::util::str_intern::InternedStr;
 
 #[derive(Debug, Clone)]
 pub(crate) struct StructSymbol {
     pub(crate) size: u64,
+    pub(crate) align: u64,
+    pub(crate) is_union: bool,
+    pub(crate) is_complete: bool, // false for a tag that is declared but not yet defined
     pub(crate) as_type: MlirType,
     pub(crate) body: HashMap<InternedStr, VariableSymbol>,
+    pub(crate) members: Vec<InternedStr>, // in declaration order
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) struct FunctionSymbol {
     pub(crate) ident: InternedStr,
-    pub(crate) location: Option<&'static str>,
+    pub(crate) location: Option<&'static str>, // the library header declaring it
     pub(crate) return_ty: MlirType,
     pub(crate) varargs: bool,
     pub(crate) params: Vec<MlirType>,
+    pub(crate) is_defined: bool,
+    pub(crate) is_static: bool, // internal linkage, only visible to its own translation unit
+}
+
+impl FunctionSymbol {
+    /// The type of the function name once it decays to a pointer.
+    pub(crate) fn pointer_type(&self) -> MlirType {
+        let signature = MlirFunctionSignature {
+            return_ty: self.return_ty.clone(),
+            params: self.params.clone(),
+            varargs: self.varargs,
+        };
+        MlirType::new(
+            MlirTypeKind::FunctionPointer(Box::new(signature)),
+            MlirTypeDecl::Basic,
+        )
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -29,57 +47,6 @@ pub(crate) struct VariableSymbol {
     pub(crate) is_const: bool,
     pub(crate) is_initialized: bool,
     pub(crate) array_size: Option<u64>,
-}
-
-lazy_static! {
-    pub(crate) static ref BUILTINS: [(&'static str, FunctionSymbol); 3] = [
-        (
-            "printf",
-            FunctionSymbol {
-                ident: str_intern::intern("printf"),
-                location: Some("stdio.h"),
-                params: vec![MlirType {
-                    kind: MlirTypeKind::Char(true),
-                    decl: MlirTypeDecl::Pointer,
-                }],
-                varargs: true,
-                return_ty: MlirType {
-                    kind: MlirTypeKind::Int(false),
-                    decl: MlirTypeDecl::Basic,
-                },
-            }
-        ),
-        (
-            "malloc",
-            FunctionSymbol {
-                ident: str_intern::intern("malloc"),
-                location: Some("stdlib.h"),
-                params: vec![MlirType {
-                    kind: MlirTypeKind::Long(true),
-                    decl: MlirTypeDecl::Basic,
-                }],
-                varargs: false,
-                return_ty: MlirType {
-                    kind: MlirTypeKind::Void,
-                    decl: MlirTypeDecl::Pointer,
-                },
-            }
-        ),
-        (
-            "free",
-            FunctionSymbol {
-                ident: str_intern::intern("free"),
-                location: Some("stdlib.h"),
-                params: vec![MlirType {
-                    kind: MlirTypeKind::Void,
-                    decl: MlirTypeDecl::Pointer,
-                }],
-                varargs: false,
-                return_ty: MlirType {
-                    kind: MlirTypeKind::Void,
-                    decl: MlirTypeDecl::Basic,
-                },
-            }
-        ),
-    ];
+    pub(crate) is_static: bool,
+    pub(crate) is_defined: bool, // false for an 'extern' declaration
 }
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::util::str_intern::InternedStr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     BadSymbol(char),
     Identifier(InternedStr),
@@ -30,7 +30,7 @@ impl Token {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Literal {
     Integer {
         value: isize,
@@ -52,8 +52,10 @@ pub enum Literal {
 pub enum Keyword {
     Void,
     Char,
+    Short,
     Long,
     Int,
+    Float,
     Double,
     Return,
     Signed,
@@ -61,12 +63,27 @@ pub enum Keyword {
     If,
     Else,
     While,
+    Do,
     For,
     Break,
     Continue,
+    Switch,
+    Case,
+    Default,
+    Goto,
     Static,
+    Extern,
+    Typedef,
     Const,
     Struct,
+    Union,
+    Enum,
+    // spelled with a '__builtin_' prefix, <stdarg.h> gives them their usual names
+    VaList,
+    VaStart,
+    VaArg,
+    VaEnd,
+    VaCopy,
 }
 
 impl Keyword {
@@ -74,13 +91,18 @@ impl Keyword {
         matches!(
             self,
             Keyword::Int
+                | Keyword::Float
                 | Keyword::Double
                 | Keyword::Void
                 | Keyword::Char
+                | Keyword::Short
                 | Keyword::Long
                 | Keyword::Signed
                 | Keyword::Unsigned
                 | Keyword::Struct
+                | Keyword::Union
+                | Keyword::Enum
+                | Keyword::VaList
         )
     }
 }
@@ -132,6 +154,7 @@ pub enum Symbol {
     Comma,
     Dot,
     Arrow,
+    Ellipsis,
 
     OpenSquare,
     CloseSquare,
@@ -140,4 +163,150 @@ pub enum Symbol {
     OpenParen,
     CloseParen,
     Semicolon,
+
+    Hash,
+    HashHash,
+}
+
+/// The spelling of a token, used when the preprocessor stringifies or pastes tokens.
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::BadSymbol(char) => write!(f, "{char}"),
+            Token::Identifier(ident) => write!(f, "{ident}"),
+            Token::Literal(literal) => write!(f, "{literal}"),
+            Token::Keyword(keyword) => write!(f, "{keyword}"),
+            Token::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suffix = |suffix: &Option<String>| suffix.clone().unwrap_or_default();
+        match self {
+            Literal::Integer { value, suffix: s } => write!(f, "{value}{}", suffix(s)),
+            Literal::Float { value, suffix: s } => write!(f, "{value:?}{}", suffix(s)),
+            Literal::Char { value } => write!(f, "'{}'", escape(&value.to_string(), '\'')),
+            Literal::String { value } => write!(f, "\"{}\"", escape(value, '"')),
+        }
+    }
+}
+
+fn escape(text: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for char in text.chars() {
+        match char {
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            '\\' => escaped.push_str("\\\\"),
+            char if char == quote => {
+                escaped.push('\\');
+                escaped.push(char);
+            }
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keyword = match self {
+            Keyword::Void => "void",
+            Keyword::Char => "char",
+            Keyword::Short => "short",
+            Keyword::Long => "long",
+            Keyword::Int => "int",
+            Keyword::Float => "float",
+            Keyword::Double => "double",
+            Keyword::Return => "return",
+            Keyword::Signed => "signed",
+            Keyword::Unsigned => "unsigned",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::While => "while",
+            Keyword::Do => "do",
+            Keyword::For => "for",
+            Keyword::Break => "break",
+            Keyword::Continue => "continue",
+            Keyword::Switch => "switch",
+            Keyword::Case => "case",
+            Keyword::Default => "default",
+            Keyword::Goto => "goto",
+            Keyword::Static => "static",
+            Keyword::Extern => "extern",
+            Keyword::Typedef => "typedef",
+            Keyword::Const => "const",
+            Keyword::Struct => "struct",
+            Keyword::Union => "union",
+            Keyword::Enum => "enum",
+            Keyword::VaList => "__builtin_va_list",
+            Keyword::VaStart => "__builtin_va_start",
+            Keyword::VaArg => "__builtin_va_arg",
+            Keyword::VaEnd => "__builtin_va_end",
+            Keyword::VaCopy => "__builtin_va_copy",
+        };
+        write!(f, "{keyword}")
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Symbol::*;
+        let symbol = match self {
+            Sizeof => "sizeof",
+            Plus => "+",
+            Minus => "-",
+            Star => "*",
+            Slash => "/",
+            Modulo => "%",
+            EqualEqual => "==",
+            BangEqual => "!=",
+            GreaterThan => ">",
+            GreaterThanEqual => ">=",
+            LessThan => "<",
+            LessThanEqual => "<=",
+            Bang => "!",
+            DoubleAmpersand => "&&",
+            DoublePipe => "||",
+            Ampersand => "&",
+            Pipe => "|",
+            Caret => "^",
+            Tilde => "~",
+            LeftShift => "<<",
+            RightShift => ">>",
+            Equal => "=",
+            PlusEqual => "+=",
+            MinusEqual => "-=",
+            StarEqual => "*=",
+            SlashEqual => "/=",
+            ModuloEqual => "%=",
+            AmpersandEqual => "&=",
+            PipeEqual => "|=",
+            CaretEqual => "^=",
+            LeftShiftEqual => "<<=",
+            RightShiftEqual => ">>=",
+            Increment => "++",
+            Decrement => "--",
+            QuestionMark => "?",
+            Colon => ":",
+            Comma => ",",
+            Dot => ".",
+            Arrow => "->",
+            Ellipsis => "...",
+            OpenSquare => "[",
+            CloseSquare => "]",
+            OpenCurly => "{",
+            CloseCurly => "}",
+            OpenParen => "(",
+            CloseParen => ")",
+            Semicolon => ";",
+            Hash => "#",
+            HashHash => "##",
+        };
+        write!(f, "{symbol}")
+    }
 }
@@ -1,3 +1,5 @@
+use std::num::IntErrorKind;
+
 use crate::data::tokens::Literal;
 
 use super::*;
@@ -136,13 +138,18 @@ impl Lexer {
 
         let literal = match state {
             State::Zero | State::Decimal | State::Hex | State::Binary | State::Octal => {
-                let result = isize::from_str_radix(&number, base);
+                let result = isize::from_str_radix(&number, base)
+                    .or_else(|_| u64::from_str_radix(&number, base).map(|value| value as isize));
                 let value = result.unwrap_or_else(|error| {
-                    self.report_error(CompilerError::ParseIntError(span));
+                    let err = match error.kind() {
+                        IntErrorKind::PosOverflow => CompilerError::NumberTooLarge(span),
+                        _ => CompilerError::ParseIntError(span),
+                    };
+                    self.report_error(err);
                     0
                 });
                 let suffix = consume_suffix!(
-                    "u" | "l" | "ul" | "lu" | "llu" | "ll",
+                    "u" | "l" | "ul" | "lu" | "ll" | "llu" | "ull",
                     CompilerError::InvalidIntegerSuffix
                 );
                 Literal::Integer { value, suffix }
@@ -5,6 +5,7 @@ use arcstr::ArcStr;
 use crate::data::error::CompilerError;
 use crate::data::tokens::Token;
 use crate::output_lexer;
+use crate::util::source_map::FileId;
 use crate::util::*;
 use crate::util::{Locatable, Span};
 
@@ -22,6 +23,7 @@ pub struct Lexer {
     pub(in crate::lexer) col: usize,
     pub(in crate::lexer) current: Option<char>,
     pub(in crate::lexer) next: Option<char>,
+    pub(in crate::lexer) file: FileId,
 }
 impl From<(ArcStr)> for Lexer {
     fn from(value: (ArcStr)) -> Self {
@@ -64,9 +66,16 @@ impl Lexer {
             line: 1,
             current,
             next,
+            file: 0,
         }
     }
 
+    /// Marks every span produced by this lexer as belonging to a file in the source map.
+    pub fn with_file(mut self, file: FileId) -> Self {
+        self.file = file;
+        self
+    }
+
     #[inline(always)]
     pub(super) fn next_char(&mut self) -> Option<char> {
         if self.current.is_some_and(|c| c == '\n') {
@@ -93,6 +102,8 @@ impl Lexer {
             end: self.position + 1, // not very important but just to place something here
             line: self.line,
             col: self.col,
+            file: self.file,
+            expansion: None,
         }
     }
 
@@ -223,6 +234,7 @@ mod tests {
             (",", Token::Symbol(Symbol::Comma)),
             (".", Token::Symbol(Symbol::Dot)),
             ("->", Token::Symbol(Symbol::Arrow)),
+            ("...", Token::Symbol(Symbol::Ellipsis)),
             ("?", Token::Symbol(Symbol::QuestionMark)),
             ("[", Token::Symbol(Symbol::OpenSquare)),
             ("]", Token::Symbol(Symbol::CloseSquare)),
@@ -231,6 +243,8 @@ mod tests {
             ("(", Token::Symbol(Symbol::OpenParen)),
             (")", Token::Symbol(Symbol::CloseParen)),
             (";", Token::Symbol(Symbol::Semicolon)),
+            ("#", Token::Symbol(Symbol::Hash)),
+            ("##", Token::Symbol(Symbol::HashHash)),
         ];
 
         for (symbol, kind) in symbols.iter() {
@@ -6,22 +6,38 @@ impl Lexer {
         self.consume_alphanumeric_text()
             .map(|text| match text.as_str() {
                 "int" => Token::Keyword(Keyword::Int),
+                "float" => Token::Keyword(Keyword::Float),
                 "double" => Token::Keyword(Keyword::Double),
                 "char" => Token::Keyword(Keyword::Char),
+                "short" => Token::Keyword(Keyword::Short),
                 "long" => Token::Keyword(Keyword::Long),
                 "void" => Token::Keyword(Keyword::Void),
                 "signed" => Token::Keyword(Keyword::Signed),
                 "unsigned" => Token::Keyword(Keyword::Unsigned),
                 "struct" => Token::Keyword(Keyword::Struct),
+                "union" => Token::Keyword(Keyword::Union),
+                "enum" => Token::Keyword(Keyword::Enum),
                 "if" => Token::Keyword(Keyword::If),
                 "else" => Token::Keyword(Keyword::Else),
                 "while" => Token::Keyword(Keyword::While),
+                "do" => Token::Keyword(Keyword::Do),
                 "for" => Token::Keyword(Keyword::For),
                 "break" => Token::Keyword(Keyword::Break),
                 "continue" => Token::Keyword(Keyword::Continue),
+                "switch" => Token::Keyword(Keyword::Switch),
+                "case" => Token::Keyword(Keyword::Case),
+                "default" => Token::Keyword(Keyword::Default),
+                "goto" => Token::Keyword(Keyword::Goto),
                 "static" => Token::Keyword(Keyword::Static),
+                "extern" => Token::Keyword(Keyword::Extern),
+                "typedef" => Token::Keyword(Keyword::Typedef),
                 "const" => Token::Keyword(Keyword::Const),
                 "return" => Token::Keyword(Keyword::Return),
+                "__builtin_va_list" => Token::Keyword(Keyword::VaList),
+                "__builtin_va_start" => Token::Keyword(Keyword::VaStart),
+                "__builtin_va_arg" => Token::Keyword(Keyword::VaArg),
+                "__builtin_va_end" => Token::Keyword(Keyword::VaEnd),
+                "__builtin_va_copy" => Token::Keyword(Keyword::VaCopy),
 
                 // not a keyword, but a special symbol
                 "sizeof" => Token::Symbol(Symbol::Sizeof),
@@ -216,7 +232,17 @@ impl Lexer {
                 }
             }),
 
-            Some('.') => single!(Dot),
+            Some('.') => Some({
+                self.next_char();
+                match (self.current, self.next) {
+                    (Some('.'), Some('.')) => {
+                        self.next_char();
+                        self.next_char();
+                        Ellipsis
+                    }
+                    _ => Dot,
+                }
+            }),
             Some('?') => single!(QuestionMark),
             Some(':') => single!(Colon),
             Some('~') => single!(Tilde),
@@ -229,6 +255,17 @@ impl Lexer {
             Some('{') => single!(OpenCurly),
             Some('}') => single!(CloseCurly),
 
+            Some('#') => Some({
+                self.next_char();
+                match self.current {
+                    Some('#') => {
+                        self.next_char();
+                        HashHash
+                    }
+                    _ => Hash,
+                }
+            }),
+
             _ => None,
         }
         .map(Token::Symbol)
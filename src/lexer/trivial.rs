@@ -34,6 +34,9 @@ impl Lexer {
 
             (Start, Some(current), _) if current.is_whitespace() => WhiteSpace,
 
+            // a backslash before a newline joins the two lines
+            (Start, Some('\\'), Some('\n')) => WhiteSpace,
+
             (BlockComment, Some('*'), Some('/')) => BlockCommentEnd,
 
             (Start, Some('/'), Some('*')) | (BlockComment, Some(_), _) => BlockComment,
@@ -7,18 +7,25 @@ use std::process::Command;
 
 use clap::Parser as ArgParser;
 use inkwell::context::Context;
+use inkwell::targets::FileType;
 use thiserror::__private::AsDisplay;
 
 use crate::analysis::Analyzer;
+use crate::codegen::passes::OptLevel;
 use crate::codegen::Compiler;
+use crate::data::error::{CompilerError, Reporter, WarningOptions};
 use crate::lexer::Lexer;
 use crate::parser::Parser;
+use crate::preprocessor::Preprocessor;
+use crate::target::TargetTriple;
 
 mod analysis;
 mod codegen;
 mod data;
 mod lexer;
 mod parser;
+mod preprocessor;
+mod target;
 mod util;
 
 /// The main entry point for the program.
@@ -28,8 +35,44 @@ mod util;
 
 static mut ARGS: Option<Args> = None;
 
-fn file_path() -> &'static str {
-    unsafe { &ARGS.as_ref().unwrap().file_path }
+fn file_paths() -> &'static [String] {
+    unsafe { &ARGS.as_ref().unwrap().file_paths }
+}
+
+fn output() -> Option<&'static str> {
+    unsafe { ARGS.as_ref().unwrap().output.as_deref() }
+}
+
+fn target() -> Option<&'static str> {
+    unsafe { ARGS.as_ref().unwrap().target.as_deref() }
+}
+
+fn cpu() -> &'static str {
+    unsafe { &ARGS.as_ref().unwrap().cpu }
+}
+
+fn target_features() -> &'static str {
+    unsafe { &ARGS.as_ref().unwrap().target_features }
+}
+
+fn opt_level() -> OptLevel {
+    unsafe { ARGS.as_ref().unwrap().opt_level }
+}
+
+fn warning_flags() -> &'static [String] {
+    unsafe { &ARGS.as_ref().unwrap().warnings }
+}
+
+fn include_dirs() -> &'static [String] {
+    unsafe { &ARGS.as_ref().unwrap().include_dirs }
+}
+
+fn defines() -> &'static [String] {
+    unsafe { &ARGS.as_ref().unwrap().defines }
+}
+
+fn undefines() -> &'static [String] {
+    unsafe { &ARGS.as_ref().unwrap().undefines }
 }
 
 macro_rules! build_access_flag {
@@ -53,14 +96,35 @@ build_access_flag!(
     stop_at_lexer,
     stop_at_parser,
     stop_at_analyzer,
-    keep_temp_files
+    keep_llir,
+    keep_temp_files,
+    emit_assembly,
+    compile_only,
+    print_passes
 );
 
 #[derive(ArgParser, Debug, Default)]
 #[command(version, about, long_about = None)]
 struct Args {
-    #[arg(help = "The file path for the source file to compile.")]
-    file_path: String,
+    #[arg(
+        required = true,
+        help = "The source files to compile, they are linked into one program."
+    )]
+    file_paths: Vec<String>,
+
+    #[arg(
+        short = 'o',
+        value_name = "FILE",
+        help = "The output path, defaults to the first source file without its extension."
+    )]
+    output: Option<String>,
+
+    #[arg(
+        short = 'c',
+        help = "Output an object file for each source file instead of a linked binary.",
+        action
+    )]
+    compile_only: bool,
 
     #[arg(long, help = "Display abstract syntax tree.", action)]
     display_ast: bool,
@@ -107,8 +171,87 @@ struct Args {
     #[arg(long, help = "Keep the .ll file produced during compilation.", action)]
     keep_llir: bool,
 
-    #[arg(long, help = "Keep temp files produced during compilation.", action)]
+    #[arg(
+        long,
+        help = "Keep temp files produced during compilation, the objects stay in the system temp directory.",
+        action
+    )]
     keep_temp_files: bool,
+
+    #[arg(
+        long,
+        value_name = "TRIPLE",
+        help = "The target triple to compile for, defaults to the host."
+    )]
+    target: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "generic",
+        help = "The cpu to generate code for."
+    )]
+    cpu: String,
+
+    #[arg(
+        long,
+        default_value = "",
+        help = "Comma separated target features to enable or disable, ex: '+neon,-fp-armv8'."
+    )]
+    target_features: String,
+
+    #[arg(
+        short = 'S',
+        help = "Output an assembly file instead of a linked binary.",
+        action
+    )]
+    emit_assembly: bool,
+
+    #[arg(
+        short = 'O',
+        value_enum,
+        default_value = "0",
+        help = "The optimization level."
+    )]
+    opt_level: OptLevel,
+
+    #[arg(
+        long,
+        help = "Print the optimization pipeline and each pass as it runs.",
+        action
+    )]
+    print_passes: bool,
+
+    #[arg(
+        short = 'W',
+        value_name = "WARNING",
+        action = clap::ArgAction::Append,
+        help = "Enable a warning by name, or disable it with 'no-<name>'. 'all' enables every warning, 'error' turns warnings into errors."
+    )]
+    warnings: Vec<String>,
+
+    #[arg(
+        short = 'I',
+        value_name = "DIR",
+        action = clap::ArgAction::Append,
+        help = "Add a directory to search for included files."
+    )]
+    include_dirs: Vec<String>,
+
+    #[arg(
+        short = 'D',
+        value_name = "NAME[=VALUE]",
+        action = clap::ArgAction::Append,
+        help = "Define a macro, its value defaults to 1."
+    )]
+    defines: Vec<String>,
+
+    #[arg(
+        short = 'U',
+        value_name = "NAME",
+        action = clap::ArgAction::Append,
+        help = "Undefine a macro defined with '-D'."
+    )]
+    undefines: Vec<String>,
 }
 
 fn main() {
@@ -120,31 +263,94 @@ fn main() {
 
     if let Err(errors) = run() {
         for error in errors {
-            eprintln!("{}", error);
+            eprintln!("{}\n", error);
         }
     }
 }
 
 fn run() -> Result<(), Vec<String>> {
-    let file_path: PathBuf = file_path().into();
-    let file_stem = file_path
-        .file_stem()
-        .map_or_else(
-            || {
-                Err(vec![format!(
-                    "Cannot read file '{}'",
-                    file_path.to_str().unwrap()
-                )])
-            },
-            Ok,
-        )?
-        .to_str()
-        .unwrap();
-    let base_path: PathBuf = file_path.parent().map_or(".".into(), |p| p.into());
-    let source = load_src(file_path.clone())?;
-    let llir = compile(source)?;
-    output_program(&base_path, file_stem, llir)?;
-    Ok(())
+    let writes_per_file = emit_assembly() || compile_only();
+    if writes_per_file && output().is_some() && file_paths().len() > 1 {
+        Err(display_to_vec(
+            "'-o' cannot be used with '-S' or '-c' when there are multiple files.",
+        ))?;
+    }
+
+    // object files are passed to the linker as they are
+    let (object_files, source_files): (Vec<&String>, Vec<&String>) = file_paths()
+        .iter()
+        .partition(|path| Path::new(path).extension().is_some_and(|ext| ext == "o"));
+    if writes_per_file && !object_files.is_empty() {
+        Err(display_to_vec(format!(
+            "'{}' is an object file, it can only be linked.",
+            object_files[0]
+        )))?;
+    }
+
+    // a program made of one file has to define main, with several files any of them can
+    let requires_main = !compile_only() && file_paths().len() == 1;
+
+    // every file is compiled, so the errors of all of them are reported at once
+    let mut outputs = Vec::new();
+    let mut libraries: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    let mut failed = false;
+    for file_path in &source_files {
+        let file_path = PathBuf::from(file_path);
+        let file_stem = parse_file_stem_from_path(&file_path)?;
+        let result = load_src(file_path.clone())
+            .and_then(|source| compile(&file_path, source, requires_main));
+        match result {
+            Ok((output, file_libraries)) => {
+                outputs.push((file_path, file_stem, output));
+                for library in file_libraries {
+                    if !libraries.contains(&library) {
+                        libraries.push(library);
+                    }
+                }
+            }
+            Err(file_errors) => {
+                failed = true;
+                errors.extend(file_errors);
+            }
+        }
+    }
+    if failed {
+        return Err(errors);
+    }
+
+    if writes_per_file {
+        let extension = if emit_assembly() { "s" } else { "o" };
+        for (file_path, file_stem, output) in outputs {
+            let out_filepath = output_path(&file_path, &format!("{file_stem}.{extension}"));
+            std::fs::write(&out_filepath, output).map_err(|_| {
+                vec![format!(
+                    "Could not write to '{}'",
+                    out_filepath.to_str().unwrap()
+                )]
+            })?;
+        }
+        return Ok(());
+    }
+
+    let first = PathBuf::from(file_paths()[0].as_str());
+    let program_path = output_path(&first, &parse_file_stem_from_path(&first)?);
+    let objects = outputs
+        .into_iter()
+        .map(|(_, file_stem, object)| (file_stem, object))
+        .collect();
+    output_program(&program_path, objects, &object_files, &libraries)
+}
+
+/// The '-o' path, or the file name placed beside the source file.
+fn output_path(file_path: &Path, file_name: &str) -> PathBuf {
+    match output() {
+        Some(output) => PathBuf::from(output),
+        None => file_path
+            .parent()
+            .map_or(".".into(), PathBuf::from)
+            .join(file_name),
+    }
 }
 
 macro_rules! abort {
@@ -194,14 +400,35 @@ fn load_src(path: PathBuf) -> Result<String, Vec<String>> {
     Ok(source)
 }
 
-fn compile(source: String) -> Result<String, Vec<String>> {
-    let lexer = Lexer::new(source.into());
-    let lexemes = lexer.lex_all().map_err(|errors| {
-        errors
-            .into_iter()
-            .map(|err| err.to_string())
-            .collect::<Vec<_>>()
-    })?;
+fn target_triple() -> Result<TargetTriple, Vec<String>> {
+    match target() {
+        Some(triple) => TargetTriple::parse(triple).map_err(display_to_vec),
+        None => Ok(TargetTriple::host()),
+    }
+}
+
+/// Compiles the source into an object file, or an assembly file when `-S` is given.
+/// Also returns the libraries the included C library headers need to be linked with.
+fn compile(
+    file_path: &Path,
+    source: String,
+    requires_main: bool,
+) -> Result<(Vec<u8>, Vec<String>), Vec<String>> {
+    let file_name = file_path.to_str().unwrap();
+    let mut preprocessor = Preprocessor::new(include_dirs().iter().map(PathBuf::from).collect());
+    for definition in defines() {
+        preprocessor.define(definition);
+    }
+    for name in undefines() {
+        preprocessor.undefine(name);
+    }
+    let lexemes = preprocessor
+        .preprocess(file_path, source.as_str().into())
+        .map_err(|errors| render_errors(&errors, file_name, &source))?;
+
+    if output_lexer() {
+        println!("\nLEXEMES-PRINTOUT: {:#?}\n", lexemes);
+    }
 
     if stop_at_lexer() {
         abort!();
@@ -209,38 +436,106 @@ fn compile(source: String) -> Result<String, Vec<String>> {
 
     let ast = Parser::new(lexemes.into_iter())
         .parse_all()
-        .map_err(|vec| {
-            vec.into_iter()
-                .map(|err| err.to_string())
-                .collect::<Vec<_>>()
-        })?;
+        .map_err(|errors| render_errors(&errors, file_name, &source))?;
 
     if stop_at_parser() {
         abort!();
     }
 
-    let analyzer = Analyzer::new(ast);
-    let mlir = analyzer.validate().map_err(|rep| {
-        rep.borrow()
-            .errors
-            .iter()
-            .map(|err| err.to_string())
-            .collect::<Vec<_>>()
-    })?;
+    let warning_options = WarningOptions::from_flags(warning_flags()).map_err(display_to_vec)?;
+    let analyzer = Analyzer::new(ast)
+        .with_main_required(requires_main)
+        .with_library_headers(preprocessor.library_headers());
+    let reporter = analyzer.reporter();
+    let result = analyzer.validate();
+    let mut errors = report_warnings(&reporter.borrow(), &warning_options, file_name, &source);
+    if let Err(rep) = &result {
+        errors.extend(render_errors(&rep.borrow().errors, file_name, &source));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let mlir = result.unwrap();
 
     if stop_at_analyzer() {
         abort!();
     }
 
+    let target = target_triple()?;
+    let machine = target
+        .create_target_machine(cpu(), target_features(), opt_level().codegen_level())
+        .map_err(display_to_vec)?;
+    let file_type = if emit_assembly() {
+        FileType::Assembly
+    } else {
+        FileType::Object
+    };
+
     let context = Context::create();
-    let module = context.create_module("main");
-    let compiler = Compiler::new(&mlir, &context, &module);
-    let llir = compiler.compile().map_err(display_to_vec)?;
+    let module_name = parse_file_stem_from_path(file_path)?;
+    let module = context.create_module(&module_name);
+    let compiler = Compiler::new(&mlir, &context, &module, &target);
+    let output = compiler
+        .compile(&machine, file_type, opt_level(), print_passes())
+        .map_err(display_to_vec)?;
+
+    if keep_llir() {
+        let ll_filepath = file_path.with_extension("ll");
+        module.print_to_file(ll_filepath).map_err(display_to_vec)?;
+    }
+
+    let libraries = preprocessor
+        .library_headers()
+        .iter()
+        .flat_map(|header| header.link.iter().cloned())
+        .collect();
+    Ok((output.as_slice().to_vec(), libraries))
+}
 
-    Ok(llir.to_string())
+fn render_errors(errors: &[CompilerError], file_name: &str, source: &str) -> Vec<String> {
+    let mut rendered = errors
+        .iter()
+        .map(|err| err.diagnostic().render(file_name, source))
+        .collect::<Vec<_>>();
+    rendered.dedup();
+    rendered
+}
+
+/// Prints the enabled warnings, or returns them as errors when `-Werror` is given.
+fn report_warnings(
+    reporter: &Reporter,
+    options: &WarningOptions,
+    file_name: &str,
+    source: &str,
+) -> Vec<String> {
+    let warnings = reporter
+        .warnings()
+        .iter()
+        .filter(|warning| options.is_enabled(warning))
+        .map(|warning| {
+            warning
+                .diagnostic(options.as_errors)
+                .render(file_name, source)
+        });
+
+    if options.as_errors {
+        return warnings.collect();
+    }
+
+    for warning in warnings {
+        eprintln!("{warning}\n");
+    }
+    Vec::new()
 }
 
-fn output_program(dir_path: &Path, file_stem: &str, llir: String) -> Result<(), Vec<String>> {
+/// Writes the objects to a directory of their own in the system temp directory, and links them
+/// with the object files that were given on the command line.
+fn output_program(
+    program_path: &Path,
+    objects: Vec<(String, Vec<u8>)>,
+    object_files: &[&String],
+    libraries: &[String],
+) -> Result<(), Vec<String>> {
     macro_rules! validate_stderr {
         ($output:expr) => {
             if !$output.stderr.is_empty() {
@@ -250,62 +545,46 @@ fn output_program(dir_path: &Path, file_stem: &str, llir: String) -> Result<(),
         };
     }
 
-    let filepath = dir_path.join(file_stem);
-    let ll_filepath = filepath.with_extension("ll");
-    let s_filepath = filepath.with_extension("s");
-    let o_filepath = filepath.with_extension("o");
-
-    let filepath = filepath.to_str().unwrap().to_string();
-    let ll_filepath = ll_filepath.to_str().unwrap().to_string();
-    let s_filepath = s_filepath.to_str().unwrap().to_string();
-    let o_filepath = o_filepath.to_str().unwrap().to_string();
-
-    let mut reported = vec![];
-
-    std::fs::write(ll_filepath.clone(), llir).map_or_else(
-        |_| {
-            reported.push(format!("Could not write to '{ll_filepath}'"));
-            Err(reported)
-        },
-        Ok,
-    )?;
-
-    validate_stderr!(Command::new("llc")
-        .args([&ll_filepath, "-o", &s_filepath])
-        .output()
-        .map_or_else(|error| Err(vec![error.to_string()]), Ok)?);
-
-    if !keep_temp_files() {
-        std::fs::remove_file(ll_filepath).unwrap();
+    let target = target_triple()?;
+    // the objects never replace files of the user, and sources with the same name do not clash
+    let temp_dir = std::env::temp_dir().join(format!("microc-{}", std::process::id()));
+    std::fs::create_dir_all(&temp_dir).map_err(|error| {
+        vec![format!(
+            "Could not create '{}': {error}",
+            temp_dir.to_string_lossy()
+        )]
+    })?;
+    let mut o_filepaths = Vec::new();
+    for (index, (file_stem, object)) in objects.into_iter().enumerate() {
+        let o_filepath = temp_dir.join(format!("{index}_{file_stem}.o"));
+        let o_filepath = o_filepath.to_str().unwrap().to_string();
+        std::fs::write(o_filepath.clone(), object)
+            .map_err(|_| vec![format!("Could not write to '{o_filepath}'")])?;
+        o_filepaths.push(o_filepath);
     }
 
-    validate_stderr!(Command::new("as")
-        .args([&s_filepath, "-o", &o_filepath])
-        .output()
-        .map_or_else(|error| Err(vec![error.to_string()]), Ok)?);
-
-    if !keep_temp_files() {
-        std::fs::remove_file(s_filepath).unwrap();
-    }
-
-    validate_stderr!(Command::new("ld")
-        .args([
-            "-o",
-            &filepath,
-            &o_filepath,
-            "-e",
-            "_main",
-            "-arch",
-            "arm64",
-            "-L/Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/usr/lib",
-            "-lSystem",
-        ])
+    let filepath = program_path.to_str().unwrap();
+    let link_objects = o_filepaths
+        .iter()
+        .chain(object_files.iter().copied())
+        .cloned()
+        .collect::<Vec<_>>();
+    let link_output = target
+        .link_command(&link_objects, libraries, filepath)
+        .map_err(display_to_vec)?
         .output()
-        .map_or_else(|error| Err(vec![error.to_string()]), Ok)?);
+        .map_or_else(|error| Err(vec![error.to_string()]), Ok)?;
 
     if !keep_temp_files() {
-        std::fs::remove_file(o_filepath).unwrap();
+        // the program is already linked, so a leftover directory is not an error
+        if let Err(error) = std::fs::remove_dir_all(&temp_dir) {
+            eprintln!(
+                "Could not remove '{}': {error}\n",
+                temp_dir.to_string_lossy()
+            );
+        }
     }
+    validate_stderr!(link_output);
 
     Ok(())
 }
@@ -321,9 +600,11 @@ mod tests {
     static DISPLAY_ERRORS_DURING_TESTS: bool = false;
     static CLEANUP_AFTER_TESTS: bool = true;
 
-    fn init_args() {
+    pub(crate) fn init_args() {
         let mut args = Args {
-            file_path: "".to_string(),
+            file_paths: Vec::new(),
+            output: None,
+            compile_only: false,
             display_ast: false,
             display_mlir: false,
             display_llvm_graph: false,
@@ -336,6 +617,16 @@ mod tests {
             stop_at_analyzer: false,
             keep_llir: false,
             keep_temp_files: false,
+            target: None,
+            cpu: "generic".to_string(),
+            target_features: "".to_string(),
+            emit_assembly: false,
+            opt_level: Default::default(),
+            print_passes: false,
+            warnings: Vec::new(),
+            include_dirs: vec!["_c_test_files/include".to_string()],
+            defines: Vec::new(),
+            undefines: Vec::new(),
         };
 
         unsafe {
@@ -370,10 +661,10 @@ mod tests {
 
     mod output_tests {
         use std::panic::catch_unwind;
-        use std::path::PathBuf;
+        use std::path::{Path, PathBuf};
         use std::process::Command;
 
-        use crate::tests::CLEANUP_AFTER_TESTS;
+        use crate::tests::{get_file_paths, CLEANUP_AFTER_TESTS};
         use crate::util::display_utils::indent_string;
         use crate::{compile, output_program};
 
@@ -388,11 +679,17 @@ mod tests {
             let expected_output = std::fs::read_to_string(expected_output_filepath)
                 .expect("Could not read expected output file.");
 
-            match catch_unwind(|| compile(src)) {
+            match catch_unwind(|| compile(Path::new(&src_filepath), src, true)) {
                 Ok(result) => match result {
-                    Ok(llir) => {
-                        let given_output =
-                            run_program_capture_output(BASE.into(), filename.into(), llir);
+                    Ok((object, libraries)) => {
+                        let objects = vec![(filename.to_string(), object)];
+                        let given_output = run_program_capture_output(
+                            BASE.into(),
+                            filename,
+                            objects,
+                            &[],
+                            &libraries,
+                        );
                         let output_is_equal = expected_output == given_output;
                         let given_output_len = given_output.len();
 
@@ -419,12 +716,15 @@ mod tests {
         fn run_program_capture_output(
             src_filepath: PathBuf,
             src_file_stem: &str,
-            llir: String,
+            objects: Vec<(String, Vec<u8>)>,
+            object_files: &[&String],
+            libraries: &[String],
         ) -> String {
             let temp_dir_filepath = src_filepath.join(PathBuf::from("temp"));
             std::fs::create_dir_all(temp_dir_filepath.clone()).unwrap();
 
-            output_program(&temp_dir_filepath, src_file_stem, llir).unwrap();
+            let program_path = temp_dir_filepath.join(src_file_stem);
+            output_program(&program_path, objects, object_files, libraries).unwrap();
 
             let given_output = Command::new(format!("./{src_file_stem}"))
                 .current_dir(&temp_dir_filepath)
@@ -456,6 +756,105 @@ mod tests {
             std_out
         }
 
+        fn run_exit_code_test(filename: &str, expected_code: i32) {
+            crate::tests::init_args();
+            static BASE: &str = "_c_test_files/output_tests/";
+
+            let src_filepath = format!("{BASE}{filename}.c");
+            let src = std::fs::read_to_string(&src_filepath).expect("Could not read source file.");
+            let (object, libraries) = compile(Path::new(&src_filepath), src, true)
+                .expect("Could not compile source file.");
+
+            let temp_dir_filepath = PathBuf::from(BASE).join("temp");
+            std::fs::create_dir_all(&temp_dir_filepath).unwrap();
+            let objects = vec![(filename.to_string(), object)];
+            output_program(&temp_dir_filepath.join(filename), objects, &[], &libraries).unwrap();
+
+            let output = Command::new(format!("./{filename}"))
+                .current_dir(&temp_dir_filepath)
+                .output()
+                .unwrap();
+
+            assert_eq!(output.status.code(), Some(expected_code));
+        }
+
+        /// Compiles every file in the directory and links them into one program, together with
+        /// the files in its 'native' directory compiled by the system's C compiler.
+        fn run_multi_file_output_test(dirname: &str) {
+            crate::tests::init_args();
+            static BASE: &str = "_c_test_files/output_tests/";
+
+            let mut src_filepaths = get_file_paths(&PathBuf::from(format!("{BASE}{dirname}")))
+                .expect("Could not read source directory.");
+            src_filepaths.retain(|path| path.extension().is_some_and(|ext| ext == "c"));
+            src_filepaths.sort();
+            let expected_output =
+                std::fs::read_to_string(format!("{BASE}{dirname}.expected_output"))
+                    .expect("Could not read expected output file.");
+
+            let mut objects = Vec::new();
+            let mut libraries = Vec::new();
+            for src_filepath in src_filepaths {
+                let src =
+                    std::fs::read_to_string(&src_filepath).expect("Could not read source file.");
+                let file_stem = src_filepath.file_stem().unwrap().to_str().unwrap();
+                match compile(&src_filepath, src, false) {
+                    Ok((object, file_libraries)) => {
+                        objects.push((file_stem.to_string(), object));
+                        libraries.extend(file_libraries);
+                    }
+                    Err(errors) => {
+                        unexpected_error_outcome!(src_filepath.display(), errors);
+                    }
+                }
+            }
+
+            let object_files = compile_native_files(&PathBuf::from(format!("{BASE}{dirname}")));
+            let object_files = object_files.iter().collect::<Vec<_>>();
+            let given_output = run_program_capture_output(
+                BASE.into(),
+                dirname,
+                objects,
+                &object_files,
+                &libraries,
+            );
+            assert_eq!(expected_output, given_output);
+        }
+
+        /// Compiles the C files of 'native' next to the micro-c files with '$CC' or 'cc', to
+        /// check that the two agree on how values are passed.
+        fn compile_native_files(directory: &Path) -> Vec<String> {
+            let Ok(mut native_filepaths) = get_file_paths(&directory.join("native")) else {
+                return Vec::new();
+            };
+            native_filepaths.retain(|path| path.extension().is_some_and(|ext| ext == "c"));
+            native_filepaths.sort();
+
+            let temp_dir_filepath = directory.with_file_name("temp");
+            std::fs::create_dir_all(&temp_dir_filepath).unwrap();
+            let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+            let mut object_files = Vec::new();
+            for native_filepath in native_filepaths {
+                let file_stem = native_filepath.file_stem().unwrap().to_str().unwrap();
+                let o_filepath = temp_dir_filepath.join(format!("{file_stem}.native.o"));
+                let output = Command::new(&compiler)
+                    .arg("-c")
+                    .arg(&native_filepath)
+                    .arg("-o")
+                    .arg(&o_filepath)
+                    .output()
+                    .expect("Could not run the C compiler.");
+                assert!(
+                    output.status.success(),
+                    "Could not compile '{}':\n{}",
+                    native_filepath.display(),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                object_files.push(o_filepath.to_str().unwrap().to_string());
+            }
+            object_files
+        }
+
         macro_rules! test {
             ($name:ident) => {
                 #[test]
@@ -494,10 +893,187 @@ mod tests {
         fn struct_malloc() {
             run_capture_output_test("struct_malloc");
         }
+
+        #[test]
+        fn pointer_to_pointer() {
+            run_capture_output_test("pointer_to_pointer");
+        }
+
+        #[test]
+        fn prototypes() {
+            run_capture_output_test("prototypes");
+        }
+
+        #[test]
+        fn main_args() {
+            run_capture_output_test("main_args");
+        }
+
+        #[test]
+        fn array_parameters() {
+            run_capture_output_test("array_parameters");
+        }
+
+        #[test]
+        fn exit_code() {
+            run_capture_output_test("exit_code");
+            run_exit_code_test("exit_code", 42);
+        }
+
+        #[test]
+        fn switch() {
+            run_capture_output_test("switch");
+        }
+
+        #[test]
+        fn do_while() {
+            run_capture_output_test("do_while");
+        }
+
+        #[test]
+        fn ternary() {
+            run_capture_output_test("ternary");
+        }
+
+        #[test]
+        fn warnings() {
+            run_capture_output_test("warnings");
+        }
+
+        #[test]
+        fn typedef() {
+            run_capture_output_test("typedef");
+        }
+
+        #[test]
+        fn enums() {
+            run_capture_output_test("enum");
+        }
+
+        #[test]
+        fn unions() {
+            run_capture_output_test("union");
+        }
+
+        #[test]
+        fn floats() {
+            run_capture_output_test("float");
+        }
+
+        #[test]
+        fn integer_types() {
+            run_capture_output_test("integer_types");
+        }
+
+        #[test]
+        fn integer_conversions() {
+            run_capture_output_test("integer_conversions");
+        }
+
+        #[test]
+        fn function_pointers() {
+            run_capture_output_test("function_pointers");
+        }
+
+        #[test]
+        fn goto() {
+            run_capture_output_test("goto");
+        }
+
+        #[test]
+        fn multidimensional_arrays() {
+            run_capture_output_test("multidimensional_arrays");
+        }
+
+        #[test]
+        fn struct_initializers() {
+            run_capture_output_test("struct_initializers");
+        }
+
+        #[test]
+        fn incomplete_structs() {
+            run_capture_output_test("incomplete_structs");
+        }
+
+        #[test]
+        fn preprocessor() {
+            run_capture_output_test("preprocessor");
+        }
+
+        #[test]
+        fn multi_file() {
+            run_multi_file_output_test("multi_file");
+        }
+
+        #[test]
+        fn struct_abi() {
+            run_multi_file_output_test("struct_abi");
+        }
+
+        #[test]
+        fn libc_headers() {
+            run_capture_output_test("libc_headers");
+        }
+
+        #[test]
+        fn char_buffers() {
+            run_capture_output_test("char_buffers");
+        }
+
+        #[test]
+        fn varargs() {
+            run_capture_output_test("varargs");
+        }
+
+        #[test]
+        fn loop_control() {
+            run_capture_output_test("loop_control");
+        }
+
+        #[test]
+        fn ternary_pointers() {
+            run_capture_output_test("ternary_pointers");
+        }
+
+        #[test]
+        fn scalar_conditions() {
+            run_capture_output_test("scalar_conditions");
+        }
+
+        #[test]
+        fn struct_layout() {
+            run_capture_output_test("struct_layout");
+        }
+
+        #[test]
+        fn integer_promotion() {
+            run_capture_output_test("integer_promotion");
+        }
+
+        #[test]
+        fn goto_nested_labels() {
+            run_capture_output_test("goto_nested_labels");
+        }
+
+        #[test]
+        fn pointer_arrays() {
+            run_capture_output_test("pointer_arrays");
+        }
+
+        #[test]
+        fn array_address() {
+            run_capture_output_test("array_address");
+        }
+
+        #[test]
+        fn block_extern() {
+            run_capture_output_test("block_extern");
+        }
     }
 
     mod should_succeed {
         use std::panic::catch_unwind;
+        use std::path::Path;
 
         use crate::compile;
         use crate::util::display_utils::indent_string;
@@ -506,7 +1082,7 @@ mod tests {
             crate::tests::init_args();
             let file_path = format!("_c_test_files/should_succeed/{}.c", filename);
             let test = std::fs::read_to_string(file_path.clone()).expect("Could not read file.");
-            match catch_unwind(|| compile(test)) {
+            match catch_unwind(|| compile(Path::new(&file_path), test, true)) {
                 Ok(result) => {
                     if let Err(errors) = result {
                         unexpected_error_outcome!(file_path.clone(), errors);
@@ -523,6 +1099,21 @@ mod tests {
             test_should_succeed_file("pointer_member")
         }
 
+        #[test]
+        fn duplicate_enum_value() {
+            test_should_succeed_file("duplicate_enum_value")
+        }
+
+        #[test]
+        fn char_pointer_sign() {
+            test_should_succeed_file("char_pointer_sign")
+        }
+
+        #[test]
+        fn assigned_after_goto() {
+            test_should_succeed_file("assigned_after_goto")
+        }
+
         #[test]
         fn basic_blocks() {
             test_should_succeed_file("basic_blocks")
@@ -566,14 +1157,15 @@ mod tests {
 
     mod should_fail {
         use std::panic::catch_unwind;
+        use std::path::Path;
 
         use crate::compile;
 
         fn test_should_fail_file(name: &str) {
             crate::tests::init_args();
             let file_path = format!("_c_test_files/should_fail/{}.c", name);
-            let test = std::fs::read_to_string(file_path).expect("Could not read file.");
-            match catch_unwind(|| compile(test)) {
+            let test = std::fs::read_to_string(&file_path).expect("Could not read file.");
+            match catch_unwind(|| compile(Path::new(&file_path), test, true)) {
                 Ok(result) => {
                     assert!(result.is_err());
                 }
@@ -598,10 +1190,150 @@ mod tests {
             test_should_fail_file("function_in_struct")
         }
 
+        #[test]
+        fn function_pointer_mismatch() {
+            test_should_fail_file("function_pointer_mismatch")
+        }
+
         #[test]
         fn if_in_condition() {
             test_should_fail_file("if_in_condition")
         }
+
+        #[test]
+        fn pointer_depth_mismatch() {
+            test_should_fail_file("pointer_depth_mismatch")
+        }
+
+        #[test]
+        fn pointer_depth_overflow() {
+            test_should_fail_file("pointer_depth_overflow")
+        }
+
+        #[test]
+        fn ternary_pointer_mismatch() {
+            test_should_fail_file("ternary_pointer_mismatch")
+        }
+
+        #[test]
+        fn address_of_depth_overflow() {
+            test_should_fail_file("address_of_depth_overflow")
+        }
+
+        #[test]
+        fn prototype_mismatch() {
+            test_should_fail_file("prototype_mismatch")
+        }
+
+        #[test]
+        fn function_redefinition() {
+            test_should_fail_file("function_redefinition")
+        }
+
+        #[test]
+        fn invalid_main_signature() {
+            test_should_fail_file("invalid_main_signature")
+        }
+
+        #[test]
+        fn duplicate_case() {
+            test_should_fail_file("duplicate_case")
+        }
+
+        #[test]
+        fn case_without_switch() {
+            test_should_fail_file("case_without_switch")
+        }
+
+        #[test]
+        fn typedef_redefinition() {
+            test_should_fail_file("typedef_redefinition")
+        }
+
+        #[test]
+        fn duplicate_label() {
+            test_should_fail_file("duplicate_label")
+        }
+
+        #[test]
+        fn enum_out_of_range() {
+            test_should_fail_file("enum_out_of_range")
+        }
+
+        #[test]
+        fn too_many_initializers() {
+            test_should_fail_file("too_many_initializers")
+        }
+
+        #[test]
+        fn designator_out_of_bounds() {
+            test_should_fail_file("designator_out_of_bounds")
+        }
+
+        #[test]
+        fn invalid_designator() {
+            test_should_fail_file("invalid_designator")
+        }
+
+        #[test]
+        fn incomplete_struct() {
+            test_should_fail_file("incomplete_struct")
+        }
+
+        #[test]
+        fn unterminated_conditional() {
+            test_should_fail_file("unterminated_conditional")
+        }
+
+        #[test]
+        fn missing_include() {
+            test_should_fail_file("missing_include")
+        }
+
+        #[test]
+        fn static_after_non_static() {
+            test_should_fail_file("static_after_non_static")
+        }
+
+        #[test]
+        fn missing_library_include() {
+            test_should_fail_file("missing_library_include")
+        }
+
+        #[test]
+        fn undefined_label() {
+            test_should_fail_file("undefined_label")
+        }
+
+        #[test]
+        fn union_wrong_tag() {
+            test_should_fail_file("union_wrong_tag")
+        }
+
+        #[test]
+        fn va_start_outside_variadic() {
+            test_should_fail_file("va_start_outside_variadic")
+        }
+
+        #[test]
+        fn local_static() {
+            test_should_fail_file("local_static")
+        }
+
+        #[test]
+        fn block_extern_mismatch() {
+            test_should_fail_file("block_extern_mismatch")
+        }
+
+        #[test]
+        fn static_parameter() {
+            test_should_fail_file("static_parameter")
+        }
+
+        #[test]
+        fn char_pointer_depth() {
+            test_should_fail_file("char_pointer_depth")
+        }
     }
 
     #[test]
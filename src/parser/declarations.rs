@@ -3,6 +3,7 @@ use crate::data::error::CompilerError;
 use crate::data::tokens::*;
 use crate::parser::{ParseResult, Parser};
 use crate::util::Locatable;
+use crate::util::str_intern::InternedStr;
 
 use super::macros::*;
 
@@ -16,7 +17,9 @@ where
         let init_dec = if is!(
             self,
             current,
-            Token::Symbol(Symbol::Semicolon) | Token::Symbol(Symbol::Comma)
+            Token::Symbol(Symbol::Semicolon)
+                | Token::Symbol(Symbol::Comma)
+                | Token::Symbol(Symbol::OpenSquare)
         ) || is!(self, current, token if token.is_assign_op() )
         {
             let variable_declaration = self.parse_variable_declaration(dec)?;
@@ -64,11 +67,20 @@ where
 
     pub(super) fn parse_declaration(&mut self) -> ParseResult<Locatable<Declaration>> {
         let location = self.current_span()?;
-        let specifier = self.parse_declaration_specifier()?;
-        let mut ident = match_token!(self, current, Token::Identifier(ident) => ident.clone());
-        if ident.is_some() {
-            self.advance()?;
-        }
+        let mut specifier = self.parse_declaration_specifier()?;
+        let ident = if is!(self, current, Token::Symbol(Symbol::OpenParen))
+            && is!(self, next, Token::Symbol(Symbol::Star))
+        {
+            let ident = self.parse_pointer_declarator(&mut specifier.value)?;
+            specifier.location = specifier.location.merge(self.last_span);
+            ident
+        } else {
+            let ident = match_token!(self, current, Token::Identifier(ident) => ident.clone());
+            if ident.is_some() {
+                self.advance()?;
+            }
+            ident
+        };
         let location = ident
             .as_ref()
             .map_or(location, |locatable| location.merge(locatable.location));
@@ -107,22 +119,21 @@ where
                 self.advance()?;
                 let ident = self.confirm_identifier()?;
                 type_specifiers.push(TypeSpecifier::Struct(ident.value));
+            } else if is!(self, current, Token::Keyword(Keyword::Union)) {
+                self.advance()?;
+                let ident = self.confirm_identifier()?;
+                type_specifiers.push(TypeSpecifier::Union(ident.value));
+            } else if is!(self, current, Token::Keyword(Keyword::Enum)) {
+                type_specifiers.push(self.parse_enum_specifier()?);
+            } else if let Some(ident) = match_token!(self, current, Token::Identifier(ident) if type_specifiers.is_empty() && self.is_typedef_name(ident) => ident.clone())
+            {
+                type_specifiers.push(TypeSpecifier::TypedefName(ident.value));
+                self.advance()?;
             } else {
                 break;
             }
         }
-        let mut pointer = false;
-        while is!(self, current, Token::Symbol(Symbol::Star)) {
-            if pointer {
-                self.report_error(CompilerError::CustomError(
-                    "Pointer depth greater than one is not currently supported!".to_string(),
-                    span,
-                ));
-                return Err(());
-            }
-            self.advance()?;
-            pointer = true;
-        }
+        let pointer_depth = self.parse_pointer_depth()?;
         let span = span.extend(self.current_span()?);
         Ok(Locatable {
             location: span,
@@ -130,19 +141,112 @@ where
                 specifiers: storage_specifiers,
                 qualifiers: type_qualifiers,
                 ty: type_specifiers,
-                pointer,
+                pointer_depth,
+                function_pointer: None,
+                array_pointer: None,
+                parameter_array: None,
             },
         })
     }
 
-    pub(super) fn parse_function_declaration(
+    /// `(*ident)(params)` or `(*ident)[size]`, where the identifier is left out in casts and
+    /// prototypes. The identifier of a function pointer can be followed by an array size.
+    fn parse_pointer_declarator(
         &mut self,
-        declaration: Locatable<Declaration>,
-    ) -> ParseResult<Locatable<FunctionDeclaration>> {
+        specifier: &mut DeclarationSpecifier,
+    ) -> ParseResult<Option<Locatable<InternedStr>>> {
+        confirm!(self, consume, Token::Symbol(Symbol::OpenParen) => (), "(")?;
+        let pointer_depth = self.parse_pointer_depth()?;
+        let ident = match_token!(self, current, Token::Identifier(ident) => ident.clone());
+        if ident.is_some() {
+            self.advance()?;
+        }
+        let array_size = if is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
+            self.advance()?;
+            let Some(size) = self.parse_array_size()? else {
+                self.report_error(CompilerError::ArraySizeNotSpecified(self.last_span));
+                return Err(());
+            };
+            Some(size)
+        } else {
+            None
+        };
+        confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
+
+        if is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
+            if array_size.is_some() {
+                self.report_error(CompilerError::CustomError(
+                    "Arrays of pointers to arrays are not currently supported.".to_string(),
+                    self.last_span,
+                ));
+                return Err(());
+            }
+            let array_sizes = self.parse_array_dimensions()?;
+            specifier.array_pointer = Some(ArrayPointerDeclarator {
+                pointer_depth,
+                array_sizes,
+            });
+            return Ok(ident);
+        }
+
+        // the parameter names are only in scope for the parameter list
+        self.push_scope();
+        let parameters = self.parse_parameter_list();
+        self.pop_scope();
+        let (parameters, varargs) = parameters?;
+        specifier.function_pointer = Some(Box::new(FunctionPointerDeclarator {
+            pointer_depth,
+            array_size,
+            parameters,
+            varargs,
+        }));
+        Ok(ident)
+    }
+
+    /// Counts the '*' of a declarator.
+    fn parse_pointer_depth(&mut self) -> ParseResult<u8> {
+        let mut pointer_depth: u8 = 0;
+        while is!(self, current, Token::Symbol(Symbol::Star)) {
+            let Some(depth) = pointer_depth.checked_add(1) else {
+                let span = self.current_span()?;
+                self.report_error(CompilerError::PointerDepthExceeded(span));
+                return Err(());
+            };
+            self.advance()?;
+            pointer_depth = depth;
+        }
+        Ok(pointer_depth)
+    }
+
+    /// The parameters and whether they end with '...'.
+    fn parse_parameter_list(&mut self) -> ParseResult<(Vec<Locatable<Declaration>>, bool)> {
         confirm!(self, consume, Token::Symbol(Symbol::OpenParen) => (), "(")?;
         let mut parameters = Vec::new();
+        let mut varargs = false;
         while !is!(self, current, Token::Symbol(Symbol::CloseParen)) {
-            let param = self.parse_declaration()?;
+            if is!(self, current, Token::Symbol(Symbol::Ellipsis)) {
+                self.advance()?;
+                if parameters.is_empty() {
+                    self.report_error(CompilerError::VarargsRequireParameter(self.last_span));
+                    return Err(());
+                }
+                varargs = true;
+                break;
+            }
+            let mut param = self.parse_declaration()?;
+            if is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
+                self.advance()?;
+                let array_size = self.parse_array_size()?;
+                let inner_array_sizes = self.parse_array_dimensions()?;
+                param.specifier.value.parameter_array = Some(ParameterArrayDeclarator {
+                    array_size,
+                    inner_array_sizes,
+                });
+                param.location = param.location.merge(self.last_span);
+            }
+            if let Some(ident) = &param.ident {
+                self.declare_name(ident.value.clone(), false);
+            }
             parameters.push(param);
             if is!(self, current, Token::Symbol(Symbol::Comma)) {
                 self.advance()?;
@@ -151,13 +255,87 @@ where
             }
         }
         confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
-        let body = self.parse_compound_statement()?;
-        let location = declaration.location.merge(body.location);
+
+        // 'f(void)' declares a function without parameters
+        if let [param] = parameters.as_slice() {
+            let specifier = &param.specifier;
+            if param.ident.is_none()
+                && specifier.pointer_depth == 0
+                && specifier.function_pointer.is_none()
+                && specifier.parameter_array.is_none()
+                && matches!(specifier.ty.as_slice(), [TypeSpecifier::Void])
+            {
+                parameters.clear();
+            }
+        }
+        Ok((parameters, varargs))
+    }
+
+    /// `enum tag`, `enum tag { ... }` or `enum { ... }`
+    fn parse_enum_specifier(&mut self) -> ParseResult<TypeSpecifier> {
+        confirm!(self, consume, Token::Keyword(Keyword::Enum) => (), "enum")?;
+        let ident = match_token!(self, current, Token::Identifier(ident) => ident.clone());
+        if ident.is_some() {
+            self.advance()?;
+        }
+        let ident = ident.map(|ident| ident.value);
+        if ident.is_some() && !is!(self, current, Token::Symbol(Symbol::OpenCurly)) {
+            return Ok(TypeSpecifier::Enum(ident, None));
+        }
+        confirm!(self, consume, Token::Symbol(Symbol::OpenCurly) => (), "{")?;
+        let mut enumerators = Vec::new();
+        while !is!(self, current, Token::Symbol(Symbol::CloseCurly)) {
+            let enumerator_ident = self.confirm_identifier()?;
+            self.declare_name(enumerator_ident.value.clone(), false);
+            let value = if is!(self, current, Token::Symbol(Symbol::Equal)) {
+                self.advance()?;
+                Some(self.parse_binary_expression(None)?)
+            } else {
+                None
+            };
+            let location = enumerator_ident.location.merge(self.last_span);
+            enumerators.push(Locatable::new(
+                location,
+                Enumerator {
+                    ident: enumerator_ident,
+                    value,
+                },
+            ));
+            if is!(self, current, Token::Symbol(Symbol::Comma)) {
+                self.advance()?;
+            } else {
+                break;
+            }
+        }
+        confirm!(self, consume, Token::Symbol(Symbol::CloseCurly) => (), "}")?;
+        Ok(TypeSpecifier::Enum(ident, Some(enumerators)))
+    }
+
+    pub(super) fn parse_function_declaration(
+        &mut self,
+        declaration: Locatable<Declaration>,
+    ) -> ParseResult<Locatable<FunctionDeclaration>> {
+        if let Some(ident) = &declaration.ident {
+            self.declare_name(ident.value.clone(), false);
+        }
+        self.push_scope();
+        let (parameters, varargs) = self.parse_parameter_list()?;
+
+        let (body, location) = if is!(self, current, Token::Symbol(Symbol::Semicolon)) {
+            self.advance()?;
+            (None, declaration.location.merge(self.last_span))
+        } else {
+            let body = self.parse_compound_statement()?;
+            let location = declaration.location.merge(body.location);
+            (Some(body), location)
+        };
+        self.pop_scope();
         Ok(Locatable::new(
             location,
             FunctionDeclaration {
                 declaration,
                 parameters,
+                varargs,
                 body,
             },
         ))
@@ -170,34 +348,14 @@ where
         let is_array = is!(self, current, Token::Symbol(Symbol::OpenSquare));
         let array_size = if is_array {
             self.advance()?;
-            let size = if let Some(Locatable {
-                location,
-                value: (integer, suffix),
-            }) = match_token!(self, current, Token::Literal(Literal::Integer {value, suffix}) => (*value, suffix.clone()))
-            {
-                if suffix.is_some() {
-                    self.report_error(CompilerError::CustomError(
-                        "Suffixes in array sizes are not currently supported.".to_string(),
-                        location,
-                    ));
-                    return Err(());
-                }
-                self.advance()?;
-                Some(integer as usize)
-            } else {
-                None
-            };
-            confirm!(self, consume, Token::Symbol(Symbol::CloseSquare) => (), "]")?;
-            size
+            self.parse_array_size()?
         } else {
             None
         };
-        if is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
-            self.report_error(CompilerError::CustomError(
-                "Nested arrays are not currently supported!".to_string(),
-                declaration.location,
-            ));
-            return Err(());
+        let inner_array_sizes = self.parse_array_dimensions()?;
+        if let Some(ident) = &declaration.ident {
+            let is_typedef = declaration.specifier.is_typedef();
+            self.declare_name(ident.value.clone(), is_typedef);
         }
         let initializer = if is!(self, current, Token::Symbol(Symbol::Equal)) {
             self.advance()?;
@@ -211,9 +369,48 @@ where
             VariableDeclaration {
                 is_array,
                 array_size,
+                inner_array_sizes,
                 declaration,
                 initializer,
             },
         ))
     }
+
+    /// The dimensions of an array declarator, which all need a size, like the `[10][20]`
+    /// in `int (*grid)[10][20]`.
+    fn parse_array_dimensions(&mut self) -> ParseResult<Vec<usize>> {
+        let mut array_sizes = Vec::new();
+        while is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
+            self.advance()?;
+            let Some(size) = self.parse_array_size()? else {
+                self.report_error(CompilerError::ArraySizeNotSpecified(self.last_span));
+                return Err(());
+            };
+            array_sizes.push(size);
+        }
+        Ok(array_sizes)
+    }
+
+    /// The size between the brackets of an array declarator, after the '['.
+    fn parse_array_size(&mut self) -> ParseResult<Option<usize>> {
+        let size = if let Some(Locatable {
+            location,
+            value: (integer, suffix),
+        }) = match_token!(self, current, Token::Literal(Literal::Integer {value, suffix}) => (*value, suffix.clone()))
+        {
+            if suffix.is_some() {
+                self.report_error(CompilerError::CustomError(
+                    "Suffixes in array sizes are not currently supported.".to_string(),
+                    location,
+                ));
+                return Err(());
+            }
+            self.advance()?;
+            Some(integer as usize)
+        } else {
+            None
+        };
+        confirm!(self, consume, Token::Symbol(Symbol::CloseSquare) => (), "]")?;
+        Ok(size)
+    }
 }
@@ -1,6 +1,9 @@
-use crate::data::ast::{BinaryOp, Declaration, Expression, PostfixOp, TypeOrExpression, UnaryOp};
+use crate::data::ast::{
+    AssignOp, BinaryOp, Declaration, Designator, Expression, InitializerElement, PostfixOp,
+    TypeOrExpression, UnaryOp,
+};
 use crate::data::error::CompilerError;
-use crate::data::tokens::{Symbol, Token};
+use crate::data::tokens::{Keyword, Symbol, Token};
 use crate::parser::{ParseResult, Parser};
 use crate::util::Locatable;
 
@@ -35,8 +38,9 @@ where
             self.advance()?;
             let mut contents = Vec::new();
             while !is!(self, current, Token::Symbol(Symbol::CloseCurly)) {
-                let expr = self.parse_initializer()?;
-                contents.push(expr);
+                let designator = self.parse_designator()?;
+                let value = self.parse_initializer()?;
+                contents.push(InitializerElement { designator, value });
                 if is!(self, current, Token::Symbol(Symbol::Comma)) {
                     self.advance()?;
                 } else {
@@ -54,6 +58,23 @@ where
         }
     }
 
+    /// The `.member =` or `[index] =` before an element of a braced initializer.
+    fn parse_designator(&mut self) -> ParseResult<Option<Designator>> {
+        let designator = if is!(self, current, Token::Symbol(Symbol::Dot)) {
+            self.advance()?;
+            Designator::Member(self.confirm_identifier()?)
+        } else if is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
+            self.advance()?;
+            let index = self.parse_binary_expression(None)?;
+            confirm!(self, consume, Token::Symbol(Symbol::CloseSquare) => (), "]")?;
+            Designator::Index(index.map(Box::new))
+        } else {
+            return Ok(None);
+        };
+        confirm!(self, consume, Token::Symbol(Symbol::Equal) => (), "=")?;
+        Ok(Some(designator))
+    }
+
     pub(super) fn parse_binary_expression(
         &mut self,
         parent_precedence: Option<u8>,
@@ -61,7 +82,18 @@ where
         let parent_precedence = parent_precedence.unwrap_or(0);
         let mut left = self.parse_prefix_unary_expression()?;
 
-        while let Ok(bin_op) = self.match_binary_op() {
+        loop {
+            if is!(self, current, Token::Symbol(Symbol::QuestionMark)) {
+                // the conditional operator binds tighter than assignment, but looser than '||'
+                if parent_precedence >= BinaryOp::LogicalOr.precedence() {
+                    break;
+                }
+                left = self.parse_ternary_expression(left)?;
+                continue;
+            }
+            let Ok(bin_op) = self.match_binary_op() else {
+                break;
+            };
             let precedence = bin_op.value.precedence();
             if precedence == 0 || precedence <= parent_precedence {
                 break;
@@ -83,12 +115,33 @@ where
         Ok(left)
     }
 
+    fn parse_ternary_expression(
+        &mut self,
+        condition: Locatable<Expression>,
+    ) -> ParseResult<Locatable<Expression>> {
+        debug_assert!(is!(self, current, Token::Symbol(Symbol::QuestionMark)));
+        self.advance()?;
+        let then = self.parse_binary_expression(None)?;
+        confirm!(self, consume, Token::Symbol(Symbol::Colon) => (), ":")?;
+        // parsing the else arm at assignment precedence makes nested conditionals right associative
+        let otherwise = self.parse_binary_expression(Some(BinaryOp::Assign(AssignOp::Assign).precedence()))?;
+        let location = condition.location.merge(otherwise.location);
+        Ok(Locatable::new(
+            location,
+            Expression::Ternary(
+                condition.map(Box::new),
+                then.map(Box::new),
+                otherwise.map(Box::new),
+            ),
+        ))
+    }
+
     fn parse_prefix_unary_expression(&mut self) -> ParseResult<Locatable<Expression>> {
         let token = self.current.as_ref().unwrap();
         if let Ok(un_op) = UnaryOp::try_from(&token.value) {
             self.create_unop(un_op)
         } else if is!(self, current, Token::Symbol(Symbol::OpenParen))
-            && is!(self, next, Token::Keyword(kw) if kw.is_for_type())
+            && is!(self, next, token if self.is_type_start(token))
         {
             self.parse_cast()
         } else if is!(self, current, Token::Symbol(Symbol::Sizeof)) {
@@ -110,7 +163,7 @@ where
 
     fn parse_cast(&mut self) -> ParseResult<Locatable<Expression>> {
         debug_assert!(is!(self, current, Token::Symbol(Symbol::OpenParen)));
-        debug_assert!(is!(self, next, Token::Keyword(kw) if kw.is_for_type()));
+        debug_assert!(is!(self, next, token if self.is_type_start(token)));
         let location = self.current_span()?;
         self.advance()?;
         let ty = self.parse_type()?;
@@ -127,12 +180,12 @@ where
         let location = self.current_span()?;
         self.advance()?;
         let expr = if is!(self, current, Token::Symbol(Symbol::OpenParen))
-            && is!(self, next, Token::Keyword(kw) if kw.is_for_type())
+            && is!(self, next, token if self.is_type_start(token))
         {
             self.advance()?;
             let ty = self.parse_type()?;
             confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
-            Expression::Sizeof(ty.map(TypeOrExpression::Type))
+            Expression::Sizeof(ty.map(|ty| TypeOrExpression::Type(Box::new(ty))))
         } else {
             Expression::Sizeof(
                 self.parse_binary_expression(None)?
@@ -155,6 +208,9 @@ where
                 confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), "\t)")?;
                 Ok(Expression::Parenthesized(expr.map(Box::new)))
             }
+            Token::Keyword(
+                keyword @ (Keyword::VaStart | Keyword::VaArg | Keyword::VaEnd | Keyword::VaCopy),
+            ) => self.parse_va_builtin(keyword),
             _ => {
                 self.report_error(CompilerError::ExpectedButFound(
                     "Literal or Expression".to_string(),
@@ -169,6 +225,28 @@ where
         self.parse_postfix_unary_expression(expression)
     }
 
+    /// The arguments of `__builtin_va_start(ap, last)`, `__builtin_va_arg(ap, type)`,
+    /// `__builtin_va_end(ap)` or `__builtin_va_copy(dest, src)`, after the keyword.
+    fn parse_va_builtin(&mut self, keyword: Keyword) -> ParseResult<Expression> {
+        confirm!(self, consume, Token::Symbol(Symbol::OpenParen) => (), "(")?;
+        let va_list = self.parse_binary_expression(None)?.map(Box::new);
+        let expression = if keyword == Keyword::VaEnd {
+            Expression::VaEnd(va_list)
+        } else {
+            confirm!(self, consume, Token::Symbol(Symbol::Comma) => (), ",")?;
+            match keyword {
+                Keyword::VaStart => Expression::VaStart(va_list, self.confirm_identifier()?),
+                Keyword::VaArg => Expression::VaArg(va_list, self.parse_type()?),
+                _ => {
+                    let src = self.parse_binary_expression(None)?.map(Box::new);
+                    Expression::VaCopy(va_list, src)
+                }
+            }
+        };
+        confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
+        Ok(expression)
+    }
+
     fn parse_postfix_unary_expression(
         &mut self,
         primary_expr: Locatable<Expression>,
@@ -185,9 +263,7 @@ where
                 Expression::PostFix(op, primary_expr.map(Box::new)),
             );
             self.parse_postfix_unary_expression(expr)
-        } else if is!(self, current, Token::Symbol(Symbol::OpenParen))
-            && matches!(&primary_expr.value, Expression::Variable(_))
-        {
+        } else if is!(self, current, Token::Symbol(Symbol::OpenParen)) {
             self.parse_function_call(primary_expr)
         } else if is!(self, current, Token::Symbol(Symbol::OpenSquare)) {
             self.parse_index_access(primary_expr)
@@ -205,12 +281,7 @@ where
         primary_expr: Locatable<Expression>,
     ) -> ParseResult<Locatable<Expression>> {
         debug_assert!(is!(self, current, Token::Symbol(Symbol::OpenParen)));
-        debug_assert!(matches!(&primary_expr.value, Expression::Variable(_)));
         let location = primary_expr.location;
-        let ident = match primary_expr.value {
-            Expression::Variable(var) => var,
-            _ => panic!("Nothing else should get to this point."),
-        };
         self.advance()?;
         let mut args = Vec::new();
         while !is!(self, current, Token::Symbol(Symbol::CloseParen)) {
@@ -224,7 +295,8 @@ where
         }
         confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), "\t)")?;
         let location = location.merge(self.last_span);
-        let expr = Locatable::new(location, Expression::FunctionCall(ident, args));
+        let callee = primary_expr.map(Box::new);
+        let expr = Locatable::new(location, Expression::FunctionCall(callee, args));
         self.parse_postfix_unary_expression(expr)
     }
 
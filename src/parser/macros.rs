@@ -71,6 +71,7 @@ macro_rules! confirm {
         $pattern:pat $(if $guard:expr)? => $if_ok:expr,
         $if_err:expr
     ) => {{
+        $invoker.check_for_eof($if_err)?;
         let locatable = &$invoker.current.as_ref().unwrap();
         let value = &locatable.value;
         let location = locatable.location;
@@ -103,7 +104,7 @@ macro_rules! confirm {
         $pattern:pat $(if $guard:expr)? => $if_ok:expr,
         $if_err:expr
     ) => {{
-        let formatted = format!("{:#?}", $value);
+        let formatted = format!("{:?}", $value);
         #[allow(clippy::redundant_closure_call)]
         match $closure($value) {
             $pattern $(if $guard)? => Ok(Locatable::new($location, $if_ok)),
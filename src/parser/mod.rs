@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use rand::RngCore;
 
 use macros::*;
@@ -35,6 +37,9 @@ where
     next: Option<LocatableToken>,
     last_span: Span,
     current_span: Span,
+    /// Names declared in each open scope, `true` for typedef names.
+    /// C needs this to tell whether `T * x;` declares `x` or multiplies.
+    names: Vec<HashMap<InternedStr, bool>>,
 }
 
 impl<L> Parser<L>
@@ -50,6 +55,7 @@ where
             next: None,
             last_span: Span::default(),
             current_span: Span::default(),
+            names: vec![HashMap::new()],
         }
     }
 
@@ -88,7 +94,7 @@ where
     #[inline(always)]
     pub(super) fn check_for_eof(&mut self, expected: &'static str) -> ParseResult<()> {
         if self.current.is_none() {
-            self.report_error(CompilerError::UnexpectedEOF);
+            self.report_error(CompilerError::UnexpectedEOF(self.last_span));
             Err(())
         } else {
             Ok(())
@@ -151,12 +157,44 @@ where
         match self.current.as_ref() {
             Some(locatable) => Ok(locatable.location),
             None => {
-                self.report_error(CompilerError::UnexpectedEOF);
+                self.report_error(CompilerError::UnexpectedEOF(self.last_span));
                 Err(())
             }
         }
     }
 
+    pub(super) fn push_scope(&mut self) {
+        self.names.push(HashMap::new());
+    }
+
+    pub(super) fn pop_scope(&mut self) {
+        self.names.pop();
+    }
+
+    pub(super) fn declare_name(&mut self, ident: InternedStr, is_typedef: bool) {
+        self.names
+            .last_mut()
+            .expect("There is always a file scope.")
+            .insert(ident, is_typedef);
+    }
+
+    pub(super) fn is_typedef_name(&self, ident: &InternedStr) -> bool {
+        self.names
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(ident))
+            .is_some_and(|is_typedef| *is_typedef)
+    }
+
+    /// Whether the token can begin a declaration.
+    pub(super) fn is_type_start(&self, token: &Token) -> bool {
+        match token {
+            Token::Keyword(keyword) => keyword.is_for_type() || *keyword == Keyword::Typedef,
+            Token::Identifier(ident) => self.is_typedef_name(ident),
+            _ => false,
+        }
+    }
+
     pub(super) fn advance(&mut self) -> ParseResult<()> {
         self.current = self.next.take();
         self.next = self.tokens.next();
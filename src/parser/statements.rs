@@ -12,11 +12,13 @@ where
     pub fn parse_compound_statement(&mut self) -> ParseResult<Locatable<Block>> {
         let location = self.current_span()?;
         confirm!(self, consume, Token::Symbol(Symbol::OpenCurly) => (), "{")?;
+        self.push_scope();
         let mut body = Vec::new();
         while !is!(self, current, Token::Symbol(Symbol::CloseCurly)) {
             let stmt = self.parse_statement()?;
             body.push(stmt);
         }
+        self.pop_scope();
         confirm!(self, consume, Token::Symbol(Symbol::CloseCurly) => (), "}")?;
         let location = location.merge(self.last_span);
         Ok(Locatable::new(location, Block(body)))
@@ -25,6 +27,9 @@ where
     pub fn parse_statement(&mut self) -> ParseResult<Locatable<Statement>> {
         self.check_for_eof("statement")?;
         let location = self.current_span()?;
+        // storage specifiers are parsed here so the analyzer can report them on local variables
+        let is_declaration = is!(self, current, token if self.is_type_start(token)
+            || matches!(token, Token::Keyword(Keyword::Static | Keyword::Extern)));
         match self.current.as_ref().unwrap().value {
             Token::Symbol(Symbol::Semicolon) => Ok(self.consume()?.map(|_| Statement::Empty)),
             Token::Keyword(Keyword::Continue) => {
@@ -32,6 +37,21 @@ where
                 self.confirm_semicolon()?;
                 Ok(stmt)
             }
+            Token::Keyword(Keyword::Goto) => {
+                self.advance()?;
+                let ident = self.confirm_identifier()?;
+                self.confirm_semicolon()?;
+                let location = location.merge(self.last_span);
+                Ok(Locatable::new(location, Statement::Goto(ident.value)))
+            }
+            // labels live in their own namespace, so a typedef name can also be a label
+            Token::Identifier(ref ident) if is!(self, next, Token::Symbol(Symbol::Colon)) => {
+                let ident = ident.clone();
+                self.consume()?;
+                confirm!(self, consume, Token::Symbol(Symbol::Colon) => (), ":")?;
+                let location = location.merge(self.last_span);
+                Ok(Locatable::new(location, Statement::Label(ident)))
+            }
             Token::Keyword(Keyword::Break) => {
                 let stmt = self.consume()?.map(|_| Statement::Break);
                 self.confirm_semicolon()?;
@@ -42,7 +62,7 @@ where
                 let location = block.location;
                 Ok(Locatable::new(location, Statement::Block(block)))
             }
-            Token::Keyword(keyword) if keyword.is_for_type() => {
+            _ if is_declaration => {
                 let dec = self.parse_declaration()?;
                 let variable_declaration = self.parse_variable_declaration(dec)?;
                 let res = self.confirm_semicolon()?;
@@ -91,9 +111,21 @@ where
                 let location = location.merge(stmt.location);
                 Ok(Locatable::new(location, Statement::While(condition, stmt)))
             }
+            Token::Keyword(Keyword::Do) => {
+                self.advance()?;
+                let stmt = Box::new(self.parse_statement()?);
+                confirm!(self, consume, Token::Keyword(Keyword::While) => (), "while")?;
+                confirm!(self, consume, Token::Symbol(Symbol::OpenParen) => (), "(")?;
+                let condition = self.parse_binary_expression(None)?;
+                confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
+                self.confirm_semicolon()?;
+                let location = location.merge(self.last_span);
+                Ok(Locatable::new(location, Statement::DoWhile(stmt, condition)))
+            }
             Token::Keyword(Keyword::For) => {
                 self.advance()?;
                 confirm!(self, consume, Token::Symbol(Symbol::OpenParen) => (), "(")?;
+                self.push_scope();
                 let initializer = if is!(self, current, Token::Symbol(Symbol::Semicolon)) {
                     None
                 } else {
@@ -114,12 +146,35 @@ where
                 };
                 confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
                 let stmt = Box::new(self.parse_statement()?);
+                self.pop_scope();
                 let location = location.merge(stmt.location);
                 Ok(Locatable::new(
                     location,
                     Statement::For(initializer, condition, after_loop, stmt),
                 ))
             }
+            Token::Keyword(Keyword::Switch) => {
+                self.advance()?;
+                confirm!(self, consume, Token::Symbol(Symbol::OpenParen) => (), "(")?;
+                let controlling = self.parse_binary_expression(None)?;
+                confirm!(self, consume, Token::Symbol(Symbol::CloseParen) => (), ")")?;
+                let body = Box::new(self.parse_statement()?);
+                let location = location.merge(body.location);
+                Ok(Locatable::new(location, Statement::Switch(controlling, body)))
+            }
+            Token::Keyword(Keyword::Case) => {
+                self.advance()?;
+                let value = self.parse_binary_expression(None)?;
+                confirm!(self, consume, Token::Symbol(Symbol::Colon) => (), ":")?;
+                let location = location.merge(self.last_span);
+                Ok(Locatable::new(location, Statement::Case(value)))
+            }
+            Token::Keyword(Keyword::Default) => {
+                self.advance()?;
+                confirm!(self, consume, Token::Symbol(Symbol::Colon) => (), ":")?;
+                let location = location.merge(self.last_span);
+                Ok(Locatable::new(location, Statement::Default))
+            }
             Token::Keyword(Keyword::Else) => {
                 let location = self.consume()?.location;
                 self.report_error(CompilerError::ElseWithNoIf(location));
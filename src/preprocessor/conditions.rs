@@ -0,0 +1,171 @@
+use crate::data::ast::BinaryOp;
+use crate::data::error::CompilerError;
+use crate::data::tokens::{Literal, Symbol, Token};
+
+use super::*;
+
+impl Preprocessor {
+    /// Evaluates the expression of an `#if` or `#elif`, an invalid expression is reported and false.
+    pub(super) fn evaluate_condition(&mut self, tokens: Vec<Locatable<Token>>, span: Span) -> bool {
+        let span = tokens.last().map_or(span, |last| span.merge(last.location));
+        let Some(tokens) = self.replace_defined(tokens) else {
+            self.report_error(CompilerError::InvalidConditionExpression(span));
+            return false;
+        };
+
+        // any identifier left after expanding macros, keywords included, counts as 0
+        let tokens = self
+            .expand(tokens)
+            .into_iter()
+            .map(|token| match token.value {
+                Token::Identifier(_) | Token::Keyword(_) | Token::Symbol(Symbol::Sizeof) => {
+                    Token::Literal(Literal::Integer {
+                        value: 0,
+                        suffix: None,
+                    })
+                }
+                value => value,
+            })
+            .collect::<Vec<_>>();
+
+        let mut evaluator = ConditionEvaluator {
+            tokens: &tokens,
+            position: 0,
+        };
+        match evaluator.expression(0) {
+            Some(value) if evaluator.position == tokens.len() => value != 0,
+            _ => {
+                self.report_error(CompilerError::InvalidConditionExpression(span));
+                false
+            }
+        }
+    }
+
+    /// Replaces `defined NAME` and `defined(NAME)` with 1 or 0, before macros are expanded.
+    fn replace_defined(&self, tokens: Vec<Locatable<Token>>) -> Option<Vec<Locatable<Token>>> {
+        let mut output = Vec::new();
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            if !matches!(&token.value, Token::Identifier(ident) if &**ident == "defined") {
+                output.push(token);
+                continue;
+            }
+
+            let mut name = tokens.next();
+            let parenthesized = name
+                .as_ref()
+                .is_some_and(|name| name.value == Token::Symbol(Symbol::OpenParen));
+            if parenthesized {
+                name = tokens.next();
+            }
+            let Some(Token::Identifier(name)) = name.map(|name| name.value) else {
+                return None;
+            };
+            if parenthesized
+                && !tokens
+                    .next()
+                    .is_some_and(|close| close.value == Token::Symbol(Symbol::CloseParen))
+            {
+                return None;
+            }
+
+            let value = self.macros.contains_key(&name) as isize;
+            output.push(Locatable::new(
+                token.location,
+                Token::Literal(Literal::Integer {
+                    value,
+                    suffix: None,
+                }),
+            ));
+        }
+        Some(output)
+    }
+}
+
+/// Evaluates the integer constant expression of a conditional directive, using the
+/// same precedences as the parser.
+struct ConditionEvaluator<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl ConditionEvaluator<'_> {
+    fn expression(&mut self, parent_precedence: u8) -> Option<i64> {
+        let mut left = self.unary()?;
+        while let Some(token) = self.tokens.get(self.position) {
+            if *token == Token::Symbol(Symbol::QuestionMark) {
+                if parent_precedence >= BinaryOp::LogicalOr.precedence() {
+                    break;
+                }
+                self.position += 1;
+                let then = self.expression(0)?;
+                self.expect(Symbol::Colon)?;
+                let otherwise = self.expression(1)?;
+                left = if left != 0 { then } else { otherwise };
+                continue;
+            }
+
+            let Ok(op) = BinaryOp::try_from(token) else {
+                break;
+            };
+            if matches!(op, BinaryOp::Assign(_)) || op.precedence() <= parent_precedence {
+                break;
+            }
+            self.position += 1;
+            let right = self.expression(op.precedence())?;
+            left = apply(op, left, right)?;
+        }
+        Some(left)
+    }
+
+    fn unary(&mut self) -> Option<i64> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        match token {
+            Token::Literal(Literal::Integer { value, .. }) => Some(*value as i64),
+            Token::Literal(Literal::Char { value }) => Some(*value as i64),
+            Token::Symbol(Symbol::Plus) => self.unary(),
+            Token::Symbol(Symbol::Minus) => self.unary().map(i64::wrapping_neg),
+            Token::Symbol(Symbol::Bang) => self.unary().map(|value| (value == 0) as i64),
+            Token::Symbol(Symbol::Tilde) => self.unary().map(|value| !value),
+            Token::Symbol(Symbol::OpenParen) => {
+                let value = self.expression(0)?;
+                self.expect(Symbol::CloseParen)?;
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, symbol: Symbol) -> Option<()> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        (*token == Token::Symbol(symbol)).then_some(())
+    }
+}
+
+/// Division by zero makes the expression invalid.
+fn apply(op: BinaryOp, left: i64, right: i64) -> Option<i64> {
+    use BinaryOp::*;
+    Some(match op {
+        Add => left.wrapping_add(right),
+        Sub => left.wrapping_sub(right),
+        Mul => left.wrapping_mul(right),
+        Div => left.checked_div(right)?,
+        Mod => left.checked_rem(right)?,
+        Equal => (left == right) as i64,
+        NotEqual => (left != right) as i64,
+        GreaterThan => (left > right) as i64,
+        GreaterThanEqual => (left >= right) as i64,
+        LessThan => (left < right) as i64,
+        LessThanEqual => (left <= right) as i64,
+        LogicalAnd => (left != 0 && right != 0) as i64,
+        LogicalOr => (left != 0 || right != 0) as i64,
+        BitwiseAnd => left & right,
+        BitwiseOr => left | right,
+        BitwiseXor => left ^ right,
+        LeftShift => left.wrapping_shl(right as u32),
+        RightShift => left.wrapping_shr(right as u32),
+        Assign(_) => return None,
+    })
+}
@@ -0,0 +1,342 @@
+use std::iter::Peekable;
+use std::path::Path;
+use std::rc::Rc;
+use std::vec::IntoIter;
+
+use arcstr::ArcStr;
+
+use crate::data::error::CompilerError;
+use crate::data::libc;
+use crate::data::tokens::{Literal, Symbol, Token};
+use crate::util::source_map;
+use crate::util::str_intern::InternedStr;
+
+use super::*;
+
+impl Preprocessor {
+    pub(super) fn directive(
+        &mut self,
+        line: Line,
+        conditionals: &mut Vec<Conditional>,
+        path: &Path,
+        source: &ArcStr,
+    ) {
+        let active = conditionals.iter().all(|conditional| conditional.active);
+        if active {
+            self.errors.extend(line.errors);
+        }
+
+        let mut tokens = line.tokens.into_iter();
+        let hash = tokens.next().unwrap();
+        // a '#' on its own is a null directive
+        let Some(name) = tokens.next() else {
+            return;
+        };
+        let directive = name.value.to_string();
+        let span = hash.location.merge(name.location);
+        let tokens = tokens.collect::<Vec<_>>();
+
+        match directive.as_str() {
+            "if" | "ifdef" | "ifndef" => {
+                let keep = active && self.evaluate_directive(&directive, tokens, span);
+                conditionals.push(Conditional {
+                    directive,
+                    span,
+                    active: keep,
+                    taken: keep || !active,
+                    else_span: None,
+                });
+            }
+            "elif" | "else" => {
+                let Some(conditional) = conditionals.last_mut() else {
+                    self.report_error(CompilerError::UnmatchedConditional(directive, span));
+                    return;
+                };
+                if conditional.else_span.is_some() {
+                    self.report_error(CompilerError::DirectiveAfterElse(
+                        directive,
+                        span,
+                        conditional.else_span,
+                    ));
+                    return;
+                }
+                if directive == "else" {
+                    conditional.active = !conditional.taken;
+                    conditional.else_span = Some(span);
+                } else {
+                    conditional.active =
+                        !conditional.taken && self.evaluate_directive(&directive, tokens, span);
+                }
+                conditional.taken |= conditional.active;
+            }
+            "endif" => {
+                if conditionals.pop().is_none() {
+                    self.report_error(CompilerError::UnmatchedConditional(directive, span));
+                }
+            }
+
+            // everything else is ignored in a skipped branch
+            _ if !active => {}
+
+            "define" => self.define_macro(tokens, span),
+            "undef" => match tokens.first() {
+                Some(Locatable {
+                    value: Token::Identifier(name),
+                    ..
+                }) => {
+                    self.macros.remove(name);
+                }
+                found => self.report_expected("a macro name", found, span),
+            },
+            "include" => self.include(tokens, span, path, source),
+            "error" => {
+                let (message, span) = match (tokens.first(), tokens.last()) {
+                    (Some(first), Some(last)) => (
+                        source_text(source, first.location.start, last.location.end),
+                        span.merge(last.location),
+                    ),
+                    _ => (String::new(), span),
+                };
+                self.report_error(CompilerError::ErrorDirective(message, span));
+            }
+            "pragma" => {
+                let is_once = tokens
+                    .first()
+                    .is_some_and(|token| token.value.to_string() == "once");
+                if let (true, Ok(path)) = (is_once, path.canonicalize()) {
+                    self.included_once.insert(path);
+                }
+                // unknown pragmas are ignored, like other compilers do
+            }
+            _ => self.report_error(CompilerError::UnknownDirective(directive, name.location)),
+        }
+    }
+
+    fn evaluate_directive(
+        &mut self,
+        directive: &str,
+        tokens: Vec<Locatable<Token>>,
+        span: Span,
+    ) -> bool {
+        if directive != "ifdef" && directive != "ifndef" {
+            return self.evaluate_condition(tokens, span);
+        }
+        match tokens.first() {
+            Some(Locatable {
+                value: Token::Identifier(name),
+                ..
+            }) => self.macros.contains_key(name) == (directive == "ifdef"),
+            found => {
+                self.report_expected("a macro name", found, span);
+                false
+            }
+        }
+    }
+
+    /// Handles `#define`, the tokens are everything after the directive's name.
+    pub(super) fn define_macro(&mut self, tokens: Vec<Locatable<Token>>, span: Span) {
+        let mut tokens = tokens.into_iter().peekable();
+        let name = match tokens.next() {
+            Some(Locatable {
+                location,
+                value: Token::Identifier(name),
+            }) => Locatable::new(location, name),
+            found => {
+                self.report_expected("a macro name", found.as_ref(), span);
+                return;
+            }
+        };
+
+        // only a '(' right after the name makes a function-like macro
+        let params = match tokens.peek() {
+            Some(paren)
+                if paren.value == Token::Symbol(Symbol::OpenParen)
+                    && paren.location.start == name.location.end =>
+            {
+                tokens.next();
+                match self.macro_params(&mut tokens, span) {
+                    Some(params) => Some(params),
+                    None => return,
+                }
+            }
+            _ => None,
+        };
+
+        let body = tokens.collect::<Vec<_>>();
+        for (index, token) in body.iter().enumerate() {
+            match token.value {
+                Token::Symbol(Symbol::HashHash) if index == 0 || index == body.len() - 1 => {
+                    self.report_error(CompilerError::CustomError(
+                        "'##' cannot be at either end of a macro".to_string(),
+                        token.location,
+                    ));
+                    return;
+                }
+                Token::Symbol(Symbol::Hash) if params.is_some() => {
+                    let next = body.get(index + 1);
+                    let is_param = next.is_some_and(|next| match &next.value {
+                        Token::Identifier(ident) => params.as_ref().unwrap().contains(ident),
+                        _ => false,
+                    });
+                    if !is_param {
+                        self.report_expected("a macro parameter after '#'", next, token.location);
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let definition = Macro { name, params, body };
+        let redefined = self
+            .macros
+            .get(&definition.name.value)
+            .filter(|previous| !previous.is_same_definition(&definition))
+            .map(|previous| previous.name.location);
+        if redefined.is_some() {
+            self.report_error(CompilerError::MacroRedefinition(
+                definition.name.value.to_string(),
+                definition.name.location,
+                redefined,
+            ));
+        }
+        self.macros
+            .insert(definition.name.value.clone(), Rc::new(definition));
+    }
+
+    fn macro_params(
+        &mut self,
+        tokens: &mut Peekable<IntoIter<Locatable<Token>>>,
+        span: Span,
+    ) -> Option<Vec<InternedStr>> {
+        let mut params: Vec<InternedStr> = Vec::new();
+        if tokens
+            .next_if(|token| token.value == Token::Symbol(Symbol::CloseParen))
+            .is_some()
+        {
+            return Some(params);
+        }
+
+        loop {
+            match tokens.next() {
+                Some(Locatable {
+                    location,
+                    value: Token::Identifier(param),
+                }) => {
+                    if params.contains(&param) {
+                        self.report_error(CompilerError::CustomError(
+                            format!("Duplicate macro parameter '{param}'"),
+                            location,
+                        ));
+                        return None;
+                    }
+                    params.push(param);
+                }
+                found => {
+                    self.report_expected("a parameter name", found.as_ref(), span);
+                    return None;
+                }
+            }
+
+            match tokens.next() {
+                Some(token) if token.value == Token::Symbol(Symbol::Comma) => {}
+                Some(token) if token.value == Token::Symbol(Symbol::CloseParen) => {
+                    return Some(params)
+                }
+                found => {
+                    self.report_expected("',' or ')'", found.as_ref(), span);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// `"file"` is looked for next to the including file and then in the include directories,
+    /// `<file>` only in the include directories.
+    fn include(&mut self, tokens: Vec<Locatable<Token>>, span: Span, path: &Path, source: &ArcStr) {
+        let (name, quoted) = match tokens.first().map(|token| &token.value) {
+            Some(Token::Literal(Literal::String { value })) => (value.to_string(), true),
+            Some(Token::Symbol(Symbol::LessThan)) => {
+                let open = &tokens[0];
+                let close = tokens
+                    .iter()
+                    .find(|token| token.value == Token::Symbol(Symbol::GreaterThan));
+                let Some(close) = close else {
+                    self.report_expected("'>'", None, span);
+                    return;
+                };
+                let name = source_text(source, open.location.end, close.location.start);
+                (name, false)
+            }
+            _ => {
+                self.report_expected("a file name", tokens.first(), span);
+                return;
+            }
+        };
+        let span = span.merge(tokens.last().unwrap().location);
+
+        let relative = path
+            .parent()
+            .map(|directory| directory.join(&name))
+            .filter(|path| quoted && path.is_file());
+        let found = relative.or_else(|| {
+            self.include_dirs
+                .iter()
+                .map(|directory| directory.join(&name))
+                .find(|path| path.is_file())
+        });
+        let Some(found) = found else {
+            if let Some(header_source) = libc::header_source(&name) {
+                let source: ArcStr = header_source.into();
+                let file = source_map::add_file(format!("<{name}>"), source.clone());
+                self.process_file(file, Path::new(&name), &source);
+                return;
+            }
+            // the C library headers need no file, the compiler knows their declarations
+            let Some(header) = libc::header(&name) else {
+                self.report_error(CompilerError::IncludeNotFound(name, span));
+                return;
+            };
+            if !self
+                .library_headers
+                .iter()
+                .any(|included| included.name == header.name)
+            {
+                self.library_headers.push(header);
+            }
+            return;
+        };
+
+        if found
+            .canonicalize()
+            .is_ok_and(|path| self.included_once.contains(&path))
+        {
+            return;
+        }
+        if self.include_depth >= MAX_INCLUDE_DEPTH {
+            self.report_error(CompilerError::IncludeDepthExceeded(span));
+            return;
+        }
+        let source: ArcStr = match std::fs::read_to_string(&found) {
+            Ok(source) => source.into(),
+            Err(error) => {
+                self.report_error(CompilerError::IoError(error));
+                return;
+            }
+        };
+
+        let file = source_map::add_file(found.display().to_string(), source.clone());
+        self.include_depth += 1;
+        self.process_file(file, &found, &source);
+        self.include_depth -= 1;
+    }
+}
+
+/// The text between two character positions of a file.
+fn source_text(source: &str, start: usize, end: usize) -> String {
+    source
+        .chars()
+        .skip(start)
+        .take(end.saturating_sub(start))
+        .collect()
+}
@@ -0,0 +1,323 @@
+use std::collections::VecDeque;
+
+use crate::data::error::CompilerError;
+use crate::data::tokens::{Literal, Symbol, Token};
+use crate::lexer::Lexer;
+use crate::util::source_map::{self, Expansion};
+use crate::util::str_intern::{self, InternedStr};
+
+use super::*;
+
+pub(super) struct Macro {
+    pub(super) name: Locatable<InternedStr>,
+    /// `None` for an object-like macro.
+    pub(super) params: Option<Vec<InternedStr>>,
+    pub(super) body: Vec<Locatable<Token>>,
+}
+
+impl Macro {
+    /// A macro may only be defined again with the same parameters and replacement.
+    pub(super) fn is_same_definition(&self, other: &Macro) -> bool {
+        self.params == other.params
+            && self.body.len() == other.body.len()
+            && self
+                .body
+                .iter()
+                .zip(&other.body)
+                .all(|(token, other)| token.value == other.value)
+    }
+}
+
+/// A token being expanded, its hide set holds the macros it came out of.
+/// A macro is never expanded again inside its own replacement, which stops recursive macros.
+struct ExpansionToken {
+    token: Locatable<Token>,
+    hide_set: Vec<InternedStr>,
+}
+
+impl Clone for ExpansionToken {
+    fn clone(&self) -> Self {
+        Self {
+            token: Locatable::new(self.token.location, self.token.value.clone()),
+            hide_set: self.hide_set.clone(),
+        }
+    }
+}
+
+impl Preprocessor {
+    /// Replaces the macros in the tokens, the replacements are rescanned for more macros.
+    pub(super) fn expand(&mut self, tokens: Vec<Locatable<Token>>) -> Vec<Locatable<Token>> {
+        let tokens = tokens
+            .into_iter()
+            .map(|token| ExpansionToken {
+                token,
+                hide_set: Vec::new(),
+            })
+            .collect();
+        self.expand_tokens(tokens)
+            .into_iter()
+            .map(|token| token.token)
+            .collect()
+    }
+
+    fn expand_tokens(&mut self, tokens: Vec<ExpansionToken>) -> Vec<ExpansionToken> {
+        let mut input = VecDeque::from(tokens);
+        let mut output = Vec::new();
+
+        while let Some(token) = input.pop_front() {
+            let definition = match &token.token.value {
+                Token::Identifier(name) if !token.hide_set.contains(name) => {
+                    self.macros.get(name).cloned()
+                }
+                _ => None,
+            };
+            let Some(definition) = definition else {
+                output.push(token);
+                continue;
+            };
+
+            let name = definition.name.value.clone();
+            let mut hide_set = token.hide_set.clone();
+            let replacement = match &definition.params {
+                None => {
+                    hide_set.push(name);
+                    self.substitute(&definition, token.token.location, Vec::new(), hide_set)
+                }
+                Some(params) => {
+                    // a function-like macro without arguments is an ordinary identifier
+                    let is_call = input
+                        .front()
+                        .is_some_and(|next| next.token.value == Token::Symbol(Symbol::OpenParen));
+                    if !is_call {
+                        output.push(token);
+                        continue;
+                    }
+
+                    let Some((args, close)) = self.collect_arguments(&name, &token, &mut input)
+                    else {
+                        continue;
+                    };
+                    let location = token.token.location;
+                    let close_location = close.token.location;
+                    let use_site = if close_location.file == location.file
+                        && close_location.expansion == location.expansion
+                    {
+                        location.merge(close_location)
+                    } else {
+                        location
+                    };
+
+                    // `F()` is a call with no arguments, not one empty argument
+                    let args = if params.is_empty() && args.len() == 1 && args[0].is_empty() {
+                        Vec::new()
+                    } else {
+                        args
+                    };
+                    if args.len() != params.len() {
+                        self.report_error(CompilerError::MacroArgumentCount(
+                            name.to_string(),
+                            params.len(),
+                            args.len(),
+                            use_site,
+                        ));
+                        continue;
+                    }
+
+                    hide_set.retain(|hidden| close.hide_set.contains(hidden));
+                    hide_set.push(name);
+                    self.substitute(&definition, use_site, args, hide_set)
+                }
+            };
+
+            for token in replacement.into_iter().rev() {
+                input.push_front(token);
+            }
+        }
+        output
+    }
+
+    /// Takes the parenthesized, comma separated arguments of a function-like macro from the input,
+    /// returning them with the closing parenthesis.
+    fn collect_arguments(
+        &mut self,
+        name: &InternedStr,
+        token: &ExpansionToken,
+        input: &mut VecDeque<ExpansionToken>,
+    ) -> Option<(Vec<Vec<ExpansionToken>>, ExpansionToken)> {
+        input.pop_front();
+        let mut args = vec![Vec::new()];
+        let mut depth = 0;
+        while let Some(next) = input.pop_front() {
+            match &next.token.value {
+                Token::Symbol(Symbol::OpenParen) => depth += 1,
+                Token::Symbol(Symbol::CloseParen) if depth == 0 => return Some((args, next)),
+                Token::Symbol(Symbol::CloseParen) => depth -= 1,
+                Token::Symbol(Symbol::Comma) if depth == 0 => {
+                    args.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+            args.last_mut().unwrap().push(next);
+        }
+
+        self.report_error(CompilerError::UnterminatedMacroCall(
+            name.to_string(),
+            token.token.location,
+        ));
+        None
+    }
+
+    /// Copies a macro's replacement to the use site, replacing its parameters with the arguments
+    /// and applying the `#` and `##` operators.
+    fn substitute(
+        &mut self,
+        definition: &Macro,
+        use_site: Span,
+        args: Vec<Vec<ExpansionToken>>,
+        hide_set: Vec<InternedStr>,
+    ) -> Vec<ExpansionToken> {
+        let params = definition.params.as_deref().unwrap_or_default();
+        let param = |token: &Locatable<Token>| match &token.value {
+            Token::Identifier(ident) => params.iter().position(|param| param == ident),
+            _ => None,
+        };
+
+        let mut expanded_args: Vec<Option<Vec<ExpansionToken>>> = vec![None; args.len()];
+        let mut output: Vec<ExpansionToken> = Vec::new();
+        // set when the left side of a '##' is an empty argument, leaving nothing to paste onto
+        let mut placemarker = false;
+        let mut index = 0;
+        while index < definition.body.len() {
+            let token = &definition.body[index];
+            let next = definition.body.get(index + 1);
+            let next_is_paste =
+                next.is_some_and(|next| next.value == Token::Symbol(Symbol::HashHash));
+
+            match (&token.value, param(token)) {
+                // the parameter after '#' is checked when the macro is defined
+                (Token::Symbol(Symbol::Hash), _) if definition.params.is_some() => {
+                    let arg = &args[param(next.unwrap()).unwrap()];
+                    let location = copy_to_use_site(definition, token, use_site).token.location;
+                    output.push(stringify(arg, location));
+                    placemarker = false;
+                    index += 1;
+                }
+                (Token::Symbol(Symbol::HashHash), _) => {
+                    let next = next.unwrap();
+                    let right = match param(next) {
+                        Some(param) => args[param].clone(),
+                        None => vec![copy_to_use_site(definition, next, use_site)],
+                    };
+                    let mut right = right.into_iter();
+                    match right.next() {
+                        Some(first) if !placemarker => {
+                            let left = output.pop().unwrap();
+                            let pasted = self.paste(left, first);
+                            output.push(pasted);
+                        }
+                        Some(first) => {
+                            output.push(first);
+                            placemarker = false;
+                        }
+                        None => {}
+                    }
+                    output.extend(right);
+                    index += 1;
+                }
+                (_, Some(param)) if next_is_paste => {
+                    output.extend(args[param].iter().cloned());
+                    placemarker = args[param].is_empty();
+                }
+                (_, Some(param)) => {
+                    if expanded_args[param].is_none() {
+                        expanded_args[param] = Some(self.expand_tokens(args[param].clone()));
+                    }
+                    output.extend(expanded_args[param].iter().flatten().cloned());
+                    placemarker = false;
+                }
+                (_, None) => {
+                    output.push(copy_to_use_site(definition, token, use_site));
+                    placemarker = false;
+                }
+            }
+            index += 1;
+        }
+
+        for token in &mut output {
+            for hidden in &hide_set {
+                if !token.hide_set.contains(hidden) {
+                    token.hide_set.push(hidden.clone());
+                }
+            }
+        }
+        output
+    }
+
+    /// Joins two tokens into one by lexing their spellings together.
+    fn paste(&mut self, left: ExpansionToken, right: ExpansionToken) -> ExpansionToken {
+        let text = format!("{}{}", left.token.value, right.token.value);
+        let mut lexer = Lexer::new(text.as_str().into());
+        match (lexer.next(), lexer.next()) {
+            (Some(Ok(token)), None) => ExpansionToken {
+                token: Locatable::new(left.token.location, token.value),
+                hide_set: left.hide_set,
+            },
+            _ => {
+                self.report_error(CompilerError::InvalidTokenPaste(
+                    left.token.value.to_string(),
+                    right.token.value.to_string(),
+                    left.token.location,
+                ));
+                left
+            }
+        }
+    }
+}
+
+/// A token of a macro's replacement, placed at the use site and remembering its definition.
+fn copy_to_use_site(
+    definition: &Macro,
+    token: &Locatable<Token>,
+    use_site: Span,
+) -> ExpansionToken {
+    let expansion = source_map::add_expansion(Expansion {
+        name: definition.name.value.clone(),
+        definition: token.location,
+        parent: use_site.expansion,
+    });
+    let location = Span {
+        expansion: Some(expansion),
+        ..use_site
+    };
+    ExpansionToken {
+        token: Locatable::new(location, token.value.clone()),
+        hide_set: Vec::new(),
+    }
+}
+
+/// The `#` operator, spells the argument as a string literal.
+fn stringify(arg: &[ExpansionToken], location: Span) -> ExpansionToken {
+    let mut text = String::new();
+    let mut previous: Option<Span> = None;
+    for token in arg {
+        let current = token.token.location;
+        // tokens keep a space between them when there was whitespace in the source
+        let spaced = previous.is_some_and(|previous| {
+            previous.end != current.start
+                || previous.file != current.file
+                || current.expansion.is_some()
+        });
+        if spaced {
+            text.push(' ');
+        }
+        text.push_str(&token.token.value.to_string());
+        previous = Some(current);
+    }
+
+    let value = str_intern::intern(text);
+    ExpansionToken {
+        token: Locatable::new(location, Token::Literal(Literal::String { value })),
+        hide_set: Vec::new(),
+    }
+}
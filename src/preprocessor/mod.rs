@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use arcstr::ArcStr;
+
+use crate::data::error::CompilerError;
+use crate::data::libc::LibcHeader;
+use crate::data::tokens::{Symbol, Token};
+use crate::lexer::Lexer;
+use crate::preprocessor::macros::Macro;
+use crate::util::source_map::{self, FileId};
+use crate::util::str_intern::InternedStr;
+use crate::util::{Locatable, Span};
+
+mod conditions;
+mod directives;
+mod macros;
+
+/// Includes nested deeper than this are assumed to be including themselves.
+const MAX_INCLUDE_DEPTH: usize = 200;
+
+/// Turns a source file into the tokens the parser sees, following `#include`s, keeping or
+/// skipping the branches of conditional directives and expanding macros.
+pub struct Preprocessor {
+    include_dirs: Vec<PathBuf>,
+    macros: HashMap<InternedStr, Rc<Macro>>,
+    /// Files marked with `#pragma once`.
+    included_once: HashSet<PathBuf>,
+    include_depth: usize,
+    /// The C library headers that were included, their declarations are known without a file.
+    library_headers: Vec<&'static LibcHeader>,
+    errors: Vec<CompilerError>,
+    output: Vec<Locatable<Token>>,
+}
+
+/// A line of source, including the lines joined onto it with a trailing backslash.
+#[derive(Default)]
+struct Line {
+    tokens: Vec<Locatable<Token>>,
+    errors: Vec<CompilerError>,
+}
+
+/// An `#if`, `#ifdef` or `#ifndef` waiting for its `#endif`.
+struct Conditional {
+    directive: String,
+    span: Span,
+    /// Whether the tokens of the current branch are kept.
+    active: bool,
+    /// Whether an earlier branch was kept, or the whole conditional is being skipped.
+    taken: bool,
+    else_span: Option<Span>,
+}
+
+impl Preprocessor {
+    pub fn new(include_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            include_dirs,
+            macros: HashMap::new(),
+            included_once: HashSet::new(),
+            include_depth: 0,
+            library_headers: Vec::new(),
+            errors: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Defines a macro the way `-D NAME` or `-D NAME=value` does.
+    pub fn define(&mut self, definition: &str) {
+        let (name, value) = definition.split_once('=').unwrap_or((definition, "1"));
+        let source: ArcStr = format!("{name} {value}").into();
+        let file = source_map::add_file("<command line>", source.clone());
+        let mut tokens = Vec::new();
+        for line in Self::lex_lines(file, &source) {
+            self.errors.extend(line.errors);
+            tokens.extend(line.tokens);
+        }
+        self.define_macro(tokens, Span::default());
+    }
+
+    pub fn undefine(&mut self, name: &str) {
+        self.macros.remove(name);
+    }
+
+    pub fn preprocess(
+        &mut self,
+        path: &Path,
+        source: ArcStr,
+    ) -> Result<Vec<Locatable<Token>>, Vec<CompilerError>> {
+        let file = source_map::add_file(path.display().to_string(), source.clone());
+        self.process_file(file, path, &source);
+
+        if self.errors.is_empty() {
+            Ok(std::mem::take(&mut self.output))
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    pub fn library_headers(&self) -> &[&'static LibcHeader] {
+        &self.library_headers
+    }
+
+    fn process_file(&mut self, file: FileId, path: &Path, source: &ArcStr) {
+        let mut conditionals: Vec<Conditional> = Vec::new();
+        let mut text = Vec::new();
+
+        for line in Self::lex_lines(file, source) {
+            let is_directive = line
+                .tokens
+                .first()
+                .is_some_and(|token| token.value == Token::Symbol(Symbol::Hash));
+            if is_directive {
+                // the tokens before a directive are expanded with the macros defined at that point
+                let expanded = self.expand(std::mem::take(&mut text));
+                self.output.extend(expanded);
+                self.directive(line, &mut conditionals, path, source);
+            } else if conditionals.iter().all(|conditional| conditional.active) {
+                self.errors.extend(line.errors);
+                text.extend(line.tokens);
+            }
+        }
+
+        let expanded = self.expand(text);
+        self.output.extend(expanded);
+        for conditional in conditionals {
+            self.report_error(CompilerError::UnterminatedConditional(
+                conditional.directive,
+                conditional.span,
+            ));
+        }
+    }
+
+    /// Lexes a file and groups its tokens by line, so directives can be told apart from code.
+    fn lex_lines(file: FileId, source: &ArcStr) -> Vec<Line> {
+        // the first line of the logical line each physical line is part of
+        let physical = source.lines().collect::<Vec<_>>();
+        let mut starts: Vec<usize> = Vec::with_capacity(physical.len());
+        for index in 0..physical.len() {
+            if index > 0 && physical[index - 1].ends_with('\\') {
+                starts.push(starts[index - 1]);
+            } else {
+                starts.push(index + 1);
+            }
+        }
+
+        let mut lines: Vec<(usize, Line)> = Vec::new();
+        for result in Lexer::new(source.clone()).with_file(file) {
+            let line = match &result {
+                Ok(token) => token.location.line,
+                Err(errors) => errors
+                    .iter()
+                    .find_map(|error| error.span())
+                    .map_or(0, |span| span.line),
+            };
+            let start = line
+                .checked_sub(1)
+                .and_then(|index| starts.get(index))
+                .copied()
+                .unwrap_or(line);
+            if lines.last().is_none_or(|(last, _)| *last != start) {
+                lines.push((start, Line::default()));
+            }
+
+            let (_, current) = lines.last_mut().unwrap();
+            match result {
+                Ok(token) => current.tokens.push(token),
+                Err(errors) => current.errors.extend(errors),
+            }
+        }
+        lines.into_iter().map(|(_, line)| line).collect()
+    }
+
+    #[inline(always)]
+    fn report_error(&mut self, error: CompilerError) {
+        self.errors.push(error);
+    }
+
+    /// Reports a missing or unexpected token in a directive.
+    fn report_expected(&mut self, expected: &str, found: Option<&Locatable<Token>>, span: Span) {
+        let (found, span) = match found {
+            Some(token) => (format!("'{}'", token.value), token.location),
+            None => ("the end of the line".to_string(), span),
+        };
+        self.report_error(CompilerError::ExpectedButFound(
+            expected.to_string(),
+            found,
+            span,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::data::tokens::{Literal, Symbol, Token};
+    use crate::util::source_map;
+    use crate::util::str_intern::intern;
+
+    use super::Preprocessor;
+
+    fn preprocess(mut preprocessor: Preprocessor, source: &str) -> Vec<Token> {
+        preprocessor
+            .preprocess(Path::new("test.c"), source.into())
+            .unwrap()
+            .into_iter()
+            .map(|token| token.value)
+            .collect()
+    }
+
+    fn integer(value: isize) -> Token {
+        Token::Literal(Literal::Integer {
+            value,
+            suffix: None,
+        })
+    }
+
+    #[test]
+    fn expands_object_and_function_like_macros() {
+        let source = "#define TWO 2\n#define ADD(a, b) a + b\nADD(TWO, 3) ADD";
+        let tokens = preprocess(Preprocessor::new(Vec::new()), source);
+        assert_eq!(
+            tokens,
+            vec![
+                integer(2),
+                Token::Symbol(Symbol::Plus),
+                integer(3),
+                Token::Identifier(intern("ADD")),
+            ]
+        );
+    }
+
+    #[test]
+    fn stringifies_and_pastes_arguments() {
+        let source = "#define STR(x) #x\n#define CAT(a, b) a ## b\nSTR(a  +\"b\") CAT(x, 1)";
+        let tokens = preprocess(Preprocessor::new(Vec::new()), source);
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Literal(Literal::String {
+                    value: intern("a +\"b\""),
+                }),
+                Token::Identifier(intern("x1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_line_definitions_select_branches() {
+        let source = "#if defined(DEBUG) && LEVEL > 3\n1\n#elif LEVEL\n2\n#else\n3\n#endif";
+        let mut preprocessor = Preprocessor::new(Vec::new());
+        preprocessor.define("DEBUG");
+        preprocessor.define("LEVEL=4");
+        assert_eq!(preprocess(preprocessor, source), vec![integer(1)]);
+
+        let mut preprocessor = Preprocessor::new(Vec::new());
+        preprocessor.define("LEVEL=4");
+        assert_eq!(preprocess(preprocessor, source), vec![integer(2)]);
+
+        let mut preprocessor = Preprocessor::new(Vec::new());
+        preprocessor.define("LEVEL=4");
+        preprocessor.undefine("LEVEL");
+        assert_eq!(preprocess(preprocessor, source), vec![integer(3)]);
+    }
+
+    #[test]
+    fn expanded_tokens_point_at_use_site_and_definition() {
+        let tokens = Preprocessor::new(Vec::new())
+            .preprocess(Path::new("test.c"), "#define ONE 1\nint x = ONE;".into())
+            .unwrap();
+        let one = &tokens[3];
+        assert_eq!(one.value, integer(1));
+        assert_eq!((one.location.line, one.location.col), (2, 9));
+
+        let chain = source_map::expansion_chain(one.location);
+        assert_eq!(chain.len(), 1);
+        assert_eq!(&*chain[0].name, "ONE");
+        assert_eq!((chain[0].definition.line, chain[0].definition.col), (1, 13));
+    }
+
+    #[test]
+    fn library_headers_are_recorded_once() {
+        let source = "#include <math.h>\n#include <stdio.h>\n#include <math.h>\nsqrt";
+        let mut preprocessor = Preprocessor::new(Vec::new());
+        let tokens = preprocessor
+            .preprocess(Path::new("test.c"), source.into())
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        let headers = preprocessor
+            .library_headers()
+            .iter()
+            .map(|header| header.name)
+            .collect::<Vec<_>>();
+        assert_eq!(headers, vec!["math.h", "stdio.h"]);
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use inkwell::targets::{
+    CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple as LlvmTriple,
+};
+use inkwell::OptimizationLevel;
+
+/// The sdk path used when linking against libSystem on macOS.
+static MACOS_SDK_LIB_PATH: &str = "/Library/Developer/CommandLineTools/SDKs/MacOSX.sdk/usr/lib";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Arch {
+    X86_64,
+    Aarch64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Os {
+    MacOs,
+    Linux,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Env {
+    Gnu,
+    Musl,
+    None,
+}
+
+/// How the target represents a `va_list`, which decides how the stdarg builtins are lowered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VaListKind {
+    /// x86_64, an array of one
+    /// `{ i32 gp_offset, i32 fp_offset, ptr overflow_arg_area, ptr reg_save_area }`.
+    X86_64,
+    /// The AArch64 procedure call standard,
+    /// `{ ptr stack, ptr gr_top, ptr vr_top, i32 gr_offs, i32 vr_offs }`.
+    Aapcs64,
+    /// Apple arm64 passes variadic arguments on the stack, so the list points to the next one.
+    CharPointer,
+}
+
+/// A parsed `<arch>-<vendor>-<os>[-<env>]` target triple.
+/// Only the targets that the compiler knows how to link for are representable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TargetTriple {
+    pub(crate) arch: Arch,
+    pub(crate) os: Os,
+    pub(crate) env: Env,
+    triple: String,
+}
+
+impl TargetTriple {
+    /// The host triple as LLVM reports it, so that version suffixes such as
+    /// `darwin23.4.0` are preserved. Falls back to the build configuration.
+    pub(crate) fn host() -> Self {
+        let default_triple = TargetMachine::get_default_triple();
+        if let Ok(triple) = Self::parse(default_triple.as_str().to_str().unwrap()) {
+            return triple;
+        }
+
+        let arch = if cfg!(target_arch = "aarch64") {
+            Arch::Aarch64
+        } else {
+            Arch::X86_64
+        };
+        let os = if cfg!(target_os = "macos") {
+            Os::MacOs
+        } else {
+            Os::Linux
+        };
+        let env = match os {
+            Os::MacOs => Env::None,
+            Os::Linux if cfg!(target_env = "musl") => Env::Musl,
+            Os::Linux => Env::Gnu,
+        };
+        Self::from_parts(arch, os, env)
+    }
+
+    pub(crate) fn parse(triple: &str) -> Result<Self, String> {
+        let unsupported = || format!("Unsupported target triple '{triple}'.");
+        let mut parts = triple.split('-');
+
+        let arch = match parts.next() {
+            Some("x86_64") => Arch::X86_64,
+            Some("aarch64" | "arm64") => Arch::Aarch64,
+            _ => return Err(unsupported()),
+        };
+
+        let mut os = None;
+        let mut env = Env::None;
+        for part in parts {
+            match part {
+                "apple" | "unknown" | "pc" => (),
+                _ if part.starts_with("darwin") || part.starts_with("macos") => {
+                    os = Some(Os::MacOs)
+                }
+                "linux" => os = Some(Os::Linux),
+                "gnu" => env = Env::Gnu,
+                "musl" => env = Env::Musl,
+                _ => return Err(unsupported()),
+            }
+        }
+
+        let os = os.ok_or_else(unsupported)?;
+        if os == Os::Linux && env == Env::None {
+            env = Env::Gnu;
+        }
+        if os == Os::MacOs && env != Env::None {
+            return Err(unsupported());
+        }
+
+        Ok(Self {
+            arch,
+            os,
+            env,
+            triple: triple.to_string(),
+        })
+    }
+
+    fn from_parts(arch: Arch, os: Os, env: Env) -> Self {
+        let arch_str = match (arch, os) {
+            (Arch::X86_64, _) => "x86_64",
+            (Arch::Aarch64, Os::MacOs) => "arm64",
+            (Arch::Aarch64, Os::Linux) => "aarch64",
+        };
+        let triple = match (os, env) {
+            (Os::MacOs, _) => format!("{arch_str}-apple-darwin"),
+            (Os::Linux, Env::Musl) => format!("{arch_str}-unknown-linux-musl"),
+            (Os::Linux, _) => format!("{arch_str}-unknown-linux-gnu"),
+        };
+        Self {
+            arch,
+            os,
+            env,
+            triple,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.triple
+    }
+
+    pub(crate) fn va_list_kind(&self) -> VaListKind {
+        match (self.arch, self.os) {
+            (Arch::X86_64, _) => VaListKind::X86_64,
+            (Arch::Aarch64, Os::Linux) => VaListKind::Aapcs64,
+            (Arch::Aarch64, Os::MacOs) => VaListKind::CharPointer,
+        }
+    }
+
+    pub(crate) fn create_target_machine(
+        &self,
+        cpu: &str,
+        features: &str,
+        opt_level: OptimizationLevel,
+    ) -> Result<TargetMachine, String> {
+        let config = InitializationConfig::default();
+        match self.arch {
+            Arch::X86_64 => Target::initialize_x86(&config),
+            Arch::Aarch64 => Target::initialize_aarch64(&config),
+        }
+
+        let triple = LlvmTriple::create(&self.triple);
+        let target = Target::from_triple(&triple).map_err(|err| err.to_string())?;
+        target
+            .create_target_machine(
+                &triple,
+                cpu,
+                features,
+                opt_level,
+                RelocMode::Default,
+                CodeModel::Default,
+            )
+            .ok_or_else(|| format!("Could not create a target machine for '{self}' (cpu: '{cpu}')."))
+    }
+
+    /// Builds the `ld` invocation that links the object files and the C library, along with
+    /// any other libraries given by name, into an executable.
+    pub(crate) fn link_command(
+        &self,
+        objects: &[String],
+        libraries: &[String],
+        output: &str,
+    ) -> Result<Command, String> {
+        let libraries = libraries.iter().map(|library| format!("-l{library}"));
+        let mut command = Command::new("ld");
+        command.args(["-o", output]);
+
+        match self.os {
+            Os::MacOs => {
+                let arch = match self.arch {
+                    Arch::X86_64 => "x86_64",
+                    Arch::Aarch64 => "arm64",
+                };
+                command.args(objects).args([
+                    "-e",
+                    "_main",
+                    "-arch",
+                    arch,
+                    &format!("-L{MACOS_SDK_LIB_PATH}"),
+                    "-lSystem",
+                ]);
+                command.args(libraries);
+            }
+            Os::Linux => {
+                // ELF executables enter through `_start` in crt1.o, which calls
+                // `__libc_start_main` with main, argc and argv.
+                let crt_dir = self.find_crt_dir()?;
+                let crt = |name: &str| crt_dir.join(name).to_str().unwrap().to_string();
+                command
+                    .args(["-dynamic-linker", self.dynamic_loader()])
+                    .args([crt("crt1.o"), crt("crti.o")])
+                    .args(objects)
+                    .arg(format!("-L{}", crt_dir.to_str().unwrap()))
+                    .args(libraries)
+                    .arg("-lc")
+                    .arg(crt("crtn.o"));
+            }
+        }
+
+        Ok(command)
+    }
+
+    fn dynamic_loader(&self) -> &'static str {
+        match (self.arch, self.env) {
+            (Arch::X86_64, Env::Musl) => "/lib/ld-musl-x86_64.so.1",
+            (Arch::Aarch64, Env::Musl) => "/lib/ld-musl-aarch64.so.1",
+            (Arch::X86_64, _) => "/lib64/ld-linux-x86-64.so.2",
+            (Arch::Aarch64, _) => "/lib/ld-linux-aarch64.so.1",
+        }
+    }
+
+    fn crt_search_paths(&self) -> Vec<PathBuf> {
+        let arch = match self.arch {
+            Arch::X86_64 => "x86_64",
+            Arch::Aarch64 => "aarch64",
+        };
+        let paths = match self.env {
+            Env::Musl => vec![
+                format!("/usr/lib/{arch}-linux-musl"),
+                "/usr/lib/musl/lib".to_string(),
+                "/usr/local/musl/lib".to_string(),
+                "/lib".to_string(),
+                "/usr/lib".to_string(),
+            ],
+            _ => vec![
+                format!("/usr/lib/{arch}-linux-gnu"),
+                "/usr/lib64".to_string(),
+                "/lib64".to_string(),
+                "/usr/lib".to_string(),
+            ],
+        };
+        paths.into_iter().map(PathBuf::from).collect()
+    }
+
+    fn find_crt_dir(&self) -> Result<PathBuf, String> {
+        self.crt_search_paths()
+            .into_iter()
+            .find(|dir| Path::new(dir).join("crt1.o").exists())
+            .ok_or_else(|| format!("Could not find the C runtime objects for '{self}'."))
+    }
+}
+
+impl Display for TargetTriple {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.triple)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::target::{Arch, Env, Os, TargetTriple};
+
+    #[test]
+    fn test_parse_linux_triples() {
+        let gnu = TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!((gnu.arch, gnu.os, gnu.env), (Arch::X86_64, Os::Linux, Env::Gnu));
+
+        let musl = TargetTriple::parse("aarch64-linux-musl").unwrap();
+        assert_eq!(
+            (musl.arch, musl.os, musl.env),
+            (Arch::Aarch64, Os::Linux, Env::Musl)
+        );
+    }
+
+    #[test]
+    fn test_parse_darwin_triple() {
+        let darwin = TargetTriple::parse("arm64-apple-darwin23.4.0").unwrap();
+        assert_eq!(
+            (darwin.arch, darwin.os, darwin.env),
+            (Arch::Aarch64, Os::MacOs, Env::None)
+        );
+        assert_eq!(darwin.as_str(), "arm64-apple-darwin23.4.0");
+    }
+
+    #[test]
+    fn test_parse_unsupported_triples() {
+        assert!(TargetTriple::parse("riscv64-unknown-linux-gnu").is_err());
+        assert!(TargetTriple::parse("x86_64-pc-windows-msvc").is_err());
+        assert!(TargetTriple::parse("x86_64-apple-darwin-musl").is_err());
+    }
+}
@@ -43,8 +43,18 @@ impl Expression {
                 let right = right.pretty_print(padding.clone(), true, false);
                 format!("{} <post> \n{}", op, right)
             }
-            Expression::FunctionCall(ident, args) => {
-                let mut output = format!("<fn-call> {}{}\n", **ident, "(");
+            Expression::Ternary(condition, then, otherwise) => {
+                let condition = condition.pretty_print(padding.clone(), false, false);
+                let then = then.pretty_print(padding.clone(), false, false);
+                let otherwise = otherwise.pretty_print(padding.clone(), true, false);
+                format!("?:\n{}{}{}", condition, then, otherwise)
+            }
+            Expression::FunctionCall(callee, args) => {
+                let callee = match callee.value.as_ref() {
+                    Expression::Variable(ident) => ident.to_string(),
+                    callee => format!("({})", callee.pretty_print(padding.clone(), true, true)),
+                };
+                let mut output = format!("<fn-call> {}{}\n", callee, "(");
                 for (i, arg) in args.iter().enumerate() {
                     let arg = &arg.pretty_print(padding.clone(), i == args.len() - 1, false);
                     let arg = display_utils::indent_string(arg.to_string(), 0, 4);
@@ -79,18 +89,58 @@ impl Expression {
             }
             Expression::ArrayInitializer(initializer) => {
                 let mut output = "{\n".to_string();
-                for (i, expr) in initializer.iter().enumerate() {
-                    output +=
-                        &expr.pretty_print(padding.clone(), i == initializer.len() - 1, false);
+                for (i, element) in initializer.iter().enumerate() {
+                    let last = i == initializer.len() - 1;
+                    output += &match &element.designator {
+                        None => element.value.pretty_print(padding.clone(), last, false),
+                        Some(designator) => {
+                            designator.pretty_print(&element.value, padding.clone(), last)
+                        }
+                    };
                 }
                 output += &"}";
                 output
             }
+            Expression::VaStart(va_list, last) => {
+                let va_list = va_list.pretty_print(padding.clone(), false, false);
+                format!("va_start\n{}{}└─ {}\n", va_list, padding, **last)
+            }
+            Expression::VaArg(va_list, ty) => {
+                let va_list = va_list.pretty_print(padding.clone(), false, false);
+                format!("va_arg\n{}{}└─ {}\n", va_list, padding, ty)
+            }
+            Expression::VaEnd(va_list) => {
+                let va_list = va_list.pretty_print(padding.clone(), true, false);
+                format!("va_end\n{}", va_list)
+            }
+            Expression::VaCopy(dest, src) => {
+                let dest = dest.pretty_print(padding.clone(), false, false);
+                let src = src.pretty_print(padding.clone(), true, false);
+                format!("va_copy\n{}{}", dest, src)
+            }
         };
         output + &child_output
     }
 }
 
+impl Designator {
+    fn pretty_print(&self, value: &Expression, padding: String, last: bool) -> String {
+        let output = format!("{}{}", padding, if last { "└─ " } else { "├─ " });
+        let padding = padding + if last { "   " } else { "│  " };
+        match self {
+            Designator::Member(member) => {
+                let value = value.pretty_print(padding, true, false);
+                format!("{}.{} =\n{}", output, **member, value)
+            }
+            Designator::Index(index) => {
+                let index = index.pretty_print(padding.clone(), false, false);
+                let value = value.pretty_print(padding, true, false);
+                format!("{}[] =\n{}{}", output, index, value)
+            }
+        }
+    }
+}
+
 impl Display for Expression {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let output = self.pretty_print("".to_string(), true, true);
@@ -138,6 +188,12 @@ impl Display for Statement {
                 display_utils::indent_string(format!("{}", cond), 0, 4),
                 display_utils::indent_string(format!("{}", body), 0, 4)
             ),
+            DoWhile(body, cond) => write!(
+                f,
+                "do <body> {{\n{}}} while <cond> (\n{});",
+                display_utils::indent_string(format!("{}", body), 0, 4),
+                display_utils::indent_string(format!("{}", cond), 0, 4)
+            ),
             For(init, cond, post, body) => write!(
                 f,
                 "for <init> (\n{}), <cond> (\n{}), <post> (\n{}) <body> {{\n{}\n}}",
@@ -158,6 +214,20 @@ impl Display for Statement {
                 },
                 display_utils::indent_string(format!("{}", body), 0, 4)
             ),
+            Switch(controlling, body) => write!(
+                f,
+                "switch <expr> (\n{}) <body> {{\n{}}}",
+                display_utils::indent_string(format!("{}", controlling), 0, 4),
+                display_utils::indent_string(format!("{}", body), 0, 4)
+            ),
+            Case(value) => write!(
+                f,
+                "case <expr> (\n{}):",
+                display_utils::indent_string(format!("{}", value), 0, 4)
+            ),
+            Default => write!(f, "default:"),
+            Label(ident) => write!(f, "{}:", ident),
+            Goto(ident) => write!(f, "goto {};", ident),
             Block(block) => write!(f, "<block> {}", block),
             Break => write!(f, "break;"),
             Continue => write!(f, "continue;"),
@@ -201,6 +271,9 @@ impl Display for VariableDeclaration {
             }
             write!(f, "]")?;
         }
+        for size in &self.inner_array_sizes {
+            write!(f, "[{}]", size)?;
+        }
         if let Some(expr) = &self.initializer {
             write!(
                 f,
@@ -231,8 +304,14 @@ impl Display for FunctionDeclaration {
                 write!(f, ", ")?;
             }
         }
+        if self.varargs {
+            write!(f, ", ...")?;
+        }
         write!(f, ")")?;
-        write!(f, " {}", self.body)
+        match &self.body {
+            Some(body) => write!(f, " {}", body),
+            None => write!(f, ";"),
+        }
     }
 }
 
@@ -262,12 +341,19 @@ impl Display for TypeSpecifier {
         match self {
             TypeSpecifier::Void => write!(f, "void"),
             TypeSpecifier::Char => write!(f, "char"),
+            TypeSpecifier::Short => write!(f, "short"),
             TypeSpecifier::Int => write!(f, "int"),
             TypeSpecifier::Long => write!(f, "long"),
+            TypeSpecifier::Float => write!(f, "float"),
             TypeSpecifier::Double => write!(f, "double"),
             TypeSpecifier::Signed => write!(f, "signed"),
             TypeSpecifier::Unsigned => write!(f, "unsigned"),
+            TypeSpecifier::VaList => write!(f, "va_list"),
             TypeSpecifier::Struct(ident) => write!(f, "struct {}", ident),
+            TypeSpecifier::Union(ident) => write!(f, "union {}", ident),
+            TypeSpecifier::Enum(Some(ident), _) => write!(f, "enum {}", ident),
+            TypeSpecifier::Enum(None, _) => write!(f, "enum"),
+            TypeSpecifier::TypedefName(ident) => write!(f, "{}", ident),
         }
     }
 }
@@ -276,6 +362,8 @@ impl Display for StorageSpecifier {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let str = match self {
             StorageSpecifier::Static => "static",
+            StorageSpecifier::Extern => "extern",
+            StorageSpecifier::Typedef => "typedef",
         }
         .to_string();
         write!(f, "{}", str)
@@ -306,6 +394,37 @@ impl Display for DeclarationSpecifier {
         write_field!(self.specifiers);
         write_field!(self.qualifiers);
         write_field!(self.ty);
+        for _ in 0..self.pointer_depth {
+            write!(f, "*")?;
+        }
+        if let Some(declarator) = &self.function_pointer {
+            write!(f, " ({})(", "*".repeat(declarator.pointer_depth as usize))?;
+            for (i, param) in declarator.parameters.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", param.specifier)?;
+            }
+            if declarator.varargs {
+                write!(f, ", ...")?;
+            }
+            write!(f, ")")?;
+        }
+        if let Some(declarator) = &self.array_pointer {
+            write!(f, " ({})", "*".repeat(declarator.pointer_depth as usize))?;
+            for size in &declarator.array_sizes {
+                write!(f, "[{}]", size)?;
+            }
+        }
+        if let Some(declarator) = &self.parameter_array {
+            match declarator.array_size {
+                Some(size) => write!(f, "[{}]", size)?,
+                None => write!(f, "[]")?,
+            }
+            for size in &declarator.inner_array_sizes {
+                write!(f, "[{}]", size)?;
+            }
+        }
         Ok(())
     }
 }
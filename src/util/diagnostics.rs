@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+use crate::util::source_map;
+use crate::util::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A message about the source, rendered with the lines it points at.
+///
+/// ```text
+/// error[E0027]: This identifier already exists in this scope and cannot be redeclared
+///  --> test.c:3:9
+///   |
+/// 2 |     int x = 1;
+///   |         - previously declared here
+/// 3 |     int x = 2;
+///   |         ^
+/// ```
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+    pub span: Option<Span>,
+    pub labels: Vec<(Span, String)>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: String, span: Option<Span>) -> Self {
+        Self {
+            severity,
+            code: None,
+            message,
+            span,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// A secondary location, only shown when the diagnostic has a primary span.
+    pub fn with_label(mut self, span: Option<Span>, label: &str) -> Self {
+        if let Some(span) = span {
+            self.labels.push((span, label.to_string()));
+        }
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut output = String::new();
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &self.code {
+            Some(code) => write!(output, "{severity}[{code}]: {}", self.message),
+            None => write!(output, "{severity}: {}", self.message),
+        }
+        .unwrap();
+
+        let mut gutter = 0;
+        if let Some(span) = self.span {
+            let mut labels = vec![(span, '^', String::new())];
+            labels.extend(
+                self.labels
+                    .iter()
+                    .map(|(span, label)| (*span, '-', label.clone())),
+            );
+            labels.extend(
+                source_map::expansion_chain(span)
+                    .into_iter()
+                    .map(|expansion| {
+                        let label = format!("expanded from macro '{}'", expansion.name);
+                        (expansion.definition, '-', label)
+                    }),
+            );
+
+            gutter = labels
+                .iter()
+                .map(|(span, _, _)| digits(span.line))
+                .max()
+                .unwrap();
+            let padding = " ".repeat(gutter);
+
+            // the primary span's file is shown first, then any other file a label points into
+            let mut files = vec![span.file];
+            for (label_span, _, _) in &labels {
+                if !files.contains(&label_span.file) {
+                    files.push(label_span.file);
+                }
+            }
+
+            for (index, file) in files.into_iter().enumerate() {
+                let mut file_labels = labels
+                    .iter()
+                    .filter(|(label_span, _, _)| label_span.file == file)
+                    .collect::<Vec<_>>();
+                let (location, arrow) = if index == 0 {
+                    (span, "-->")
+                } else {
+                    (file_labels[0].0, ":::")
+                };
+                file_labels.sort_by_key(|(span, _, _)| (span.line, span.col));
+
+                let loaded = source_map::file(file);
+                let (name, text) = loaded.as_ref().map_or((file_name, source), |loaded| {
+                    (loaded.name.as_str(), loaded.source.as_str())
+                });
+
+                write!(
+                    output,
+                    "\n{padding}{arrow} {name}:{}:{}",
+                    location.line, location.col
+                )
+                .unwrap();
+                write!(output, "\n{padding} |").unwrap();
+                let mut last_line = None;
+                for (span, marker, label) in file_labels {
+                    let Some(line) = text.lines().nth(span.line.saturating_sub(1)) else {
+                        continue;
+                    };
+                    if last_line != Some(span.line) {
+                        write!(output, "\n{:>gutter$} | {}", span.line, line.trim_end()).unwrap();
+                    }
+                    last_line = Some(span.line);
+                    let underline = underline(line, *span, *marker);
+                    write!(output, "\n{padding} | {underline}").unwrap();
+                    if !label.is_empty() {
+                        write!(output, " {label}").unwrap();
+                    }
+                }
+            }
+        } else if !file_name.is_empty() {
+            write!(output, "\n--> {file_name}").unwrap();
+        }
+
+        let padding = " ".repeat(gutter);
+        for note in &self.notes {
+            write!(output, "\n{padding} = note: {note}").unwrap();
+        }
+        if let Some(help) = &self.help {
+            write!(output, "\n{padding} = help: {help}").unwrap();
+        }
+        output
+    }
+}
+
+fn digits(number: usize) -> usize {
+    number.to_string().len()
+}
+
+/// Marks the span on its first line, keeping tabs so the marker lines up with the source.
+fn underline(line: &str, span: Span, marker: char) -> String {
+    let start = span.col.saturating_sub(1);
+    let line_length = line.trim_end().chars().count();
+    let length = span
+        .end
+        .saturating_sub(span.start)
+        .min(line_length.saturating_sub(start))
+        .max(1);
+    let indent = line
+        .chars()
+        .take(start)
+        .map(|char| if char == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    format!("{indent}{}", marker.to_string().repeat(length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_snippet_with_labels() {
+        let source = "int main() {\n    int x = 1;\n    int x = 2;\n}\n";
+        let diagnostic = Diagnostic::new(
+            Severity::Error,
+            "Redeclared".to_string(),
+            Some(Span::new(35, 36, 9, 3)),
+        )
+        .with_code("E0027")
+        .with_label(Some(Span::new(21, 22, 9, 2)), "previously declared here")
+        .with_help("rename one of the variables");
+
+        let expected = "error[E0027]: Redeclared
+ --> test.c:3:9
+  |
+2 |     int x = 1;
+  |         - previously declared here
+3 |     int x = 2;
+  |         ^
+  = help: rename one of the variables";
+        assert_eq!(diagnostic.render("test.c", source), expected);
+    }
+
+    #[test]
+    fn span_displays_line_and_column() {
+        let span = Span::new(35, 36, 9, 3);
+        assert_eq!(span.to_string(), "3:9");
+        assert_eq!(format!("{span:?}"), "3:9");
+    }
+
+    #[test]
+    fn renders_without_span() {
+        let diagnostic =
+            Diagnostic::new(Severity::Warning, "Careful".to_string(), None).with_note("a note");
+        assert_eq!(
+            diagnostic.render("test.c", ""),
+            "warning: Careful\n--> test.c\n = note: a note"
+        );
+    }
+
+    #[test]
+    fn renders_macro_definition_from_other_file() {
+        let header = source_map::add_file("macros.h", "#define ONE 1\n".into());
+        let definition = Span {
+            file: header,
+            ..Span::new(12, 13, 13, 1)
+        };
+        let expansion = source_map::add_expansion(source_map::Expansion {
+            name: "ONE".into(),
+            definition,
+            parent: None,
+        });
+        let span = Span {
+            expansion: Some(expansion),
+            ..Span::new(8, 11, 9, 1)
+        };
+        let diagnostic = Diagnostic::new(Severity::Error, "Oops".to_string(), Some(span));
+
+        let expected = "error: Oops
+ --> test.c:1:9
+  |
+1 | int x = ONE;
+  |         ^^^
+ ::: macros.h:1:13
+  |
+1 | #define ONE 1
+  |             - expanded from macro 'ONE'";
+        assert_eq!(diagnostic.render("test.c", "int x = ONE;\n"), expected);
+    }
+}
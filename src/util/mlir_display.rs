@@ -18,6 +18,13 @@ impl Display for MlirStmt {
                     condition, then, _else
                 )
             }
+            MlirStmt::Switch(value, cases, default) => {
+                write!(f, "switch | value: {};", value)?;
+                for (case, label) in cases {
+                    write!(f, " case {}: {};", case, label)?;
+                }
+                write!(f, " default: {};", default)
+            }
             MlirStmt::Return(value) => {
                 write!(f, "return")?;
                 if let Some(value) = value.as_ref() {
@@ -44,11 +51,17 @@ impl Display for MlirBlock {
 
 impl Display for MlirFunction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_static {
+            write!(f, "static ")?;
+        }
         write!(f, "{} {}(", self.ty, self.ident)?;
 
         for param in &self.parameters {
             write!(f, " {},", &param.value)?;
         }
+        if self.varargs {
+            write!(f, " ...")?;
+        }
         writeln!(f, ") {{")?;
         writeln!(f, "{}", self.body);
         write!(f, "}}")
@@ -61,10 +74,17 @@ impl Display for MlirVarInit {
             MlirVarInit::Expr(expression) => write!(f, "{}", expression),
             MlirVarInit::Array(elements) => {
                 write!(f, "{{ ");
-                for element in elements {
-                    write!(f, "{}", element);
+                for (index, element) in elements {
+                    write!(f, "[{}] = {}, ", index, element);
                 }
-                write!(f, " }}")
+                write!(f, "}}")
+            }
+            MlirVarInit::Struct(members) => {
+                write!(f, "{{ ");
+                for (member, element) in members {
+                    write!(f, ".{} = {}, ", member, element);
+                }
+                write!(f, "}}")
             }
         }
     }
@@ -142,6 +162,9 @@ impl Display for MlirExpr {
             MlirExprKind::LeftShift(left, right) => write!(f, "({} << {})", left, right),
             MlirExprKind::RightShift(left, right) => write!(f, "({} >> {})", left, right),
             MlirExprKind::Assign(left, right) => write!(f, "({} = {})", left, right),
+            MlirExprKind::Ternary(condition, then, otherwise) => {
+                write!(f, "({} ? {} : {})", condition, then, otherwise)
+            }
             MlirExprKind::FunctionCall {
                 location,
                 ident,
@@ -154,17 +177,26 @@ impl Display for MlirExpr {
                 }
                 write!(f, "")
             }
+            MlirExprKind::Function(ident) => write!(f, "<fn: {}>", ident),
+            MlirExprKind::IndirectCall(callee, _) => write!(f, "<indirect-fn-call: {}>", callee),
             MlirExprKind::Index(left, right) => write!(f, "(({})[{}])", left, right),
             MlirExprKind::Member(left, right) => write!(f, "{}.{}", left, right),
             MlirExprKind::Cast(cast_to, cast_type, expression) => {
                 write!(f, "(<cast-{}>({cast_to}, {}))", cast_type, expression)
             }
+            MlirExprKind::VaStart(va_list) => write!(f, "<va-start: {}>", va_list),
+            MlirExprKind::VaArg(va_list) => write!(f, "<va-arg-{}: {}>", self.ty, va_list),
+            MlirExprKind::VaEnd(va_list) => write!(f, "<va-end: {}>", va_list),
+            MlirExprKind::VaCopy(dest, src) => write!(f, "<va-copy: {} <- {}>", dest, src),
         }
     }
 }
 
 impl Display for MlirVariable {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_static {
+            write!(f, "static ")?;
+        }
         if self.is_const {
             write!(f, "const ")?;
         }
@@ -181,7 +213,8 @@ impl Display for MlirVariable {
 
 impl Display for MlirStruct {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "struct {} <{} bytes> {{", self.ident.value, self.size)?;
+        let keyword = if self.is_union { "union" } else { "struct" };
+        writeln!(f, "{keyword} {} <{} bytes> {{", self.ident.value, self.size)?;
         for field in &self.members {
             writeln!(f, "{};", field.value)?;
         }
@@ -207,6 +240,22 @@ impl Display for MlirModule {
             indent_write!(var);
         }
 
+        write!(f, "}}\n External Global Variables {{ ")?;
+        for var in self.external_globals.iter() {
+            indent_write!(var);
+        }
+
+        write!(f, "}}\n External Functions {{")?;
+        for func in self.external_functions.iter() {
+            let params = func
+                .params
+                .iter()
+                .map(|param| param.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            indent_write!(format!("{} {}({});", func.return_ty, func.ident, params));
+        }
+
         write!(f, "}}\n Functions {{")?;
         for func in self.functions.iter() {
             indent_write!(func);
@@ -232,11 +281,27 @@ impl Display for MlirTypeKind {
         match self {
             Void => write!(f, "void"),
             Char(unsigned) => write_signed!("char", *unsigned),
+            Short(unsigned) => write_signed!("short", *unsigned),
             Int(unsigned) => write_signed!("int", *unsigned),
             Long(unsigned) => write_signed!("long", *unsigned),
             Float => write!(f, "float"),
             Double => write!(f, "double"),
             Struct(ident) => write!(f, "struct {}", ident),
+            FunctionPointer(signature) => {
+                write!(f, "{} (*)(", signature.return_ty)?;
+                for (i, param) in signature.params.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                if signature.varargs {
+                    write!(f, ", ...")?;
+                }
+                write!(f, ")")
+            }
+            Array(element) => write!(f, "{}", element),
+            VaList => write!(f, "va_list"),
         }
     }
 }
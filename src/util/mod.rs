@@ -6,10 +6,13 @@ use derive_new::new;
 
 use crate::data::error::CompilerError;
 use crate::data::tokens::Token as LexToken;
+use crate::util::source_map::{ExpansionId, FileId};
 
 pub mod ast_pretty_print;
+pub mod diagnostics;
 pub mod display_utils;
 pub mod mlir_display;
+pub mod source_map;
 pub mod str_intern;
 
 pub type LocatableToken = Locatable<LexToken>;
@@ -70,6 +73,10 @@ pub struct Span {
     pub end: usize,
     pub col: usize,
     pub line: usize,
+    /// The file the span is in, `0` when it is in the source handed to the renderer.
+    pub file: FileId,
+    /// The macro expansion this token came out of, the span itself is the use site.
+    pub expansion: Option<ExpansionId>,
 }
 
 impl Span {
@@ -81,6 +88,8 @@ impl Span {
             end,
             col,
             line,
+            file: 0,
+            expansion: None,
         }
     }
 
@@ -104,11 +113,11 @@ impl Span {
 
 impl Display for Span {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{}:{}:{}:{}", self.line, self.col, self.start, self.end)
+        write!(f, "{}:{}", self.line, self.col)
     }
 }
 impl Debug for Span {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{}:{}:{}:{}", self.line, self.col, self.start, self.end)
+        write!(f, "{}:{}", self.line, self.col)
     }
 }
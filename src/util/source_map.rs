@@ -0,0 +1,79 @@
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+
+use arcstr::ArcStr;
+use lazy_static::lazy_static;
+
+use crate::util::str_intern::InternedStr;
+use crate::util::Span;
+
+// Every file read by the preprocessor and every macro expansion it performs is recorded here,
+// so a span only has to carry two small ids and diagnostics can still show where it came from.
+
+// both are kept small since every span carries them
+pub type FileId = u32;
+pub type ExpansionId = NonZeroU32;
+
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub name: String,
+    pub source: ArcStr,
+}
+
+/// One token produced by expanding a macro.
+#[derive(Debug, Clone)]
+pub struct Expansion {
+    pub name: InternedStr,
+    /// The token in the macro definition that was copied.
+    pub definition: Span,
+    /// The expansion the macro was used in, when the use site came from another macro.
+    pub parent: Option<ExpansionId>,
+}
+
+#[derive(Default)]
+struct SourceMap {
+    files: Vec<SourceFile>,
+    expansions: Vec<Expansion>,
+}
+
+lazy_static! {
+    static ref SOURCE_MAP: Mutex<SourceMap> = Mutex::new(SourceMap::default());
+}
+
+/// Ids start at 1, `0` is left for the source the diagnostics are rendered with.
+pub fn add_file(name: impl Into<String>, source: ArcStr) -> FileId {
+    let mut map = SOURCE_MAP.lock().unwrap();
+    map.files.push(SourceFile {
+        name: name.into(),
+        source,
+    });
+    map.files.len() as FileId
+}
+
+pub fn file(id: FileId) -> Option<SourceFile> {
+    let map = SOURCE_MAP.lock().unwrap();
+    let index = (id as usize).checked_sub(1)?;
+    map.files.get(index).cloned()
+}
+
+pub fn add_expansion(expansion: Expansion) -> ExpansionId {
+    let mut map = SOURCE_MAP.lock().unwrap();
+    map.expansions.push(expansion);
+    ExpansionId::new(map.expansions.len() as u32).unwrap()
+}
+
+pub fn expansion(id: ExpansionId) -> Option<Expansion> {
+    let map = SOURCE_MAP.lock().unwrap();
+    map.expansions.get(id.get() as usize - 1).cloned()
+}
+
+/// The definitions a span was expanded from, innermost macro first.
+pub fn expansion_chain(span: Span) -> Vec<Expansion> {
+    let mut chain = Vec::new();
+    let mut current = span.expansion;
+    while let Some(expansion) = current.and_then(expansion) {
+        current = expansion.parent;
+        chain.push(expansion);
+    }
+    chain
+}
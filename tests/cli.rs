@@ -0,0 +1,189 @@
+//! Tests that run the compiler binary, for the options that only exist on the command line.
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+static OUTPUT_TESTS: &str = "_c_test_files/output_tests";
+
+/// A directory of its own for every test, the compiled programs are written to it. It is removed
+/// again when the test is done.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(test_name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("microc-{}-{test_name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn microc(args: &[&str], output: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_microc"))
+        .args(args)
+        .arg("-o")
+        .arg(output)
+        .output()
+        .expect("Could not run microc.")
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn optimized_output() {
+    let dir = TempDir::new("optimized_output");
+    for name in [
+        "fizz_buzz",
+        "function_pointers",
+        "integer_types",
+        "float",
+        "struct_initializers",
+        "union",
+        "varargs",
+    ] {
+        let src_filepath = format!("{OUTPUT_TESTS}/{name}.c");
+        let program = dir.join(name);
+        let output = microc(&["-O2", &src_filepath], &program);
+        assert!(
+            program.exists(),
+            "'{name}' did not compile:\n{}",
+            stderr(&output)
+        );
+
+        let run = Command::new(&program).output().unwrap();
+        let expected = std::fs::read_to_string(format!("{OUTPUT_TESTS}/{name}.expected_output"))
+            .expect("Could not read expected output file.");
+        assert_eq!(
+            expected,
+            String::from_utf8(run.stdout).unwrap(),
+            "'{name}' at -O2"
+        );
+    }
+}
+
+#[test]
+fn print_passes() {
+    let dir = TempDir::new("print_passes");
+    let src_filepath = format!("{OUTPUT_TESTS}/fizz_buzz.c");
+
+    let output = microc(
+        &["-c", "-O2", "--print-passes", &src_filepath],
+        &dir.join("O2.o"),
+    );
+    let passes = stderr(&output);
+    assert!(
+        passes.starts_with("Pass pipeline: default<O2>\n"),
+        "{passes}"
+    );
+    assert!(passes.contains("Running pass: InstCombinePass"), "{passes}");
+
+    let output = microc(&["-c", "--print-passes", &src_filepath], &dir.join("O0.o"));
+    let passes = stderr(&output);
+    assert!(
+        passes.starts_with("Pass pipeline: default<O0>\n"),
+        "{passes}"
+    );
+    assert!(!passes.contains("InstCombinePass"), "{passes}");
+
+    let output = microc(&["-c", "-O2", &src_filepath], &dir.join("quiet.o"));
+    assert!(!stderr(&output).contains("Pass pipeline"));
+}
+
+#[test]
+fn warning_flags() {
+    let dir = TempDir::new("warning_flags");
+    let src_filepath = format!("{OUTPUT_TESTS}/warnings.c");
+    let unreachable = "warning[-Wunreachable-code]: Unreachable code";
+
+    let program = dir.join("default");
+    let output = microc(&[&src_filepath], &program);
+    assert_eq!(stderr(&output).matches(unreachable).count(), 2);
+    assert!(program.exists());
+
+    let program = dir.join("disabled");
+    let output = microc(&["-Wno-unreachable-code", &src_filepath], &program);
+    assert_eq!(stderr(&output), "");
+    assert!(program.exists());
+
+    let program = dir.join("as_errors");
+    let output = microc(&["-Werror", &src_filepath], &program);
+    let errors = stderr(&output);
+    assert!(!errors.contains(unreachable), "{errors}");
+    assert_eq!(
+        errors
+            .matches("error[-Werror=unreachable-code]: Unreachable code")
+            .count(),
+        2,
+        "{errors}"
+    );
+    assert!(!program.exists());
+
+    let output = microc(&["-Wbogus", &src_filepath], &dir.join("unknown"));
+    assert!(stderr(&output).contains("Unknown warning option '-Wbogus'"));
+
+    // the uninitialized check is conservative, so it only runs with '-Wall'
+    let src_filepath = "_c_test_files/should_succeed/assigned_after_goto.c";
+    let program = dir.join("goto_default");
+    let output = microc(&["-Werror", src_filepath], &program);
+    assert_eq!(stderr(&output), "");
+    assert!(program.exists());
+
+    let program = dir.join("goto_all");
+    let output = microc(&["-Wall", "-Werror", src_filepath], &program);
+    assert!(stderr(&output).contains("error[-Werror=uninitialized]"));
+    assert!(!program.exists());
+}
+
+#[test]
+fn temp_objects() {
+    let dir = TempDir::new("temp_objects");
+
+    // an object of the user beside the program is neither replaced nor removed
+    let users_object = dir.join("fizz_buzz.o");
+    std::fs::write(&users_object, "not a temp file").unwrap();
+    let program = dir.join("fizz_buzz");
+    let output = microc(&[&format!("{OUTPUT_TESTS}/fizz_buzz.c")], &program);
+    assert!(program.exists(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(&users_object).unwrap(),
+        "not a temp file"
+    );
+
+    // source files with the same name in different directories
+    for (part, source) in [
+        (
+            "first",
+            "int second(void);\nint main() {\n    return second() - 7;\n}\n",
+        ),
+        ("second", "int second(void) {\n    return 7;\n}\n"),
+    ] {
+        std::fs::create_dir_all(dir.join(part)).unwrap();
+        std::fs::write(dir.join(part).join("part.c"), source).unwrap();
+    }
+    let program = dir.join("parts");
+    let first = dir.join("first").join("part.c");
+    let second = dir.join("second").join("part.c");
+    let output = microc(
+        &[first.to_str().unwrap(), second.to_str().unwrap()],
+        &program,
+    );
+    assert!(program.exists(), "{}", stderr(&output));
+    let run = Command::new(&program).status().unwrap();
+    assert_eq!(run.code(), Some(0));
+}